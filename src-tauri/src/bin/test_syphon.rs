@@ -31,23 +31,28 @@ fn main() -> Result<()> {
     mpv.set_property("vo", "libmpv").expect("vo の設定に失敗");
     mpv.set_property("cache", true).expect("cache の設定に失敗");
     mpv.set_property("cache-secs", 10i64).expect("cache-secs の設定に失敗");
-    // 注意: loadfile は Syphon スレッドで RenderContext 作成後に実行する
+    // 注意: loadfile は Syphon 出力に接続（RenderContext 作成）した後に実行する
 
     let mpv_handle = mpv.ctx.as_ptr();
 
     // Syphon スレッドを起動
     log::info!("Syphon 出力を起動します...");
 
+    // イベントディスパッチャを起動（解像度の検知とスタンバイ表示の切り替えに必要）
+    let events = app_lib::events::EventBus::new();
+    app_lib::events::spawn(mpv_handle, events.clone(), None, |_| {})?;
+
     // テストプログラムなので app_handle は None
     let handle = app_lib::output::syphon::spawn(
-        mpv_handle,
         server_name,
-        url,
         width,
         height,
         None, // プレビュー無効
-        std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        events,
+        app_lib::output::OutputRegistry::new(),
     )?;
+    handle.attach(mpv_handle, std::sync::Arc::new(app_lib::output::OutputStats::default()))?;
+    mpv.command("loadfile", &[url, "replace"]).expect("loadfile に失敗");
 
     log::info!("Syphon スレッドが起動しました");
     log::info!("TouchDesigner や VDMX で Syphon サーバー '{}' を探してください", server_name);
@@ -57,7 +62,8 @@ fn main() -> Result<()> {
     // mpv インスタンスを保持し続ける必要がある
     std::thread::park();
 
-    // クリーンアップ
+    // クリーンアップ（mpv より先に切り離す）
+    handle.detach();
     handle.stop();
     drop(mpv); // mpv を明示的に破棄
     log::info!("Syphon 出力を停止しました");
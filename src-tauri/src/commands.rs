@@ -1,4 +1,25 @@
-use crate::player::{PlayerState, PlayStatus};
+use crate::error::AppError;
+use crate::output::autocrop::{AutoCropSettings, CropStatus};
+use crate::output::color::ColorAdjust;
+use crate::output::key::Key;
+use crate::output::overlay::OverlayLayer;
+use crate::output::shader::ShaderEffect;
+use crate::output::standby::StandbySource;
+use crate::output::transform::Transform;
+use crate::player::audio::{AudioOutputSettings, ChannelRouting, DeviceAudio};
+use crate::player::audio_settings::{AudioDeviceStatus, DevicePolicy};
+use crate::player::beat_sync::{BeatSyncSettings, BeatSyncStatus};
+use crate::player::chase::{ChaseSettings, ChaseStatus};
+use crate::player::cues::Cue;
+use crate::player::looping::{Chapter, LoopMode, LoopPoint};
+use crate::player::meter::{MeterLevels, MeterSettings};
+use crate::player::net_sync::{NetSyncSettings, NetSyncStatus};
+use crate::player::seek::SeekRequest;
+use crate::player::subtitles::{SubtitleSettings, SubtitleTrack};
+use crate::player::tracks::{Track, TrackKind, TrackPreferences};
+use crate::player::volume::{FadeCurve, VolumeLevel, VolumeSettings};
+use crate::player::{PlayerState, PlayStatus, StatusKind};
+use crate::timecode;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
@@ -13,11 +34,16 @@ pub struct PlayRequest {
 /// フロントエンドに返すステータス
 #[derive(Debug, Serialize, Clone)]
 pub struct StatusResponse {
-    pub status: String,       // "idle" | "loading" | "playing" | "paused" | "error"
+    pub status: StatusKind,
     pub url: Option<String>,
-    pub error: Option<String>,
+    /// status = "error" の場合のエラー（code で分類を判別できる）
+    pub error: Option<AppError>,
+    /// status = "buffering" の場合のキャッシュ充填率（0–100）
+    pub buffering: Option<u8>,
     pub spout_active: bool,
     pub syphon_active: bool,
+    /// Syphon 出力の黒帯検出状況（検出した矩形と適用中の矩形）
+    pub crop: Option<CropStatus>,
 }
 
 /// オーディオデバイス情報
@@ -34,51 +60,57 @@ pub struct AudioDevice {
 pub async fn play(
     request: PlayRequest,
     state: State<'_, PlayerState>,
-) -> Result<StatusResponse, String> {
+) -> Result<StatusResponse, AppError> {
     log::info!("play command: url={}", request.url);
 
     state
         .play(&request.url, request.quality.as_deref())
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::from)?;
 
     Ok(StatusResponse {
-        status: "loading".to_string(),
+        status: StatusKind::Loading,
         url: Some(request.url),
         error: None,
+        buffering: None,
         spout_active: state.is_output_active(),
         syphon_active: state.is_output_active(),
+        crop: state.crop_status("syphon").ok(),
     })
 }
 
 /// 再生を停止し、Spout/Syphon 出力をクリアする
 #[tauri::command]
-pub async fn stop(state: State<'_, PlayerState>) -> Result<StatusResponse, String> {
+pub async fn stop(state: State<'_, PlayerState>) -> Result<StatusResponse, AppError> {
     log::info!("stop command");
 
-    state.stop().await.map_err(|e| e.to_string())?;
+    state.stop().await.map_err(AppError::from)?;
 
     Ok(StatusResponse {
-        status: "idle".to_string(),
+        status: StatusKind::Idle,
         url: None,
         error: None,
-        spout_active: false,
-        syphon_active: false,
+        buffering: None,
+        spout_active: state.is_output_active(),
+        syphon_active: state.is_output_active(),
+        crop: state.crop_status("syphon").ok(),
     })
 }
 
 /// 一時停止 / 再開トグル
 #[tauri::command]
-pub async fn pause(state: State<'_, PlayerState>) -> Result<StatusResponse, String> {
-    let paused = state.toggle_pause().await.map_err(|e| e.to_string())?;
+pub async fn pause(state: State<'_, PlayerState>) -> Result<StatusResponse, AppError> {
+    let paused = state.toggle_pause().await.map_err(AppError::from)?;
 
-    let status_str = if paused { "paused" } else { "playing" };
+    let status = if paused { StatusKind::Paused } else { StatusKind::Playing };
     Ok(StatusResponse {
-        status: status_str.to_string(),
+        status,
         url: state.current_url(),
         error: None,
+        buffering: None,
         spout_active: state.is_output_active(),
         syphon_active: state.is_output_active(),
+        crop: state.crop_status("syphon").ok(),
     })
 }
 
@@ -87,21 +119,19 @@ pub async fn pause(state: State<'_, PlayerState>) -> Result<StatusResponse, Stri
 pub fn get_status(state: State<'_, PlayerState>) -> StatusResponse {
     let play_status = state.status();
     StatusResponse {
-        status: match play_status {
-            PlayStatus::Idle => "idle",
-            PlayStatus::Loading => "loading",
-            PlayStatus::Playing => "playing",
-            PlayStatus::Paused => "paused",
-            PlayStatus::Error(_) => "error",
-        }
-        .to_string(),
+        status: play_status.kind(),
         url: state.current_url(),
-        error: match play_status {
-            PlayStatus::Error(e) => Some(e),
+        error: match &play_status {
+            PlayStatus::Error(e) => Some(AppError::from(e)),
+            _ => None,
+        },
+        buffering: match play_status {
+            PlayStatus::Buffering(percent) => Some(percent),
             _ => None,
         },
         spout_active: state.is_output_active(),
         syphon_active: state.is_output_active(),
+        crop: state.crop_status("syphon").ok(),
     }
 }
 
@@ -121,80 +151,598 @@ pub fn get_audio_devices(state: State<'_, PlayerState>) -> Vec<AudioDevice> {
 pub async fn set_audio_device(
     device_id: String,
     state: State<'_, PlayerState>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     state
         .set_audio_device(&device_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
+}
+
+/// JACK / PipeWire に見せる出力の設定（クライアント名・チャンネル数）
+/// 出力先は set_audio_device で "jack"・"jack/<クライアント名>"・"pipewire/<ノード名>" を選ぶ
+#[tauri::command]
+pub fn set_audio_output_settings(
+    settings: AudioOutputSettings,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state.set_audio_output_settings(settings).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn get_audio_output_settings(state: State<'_, PlayerState>) -> Result<AudioOutputSettings, AppError> {
+    state.audio_output_settings().map_err(AppError::from)
+}
+
+/// 優先デバイスが抜けたときの動作 ("fallback" / "pause" / "retry")
+#[tauri::command]
+pub fn set_audio_device_policy(policy: DevicePolicy, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.set_audio_device_policy(policy).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn get_audio_device_status(state: State<'_, PlayerState>) -> Result<AudioDeviceStatus, AppError> {
+    state.audio_device_status().map_err(AppError::from)
+}
+
+/// 出力中のデバイスの音声の遅延（秒）。デバイスごとに保存される
+#[tauri::command]
+pub fn set_audio_delay(seconds: f64, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.set_audio_delay(seconds).map_err(AppError::from)
+}
+
+/// 出力中のデバイスのチャンネルの割り当て ("stereo" / "mono" / "swap" / "channels34")
+#[tauri::command]
+pub fn set_channel_routing(routing: ChannelRouting, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.set_channel_routing(routing).map_err(AppError::from)
 }
 
-/// ボリューム設定 (0–100)
 #[tauri::command]
-pub async fn set_volume(volume: u8, state: State<'_, PlayerState>) -> Result<(), String> {
+pub fn get_device_audio(state: State<'_, PlayerState>) -> Result<DeviceAudio, AppError> {
+    state.device_audio().map_err(AppError::from)
+}
+
+/// ボリューム設定 (%。0–音量の上限。100 を超えると増幅する)
+#[tauri::command]
+pub async fn set_volume(volume: f64, state: State<'_, PlayerState>) -> Result<(), AppError> {
     state
         .set_volume(volume)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
+}
+
+/// ボリューム設定 (dB。0 dB が原音のまま)
+#[tauri::command]
+pub async fn set_volume_db(db: f64, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.set_volume_db(db).await.map_err(AppError::from)
+}
+
+/// 音量を duration_ms かけて volume (%) まで変える
+#[tauri::command]
+pub fn fade_volume(
+    volume: f64,
+    duration_ms: u64,
+    curve: Option<FadeCurve>,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state.fade_volume(volume, duration_ms, curve).map_err(AppError::from)
+}
+
+/// 音量を duration_ms かけて db まで変える
+#[tauri::command]
+pub fn fade_volume_db(
+    db: f64,
+    duration_ms: u64,
+    curve: Option<FadeCurve>,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state.fade_volume_db(db, duration_ms, curve).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn get_volume(state: State<'_, PlayerState>) -> Result<VolumeLevel, AppError> {
+    state.volume_level().map_err(AppError::from)
+}
+
+/// 音量の上限と、再生・停止時のフェード
+#[tauri::command]
+pub fn set_volume_settings(settings: VolumeSettings, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.set_volume_settings(settings).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn get_volume_settings(state: State<'_, PlayerState>) -> Result<VolumeSettings, AppError> {
+    state.volume_settings().map_err(AppError::from)
 }
 
 /// ミュート設定
 #[tauri::command]
-pub async fn set_mute(mute: bool, state: State<'_, PlayerState>) -> Result<(), String> {
-    state.set_mute(mute).await.map_err(|e| e.to_string())
+pub async fn set_mute(mute: bool, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.set_mute(mute).await.map_err(AppError::from)
 }
 
 /// ミュート状態を取得
 #[tauri::command]
-pub fn get_mute(state: State<'_, PlayerState>) -> Result<bool, String> {
-    state.get_mute().map_err(|e| e.to_string())
+pub fn get_mute(state: State<'_, PlayerState>) -> Result<bool, AppError> {
+    state.get_mute().map_err(AppError::from)
+}
+
+// ─── 音声メーター ───────────────────────────────────────────────────────────
+
+/// メーター（チャンネルごとの RMS / ピーク・LUFS・帯域ごとのレベル）の設定
+/// 有効にすると測定値を `mpv-event`（type: audio-meter）と OSC で送る
+#[tauri::command]
+pub fn set_meter_settings(settings: MeterSettings, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.set_meter_settings(settings).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn get_meter_settings(state: State<'_, PlayerState>) -> Result<MeterSettings, AppError> {
+    state.meter_settings().map_err(AppError::from)
+}
+
+/// メーターの最新の測定値を取得する
+#[tauri::command]
+pub fn get_meter_levels(state: State<'_, PlayerState>) -> Result<MeterLevels, AppError> {
+    state.meter_levels().map_err(AppError::from)
 }
 
 // ─── プレイヤー制御の拡張機能 ─────────────────────────────────────────────
 
-/// ループ再生を設定
+/// ループ再生を設定（オンはファイル全体のループ）
 #[tauri::command]
-pub async fn set_loop(enabled: bool, state: State<'_, PlayerState>) -> Result<(), String> {
-    state.set_loop(enabled).await.map_err(|e| e.to_string())
+pub async fn set_loop(enabled: bool, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.set_loop(enabled).await.map_err(AppError::from)
 }
 
-/// ループ再生の状態を取得
+/// いずれかのループ再生が有効かを取得
 #[tauri::command]
-pub fn get_loop(state: State<'_, PlayerState>) -> Result<bool, String> {
-    state.get_loop().map_err(|e| e.to_string())
+pub fn get_loop(state: State<'_, PlayerState>) -> Result<bool, AppError> {
+    state.get_loop().map_err(AppError::from)
+}
+
+/// ループ再生（なし / ファイル全体 / A-B 区間 / チャプター）を設定する
+#[tauri::command]
+pub fn set_loop_mode(mode: LoopMode, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.set_loop_mode(mode).map_err(AppError::from)
+}
+
+/// ループ再生の設定を取得する
+#[tauri::command]
+pub fn get_loop_mode(state: State<'_, PlayerState>) -> Result<LoopMode, AppError> {
+    state.loop_mode().map_err(AppError::from)
+}
+
+/// 現在の再生位置を A-B ループの A 点（"a"）または B 点（"b"）にする
+/// 設定後のループを返す
+#[tauri::command]
+pub fn set_loop_point(point: LoopPoint, state: State<'_, PlayerState>) -> Result<LoopMode, AppError> {
+    state.set_loop_point(point).map_err(AppError::from)
+}
+
+/// チャプターの一覧を取得する（YouTube のチャプター）
+#[tauri::command]
+pub fn get_chapters(state: State<'_, PlayerState>) -> Result<Vec<Chapter>, AppError> {
+    state.chapters().map_err(AppError::from)
+}
+
+/// チャプターの先頭へ移動する
+#[tauri::command]
+pub fn jump_to_chapter(index: i64, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.jump_to_chapter(index).map_err(AppError::from)
 }
 
 /// シーク（秒単位）
 #[tauri::command]
-pub async fn seek(seconds: f64, state: State<'_, PlayerState>) -> Result<(), String> {
-    state.seek(seconds).await.map_err(|e| e.to_string())
+pub async fn seek(seconds: f64, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.seek(seconds).await.map_err(AppError::from)
+}
+
+/// 基準（"absolute" / "relative" / "percent"）と精度（exact）を指定してシーク
+#[tauri::command]
+pub fn seek_with_mode(seek: SeekRequest, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.seek_with(seek).map_err(AppError::from)
+}
+
+/// 1 フレーム進める（移動後は一時停止）
+#[tauri::command]
+pub fn frame_step(state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.frame_step(true).map_err(AppError::from)
+}
+
+/// 1 フレーム戻す（移動後は一時停止）
+#[tauri::command]
+pub fn frame_back_step(state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.frame_step(false).map_err(AppError::from)
+}
+
+/// フレーム番号（0 始まり）の位置へ正確に移動する。fps は映像トラックの情報から取る
+/// 移動先の秒数を返す
+#[tauri::command]
+pub fn seek_to_frame(frame: u64, state: State<'_, PlayerState>) -> Result<f64, AppError> {
+    state.seek_to_frame(frame).map_err(AppError::from)
 }
 
 /// 再生位置を取得（秒）
 #[tauri::command]
-pub fn get_time_pos(state: State<'_, PlayerState>) -> Result<f64, String> {
-    state.get_time_pos().map_err(|e| e.to_string())
+pub fn get_time_pos(state: State<'_, PlayerState>) -> Result<f64, AppError> {
+    state.get_time_pos().map_err(AppError::from)
 }
 
 /// 総再生時間を取得（秒）
 #[tauri::command]
-pub fn get_duration(state: State<'_, PlayerState>) -> Result<f64, String> {
-    state.get_duration().map_err(|e| e.to_string())
+pub fn get_duration(state: State<'_, PlayerState>) -> Result<f64, AppError> {
+    state.get_duration().map_err(AppError::from)
 }
 
 /// 再生速度を設定（0.25 〜 4.0）
 #[tauri::command]
-pub async fn set_speed(speed: f64, state: State<'_, PlayerState>) -> Result<(), String> {
-    state.set_speed(speed).await.map_err(|e| e.to_string())
+pub async fn set_speed(speed: f64, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.set_speed(speed).await.map_err(AppError::from)
 }
 
 /// 再生速度を取得
 #[tauri::command]
-pub fn get_speed(state: State<'_, PlayerState>) -> Result<f64, String> {
-    state.get_speed().map_err(|e| e.to_string())
+pub fn get_speed(state: State<'_, PlayerState>) -> Result<f64, AppError> {
+    state.get_speed().map_err(AppError::from)
 }
 
 /// 動画タイトルを取得
 #[tauri::command]
-pub fn get_media_title(state: State<'_, PlayerState>) -> Result<String, String> {
-    state.get_media_title().map_err(|e| e.to_string())
+pub fn get_media_title(state: State<'_, PlayerState>) -> Result<String, AppError> {
+    state.get_media_title().map_err(AppError::from)
+}
+
+// ─── タイムコード ───────────────────────────────────────────────────────────
+
+/// 外部タイムコード（MTC / LTC）への追従を開始する
+#[tauri::command]
+pub fn start_chase(settings: ChaseSettings, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.start_chase(settings).map_err(AppError::from)
+}
+
+/// タイムコードへの追従を止める
+#[tauri::command]
+pub fn stop_chase(state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.stop_chase().map_err(AppError::from)
+}
+
+/// チェイスの状態（waiting / stopped / seeking / chasing / locked）と差を取得する
+#[tauri::command]
+pub fn get_chase_status(state: State<'_, PlayerState>) -> Result<ChaseStatus, AppError> {
+    state.chase_status().map_err(AppError::from)
+}
+
+/// MTC を受信できる MIDI 入力ポートの一覧
+#[tauri::command]
+pub fn list_midi_inputs() -> Result<Vec<String>, AppError> {
+    timecode::mtc::list_ports().map_err(AppError::from)
+}
+
+/// LTC を受信できるオーディオ入力デバイスの一覧
+#[tauri::command]
+pub fn list_audio_inputs() -> Result<Vec<String>, AppError> {
+    timecode::ltc::list_inputs().map_err(AppError::from)
+}
+
+// ─── ビート同期 ─────────────────────────────────────────────────────────────
+
+/// ネットワークのテンポ（UDP テンポブロードキャスト）に合わせて再生速度の自動調整を開始する
+/// クリップの BPM と位相を合わせる周期（拍）を指定する
+#[tauri::command]
+pub fn start_beat_sync(settings: BeatSyncSettings, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.start_beat_sync(settings).map_err(AppError::from)
+}
+
+/// ビート同期を止める（再生速度は 1.0 に戻る）
+#[tauri::command]
+pub fn stop_beat_sync(state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.stop_beat_sync().map_err(AppError::from)
+}
+
+/// ビート同期の状態を取得する
+#[tauri::command]
+pub fn get_beat_sync_status(state: State<'_, PlayerState>) -> Result<BeatSyncStatus, AppError> {
+    state.beat_sync_status().map_err(AppError::from)
+}
+
+// ─── 複数台の同期 ───────────────────────────────────────────────────────────
+
+/// LAN 上の複数台で同期再生する（マスターは再生位置を送り、フォロワーはそれに追従する）
+#[tauri::command]
+pub fn start_net_sync(settings: NetSyncSettings, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.start_net_sync(settings).map_err(AppError::from)
+}
+
+/// 同期を止める（フォロワーは再生速度が 1.0 に戻る）
+#[tauri::command]
+pub fn stop_net_sync(state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.stop_net_sync().map_err(AppError::from)
+}
+
+/// 同期の状態とマスターとの差（sync_error）を取得する
+#[tauri::command]
+pub fn get_net_sync_status(state: State<'_, PlayerState>) -> Result<NetSyncStatus, AppError> {
+    state.net_sync_status().map_err(AppError::from)
+}
+
+// ─── キューポイント ─────────────────────────────────────────────────────────
+
+/// クリップ（URL）のキューポイントを置き換えて保存する
+/// アクションは pause / jump / switch-clip / send-osc / fade-volume
+#[tauri::command]
+pub fn set_cues(url: String, cues: Vec<Cue>, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.set_cues(&url, cues).map_err(AppError::from)
+}
+
+/// クリップ（URL）のキューポイントを取得する
+#[tauri::command]
+pub fn get_cues(url: String, state: State<'_, PlayerState>) -> Result<Vec<Cue>, AppError> {
+    state.cues(&url).map_err(AppError::from)
+}
+
+// ─── トラック ───────────────────────────────────────────────────────────────
+
+/// 音声・映像・字幕トラックの一覧を取得する
+#[tauri::command]
+pub fn list_tracks(state: State<'_, PlayerState>) -> Result<Vec<Track>, AppError> {
+    state.tracks().map_err(AppError::from)
+}
+
+/// トラックを選択する（kind は "audio" / "video" / "sub"。id を省略すると選択を外す）
+/// 音声・字幕はトラックの言語を優先言語として覚える
+#[tauri::command]
+pub fn select_track(
+    kind: TrackKind,
+    id: Option<i64>,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state.select_track(kind, id).map_err(AppError::from)
+}
+
+/// 音声の優先言語を設定する
+#[tauri::command]
+pub fn set_track_preferences(
+    preferences: TrackPreferences,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state
+        .set_track_preferences(preferences)
+        .map_err(AppError::from)
+}
+
+/// 音声の優先言語を取得する
+#[tauri::command]
+pub fn get_track_preferences(state: State<'_, PlayerState>) -> Result<TrackPreferences, AppError> {
+    state.track_preferences().map_err(AppError::from)
+}
+
+// ─── 字幕 ───────────────────────────────────────────────────────────────────
+
+/// 字幕トラックの一覧を取得する（手動字幕と自動生成字幕）
+#[tauri::command]
+pub fn get_subtitle_tracks(state: State<'_, PlayerState>) -> Result<Vec<SubtitleTrack>, AppError> {
+    state.subtitle_tracks().map_err(AppError::from)
+}
+
+/// 字幕トラックを選択する（id を省略すると字幕なし）
+#[tauri::command]
+pub fn select_subtitle(id: Option<i64>, state: State<'_, PlayerState>) -> Result<(), AppError> {
+    state.select_subtitle(id).map_err(AppError::from)
+}
+
+/// 字幕の表示先（焼き込み / プレビューのみ / なし）・言語・見た目を設定する
+/// 取得する言語の変更は次の読み込みから有効になる
+#[tauri::command]
+pub fn set_subtitle_settings(
+    settings: SubtitleSettings,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state
+        .set_subtitle_settings(settings)
+        .map_err(AppError::from)
+}
+
+/// 字幕の設定を取得する
+#[tauri::command]
+pub fn get_subtitle_settings(state: State<'_, PlayerState>) -> Result<SubtitleSettings, AppError> {
+    state.subtitle_settings().map_err(AppError::from)
+}
+
+// ─── 出力設定 ───────────────────────────────────────────────────────────────
+
+/// 出力を有効・無効にする（無効にするとサーバーを停止する）
+/// 出力名はこの環境で映像を送れるもの（macOS の "syphon"）のみ受け付ける
+#[tauri::command]
+pub fn set_output_enabled(
+    output: String,
+    enabled: bool,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state
+        .set_output_enabled(&output, enabled)
+        .map_err(AppError::from)
+}
+
+/// 出力が有効かを取得する
+#[tauri::command]
+pub fn get_output_enabled(
+    output: String,
+    state: State<'_, PlayerState>,
+) -> Result<bool, AppError> {
+    state.output_enabled(&output).map_err(AppError::from)
+}
+
+/// 出力（"syphon"）のスタンバイ映像を設定する
+/// 読み込み中・エラー時・停止時に本編の代わりに送られる
+#[tauri::command]
+pub fn set_standby_source(
+    output: String,
+    source: StandbySource,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state
+        .set_standby_source(&output, source)
+        .map_err(AppError::from)
+}
+
+/// 出力のスタンバイ映像の設定を取得する
+#[tauri::command]
+pub fn get_standby_source(
+    output: String,
+    state: State<'_, PlayerState>,
+) -> Result<StandbySource, AppError> {
+    state.standby_source(&output).map_err(AppError::from)
+}
+
+/// 出力の映像変形（切り出し・パン/ズーム・反転・回転・スケーリング）を設定する
+/// 再生中の出力にもすぐ反映される
+#[tauri::command]
+pub fn set_output_transform(
+    output: String,
+    transform: Transform,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state
+        .set_output_transform(&output, transform)
+        .map_err(AppError::from)
+}
+
+/// 出力の映像変形の設定を取得する
+#[tauri::command]
+pub fn get_output_transform(
+    output: String,
+    state: State<'_, PlayerState>,
+) -> Result<Transform, AppError> {
+    state.output_transform(&output).map_err(AppError::from)
+}
+
+/// 出力の色調整（明るさ・コントラスト・彩度・ガンマ・色相）と `.cube` LUT を設定する
+/// 再生中の出力にもすぐ反映される
+#[tauri::command]
+pub fn set_output_color(
+    output: String,
+    color: ColorAdjust,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state
+        .set_output_color(&output, color)
+        .map_err(AppError::from)
+}
+
+/// 出力の色調整の設定を取得する
+#[tauri::command]
+pub fn get_output_color(
+    output: String,
+    state: State<'_, PlayerState>,
+) -> Result<ColorAdjust, AppError> {
+    state.output_color(&output).map_err(AppError::from)
+}
+
+/// 出力に適用するユーザー GLSL シェーダを設定する（配列の順に適用）
+/// ファイルを保存し直すと再生中でも自動で読み込み直される
+#[tauri::command]
+pub fn set_output_shaders(
+    output: String,
+    shaders: Vec<ShaderEffect>,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state
+        .set_output_shaders(&output, shaders)
+        .map_err(AppError::from)
+}
+
+/// 出力のユーザーシェーダの設定を取得する
+#[tauri::command]
+pub fn get_output_shaders(
+    output: String,
+    state: State<'_, PlayerState>,
+) -> Result<Vec<ShaderEffect>, AppError> {
+    state.output_shaders(&output).map_err(AppError::from)
+}
+
+/// 出力のキーイング（クロマキー / ルミナンスキー）を設定する
+/// 透明部分はアルファとして Syphon / Spout にそのまま送られる
+#[tauri::command]
+pub fn set_output_key(
+    output: String,
+    key: Key,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state
+        .set_output_key(&output, key)
+        .map_err(AppError::from)
+}
+
+/// 出力のキーイングの設定を取得する
+#[tauri::command]
+pub fn get_output_key(
+    output: String,
+    state: State<'_, PlayerState>,
+) -> Result<Key, AppError> {
+    state.output_key(&output).map_err(AppError::from)
+}
+
+/// 出力にオーバーレイ（文字・画像）のレイヤーを追加する。同じ id があれば置き換える
+/// 文字には {title} / {elapsed} / {remaining} / {duration} / {clock} を埋め込める
+#[tauri::command]
+pub fn set_overlay(
+    output: String,
+    layer: OverlayLayer,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state.set_overlay(&output, layer).map_err(AppError::from)
+}
+
+/// 出力からオーバーレイのレイヤーを取り除く
+#[tauri::command]
+pub fn remove_overlay(
+    output: String,
+    id: String,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state.remove_overlay(&output, &id).map_err(AppError::from)
+}
+
+/// オーバーレイのレイヤーをフェードイン / フェードアウトする
+#[tauri::command]
+pub fn set_overlay_visible(
+    output: String,
+    id: String,
+    visible: bool,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state
+        .set_overlay_visible(&output, &id, visible)
+        .map_err(AppError::from)
+}
+
+/// 出力のオーバーレイのレイヤー一覧を取得する
+#[tauri::command]
+pub fn get_overlays(
+    output: String,
+    state: State<'_, PlayerState>,
+) -> Result<Vec<OverlayLayer>, AppError> {
+    state.overlays(&output).map_err(AppError::from)
+}
+
+/// 出力の黒帯自動切り出しを設定する
+/// mode: "off" / "auto" / "locked"（rect 省略時は現在の検出結果で固定）/ "manual"（rect を指定）
+#[tauri::command]
+pub fn set_auto_crop(
+    output: String,
+    autocrop: AutoCropSettings,
+    state: State<'_, PlayerState>,
+) -> Result<(), AppError> {
+    state
+        .set_auto_crop(&output, autocrop)
+        .map_err(AppError::from)
+}
+
+/// 出力の黒帯自動切り出しの設定を取得する
+#[tauri::command]
+pub fn get_auto_crop(
+    output: String,
+    state: State<'_, PlayerState>,
+) -> Result<AutoCropSettings, AppError> {
+    state.auto_crop(&output).map_err(AppError::from)
 }
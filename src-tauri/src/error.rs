@@ -0,0 +1,123 @@
+/// IPC 層のエラー型
+///
+/// フロントエンドやリモートクライアントが機械的に判別できるよう、
+/// `{ "code": "not_playing", "message": "...", "detail": "..." }` の形でシリアライズする。
+/// `message` は既定の日本語メッセージで、ローカライズする場合は `code` をキーにする。
+///
+/// PlayerState 内部では anyhow::Error に包んで返し、コマンド層で `From<anyhow::Error>` により
+/// 取り出す（包まれていないエラーは `Internal` になる）。
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::player::{ErrorCause, PlayError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// 再生中でないと実行できない操作
+    NotPlaying,
+    /// URL の形式が不正
+    InvalidUrl(String),
+    /// yt-dlp による URL 解決の失敗
+    ExtractorFailed(String),
+    /// ストリームの取得に失敗
+    NetworkFailed(String),
+    /// デコードに失敗 / 未対応フォーマット
+    DecoderFailed(String),
+    /// 出力（Syphon / Spout / 音声出力）が利用できない
+    OutputUnavailable(String),
+    /// 指定したデバイス（オーディオ出力・MIDI 入力）が存在しない
+    DeviceNotFound(String),
+    /// 引数が範囲外・不正
+    InvalidArgument(String),
+    /// 上記以外の内部エラー
+    Internal(String),
+}
+
+impl AppError {
+    /// 機械可読なエラーコード
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotPlaying => "not_playing",
+            AppError::InvalidUrl(_) => "invalid_url",
+            AppError::ExtractorFailed(_) => "extractor_failed",
+            AppError::NetworkFailed(_) => "network_failed",
+            AppError::DecoderFailed(_) => "decoder_failed",
+            AppError::OutputUnavailable(_) => "output_unavailable",
+            AppError::DeviceNotFound(_) => "device_not_found",
+            AppError::InvalidArgument(_) => "invalid_argument",
+            AppError::Internal(_) => "internal",
+        }
+    }
+
+    /// 既定（日本語）のメッセージ
+    pub fn message(&self) -> &'static str {
+        match self {
+            AppError::NotPlaying => "再生中ではありません",
+            AppError::InvalidUrl(_) => "URL が正しくありません",
+            AppError::ExtractorFailed(_) => "yt-dlp で動画の URL を取得できませんでした",
+            AppError::NetworkFailed(_) => "ストリームを取得できませんでした",
+            AppError::DecoderFailed(_) => "動画をデコードできませんでした",
+            AppError::OutputUnavailable(_) => "出力を利用できません",
+            AppError::DeviceNotFound(_) => "デバイスが見つかりません",
+            AppError::InvalidArgument(_) => "引数が正しくありません",
+            AppError::Internal(_) => "内部エラーが発生しました",
+        }
+    }
+
+    /// 補足情報（原因となった値や下位のエラーメッセージ）
+    pub fn detail(&self) -> Option<&str> {
+        match self {
+            AppError::NotPlaying => None,
+            AppError::InvalidUrl(s)
+            | AppError::ExtractorFailed(s)
+            | AppError::NetworkFailed(s)
+            | AppError::DecoderFailed(s)
+            | AppError::OutputUnavailable(s)
+            | AppError::DeviceNotFound(s)
+            | AppError::InvalidArgument(s)
+            | AppError::Internal(s) => Some(s),
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.detail() {
+            Some(detail) => write!(f, "{}: {}", self.message(), detail),
+            None => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("AppError", 3)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", self.message())?;
+        s.serialize_field("detail", &self.detail())?;
+        s.end()
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast_ref::<AppError>() {
+            Some(app_error) => app_error.clone(),
+            None => AppError::Internal(e.to_string()),
+        }
+    }
+}
+
+impl From<&PlayError> for AppError {
+    fn from(e: &PlayError) -> Self {
+        let detail = e.message.clone();
+        match e.cause {
+            ErrorCause::Network => AppError::NetworkFailed(detail),
+            ErrorCause::Extractor => AppError::ExtractorFailed(detail),
+            ErrorCause::Decoder => AppError::DecoderFailed(detail),
+            ErrorCause::Output => AppError::OutputUnavailable(detail),
+        }
+    }
+}
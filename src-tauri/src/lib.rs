@@ -1,7 +1,13 @@
 mod commands;
+mod error;
 mod player;
+mod tempo;
+mod timecode;
 pub mod output;
 
+// output::syphon::spawn がイベント型を公開 API で使うため再公開する
+pub use player::events;
+
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -36,18 +42,88 @@ pub fn run() {
             commands::get_status,
             commands::get_audio_devices,
             commands::set_audio_device,
+            commands::set_audio_output_settings,
+            commands::get_audio_output_settings,
+            commands::set_audio_device_policy,
+            commands::get_audio_device_status,
+            commands::set_audio_delay,
+            commands::set_channel_routing,
+            commands::get_device_audio,
             commands::set_volume,
+            commands::set_volume_db,
+            commands::fade_volume,
+            commands::fade_volume_db,
+            commands::get_volume,
+            commands::set_volume_settings,
+            commands::get_volume_settings,
             commands::set_mute,
             commands::get_mute,
+            commands::set_meter_settings,
+            commands::get_meter_settings,
+            commands::get_meter_levels,
             commands::set_loop,
             commands::get_loop,
+            commands::set_loop_mode,
+            commands::get_loop_mode,
+            commands::set_loop_point,
+            commands::get_chapters,
+            commands::jump_to_chapter,
             commands::seek,
+            commands::seek_with_mode,
+            commands::frame_step,
+            commands::frame_back_step,
+            commands::seek_to_frame,
             commands::get_time_pos,
             commands::get_duration,
             commands::set_speed,
             commands::get_speed,
             commands::get_media_title,
+            commands::start_chase,
+            commands::stop_chase,
+            commands::get_chase_status,
+            commands::list_midi_inputs,
+            commands::list_audio_inputs,
+            commands::start_beat_sync,
+            commands::stop_beat_sync,
+            commands::get_beat_sync_status,
+            commands::start_net_sync,
+            commands::stop_net_sync,
+            commands::get_net_sync_status,
+            commands::set_cues,
+            commands::get_cues,
+            commands::list_tracks,
+            commands::select_track,
+            commands::set_track_preferences,
+            commands::get_track_preferences,
+            commands::get_subtitle_tracks,
+            commands::select_subtitle,
+            commands::set_subtitle_settings,
+            commands::get_subtitle_settings,
+            commands::set_output_enabled,
+            commands::get_output_enabled,
+            commands::set_standby_source,
+            commands::get_standby_source,
+            commands::set_output_transform,
+            commands::get_output_transform,
+            commands::set_output_color,
+            commands::get_output_color,
+            commands::set_output_shaders,
+            commands::get_output_shaders,
+            commands::set_output_key,
+            commands::get_output_key,
+            commands::set_overlay,
+            commands::remove_overlay,
+            commands::set_overlay_visible,
+            commands::get_overlays,
+            commands::set_auto_crop,
+            commands::get_auto_crop,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // 出力のサーバーはアプリの終了時にだけ停止する
+            if let tauri::RunEvent::Exit = event {
+                app.state::<player::PlayerState>().shutdown();
+            }
+        });
 }
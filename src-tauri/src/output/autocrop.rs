@@ -0,0 +1,394 @@
+/// レターボックス / ピラーボックス（黒帯）の自動検出と切り出し
+///
+/// ## 仕組み
+/// 1. 出力スレッドが数フレームごとに mpv の FBO を縮小して読み戻す（`CropSampler`）
+/// 2. `detect_bars()` が上下左右から輝度のある行・列を探し、映像部分の矩形を求める
+/// 3. `AutoCropper` が検出結果を見守り、同じ矩形が `settle_secs` 続いたら適用する
+///    （暗いシーンで一時的に小さく検出されても切り出しが揺れないようにする）
+///
+/// ## モード
+/// - `Off`: 検出しない（変形設定の crop をそのまま使う）
+/// - `Auto`: 検出して安定した矩形を適用する
+/// - `Locked`: 固定した矩形を使い続ける（検出は表示のためだけに続ける）
+/// - `Manual`: オペレーターが指定した矩形を使う（検出は表示のためだけに続ける）
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::output::transform::Rect;
+
+/// 検出結果の揺れとみなす差（正規化座標）
+const TOLERANCE: f32 = 0.01;
+/// これより小さい矩形は暗いシーンの誤検出とみなす
+const MIN_EXTENT: f32 = 0.25;
+
+/// 自動切り出しのモード
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CropMode {
+    #[default]
+    Off,
+    Auto,
+    Locked,
+    Manual,
+}
+
+/// 自動切り出しの設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoCropSettings {
+    pub mode: CropMode,
+    /// Locked / Manual で使う矩形
+    pub rect: Option<Rect>,
+    /// 黒とみなす輝度の上限（0–255）
+    pub threshold: u8,
+    /// 検出結果が安定してから適用するまでの秒数
+    pub settle_secs: f32,
+}
+
+impl Default for AutoCropSettings {
+    fn default() -> Self {
+        Self {
+            mode: CropMode::Off,
+            rect: None,
+            threshold: 24,
+            settle_secs: 2.0,
+        }
+    }
+}
+
+impl AutoCropSettings {
+    /// 設定値の範囲を確認する（Locked の rect は呼び出し側で補ってから確認する）
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=30.0).contains(&self.settle_secs) {
+            return Err(anyhow::anyhow!("settle_secs は 0–30 で指定してください: {}", self.settle_secs));
+        }
+        match (self.mode, &self.rect) {
+            (CropMode::Locked | CropMode::Manual, None) => {
+                Err(anyhow::anyhow!("{:?} では rect を指定してください", self.mode))
+            }
+            (_, Some(rect)) if !rect.is_inside_frame() => {
+                Err(anyhow::anyhow!("rect が映像の範囲外です: {:?}", rect))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 出力に適用する切り出し矩形（None = 変形設定の crop を使う）
+    pub fn effective_rect(&self, applied: Option<Rect>) -> Option<Rect> {
+        match self.mode {
+            CropMode::Off => None,
+            CropMode::Auto => applied,
+            CropMode::Locked | CropMode::Manual => self.rect,
+        }
+    }
+}
+
+/// get_status で返す検出状況
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CropStatus {
+    pub mode: CropMode,
+    /// 直近のフレームで検出した矩形
+    pub detected: Option<Rect>,
+    /// 安定した検出結果（Auto で適用される矩形）
+    pub applied: Option<Rect>,
+}
+
+// ─── 検出 ────────────────────────────────────────────────────────────────────
+
+/// RGB8（上の行から並ぶ）のフレームから黒帯を除いた映像部分の矩形を求める
+///
+/// 画面全体が暗い場合や、映像部分が小さすぎる場合は判定できないため None を返す。
+pub fn detect_bars(pixels: &[u8], width: u32, height: u32, threshold: u8) -> Option<Rect> {
+    let (w, h) = (width as usize, height as usize);
+    if w == 0 || h == 0 || pixels.len() < w * h * 3 {
+        return None;
+    }
+
+    let bright = |x: usize, y: usize| {
+        let i = (y * w + x) * 3;
+        let luma = (pixels[i] as u32 * 299 + pixels[i + 1] as u32 * 587 + pixels[i + 2] as u32 * 114) / 1000;
+        luma > threshold as u32
+    };
+    // ノイズや字幕の数ピクセルで帯と判定しないよう、一定割合が明るい行・列を映像とみなす
+    let row_is_image = |y: usize| (0..w).filter(|&x| bright(x, y)).count() >= (w / 50).max(1);
+
+    let top = (0..h).find(|&y| row_is_image(y))?;
+    let bottom = (0..h).rev().find(|&y| row_is_image(y))?;
+    let rows = bottom - top + 1;
+    let col_is_image = |x: usize| (top..=bottom).filter(|&y| bright(x, y)).count() >= (rows / 50).max(1);
+    let left = (0..w).find(|&x| col_is_image(x))?;
+    let right = (0..w).rev().find(|&x| col_is_image(x))?;
+
+    let rect = Rect {
+        x: left as f32 / width as f32,
+        y: top as f32 / height as f32,
+        width: (right - left + 1) as f32 / width as f32,
+        height: rows as f32 / height as f32,
+    };
+    if rect.width < MIN_EXTENT || rect.height < MIN_EXTENT {
+        return None;
+    }
+    Some(rect)
+}
+
+fn approx_eq(a: &Rect, b: &Rect) -> bool {
+    (a.x - b.x).abs() <= TOLERANCE
+        && (a.y - b.y).abs() <= TOLERANCE
+        && (a.width - b.width).abs() <= TOLERANCE
+        && (a.height - b.height).abs() <= TOLERANCE
+}
+
+// ─── 安定化 ──────────────────────────────────────────────────────────────────
+
+/// 検出結果が一定時間安定してから適用する
+#[derive(Debug, Default)]
+pub struct AutoCropper {
+    /// 直近の検出結果
+    detected: Option<Rect>,
+    /// 安定待ちの候補と、その検出が始まった時刻
+    candidate: Option<(Rect, Instant)>,
+    /// 適用中の矩形
+    applied: Option<Rect>,
+}
+
+impl AutoCropper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 新しい動画の読み込み時に呼ぶ
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn detected(&self) -> Option<Rect> {
+        self.detected
+    }
+
+    pub fn applied(&self) -> Option<Rect> {
+        self.applied
+    }
+
+    /// 検出結果を与える。適用する矩形が変わった場合は true を返す
+    pub fn on_sample(&mut self, detected: Option<Rect>, settle: Duration, now: Instant) -> bool {
+        // 判定できないフレーム（真っ暗など）は候補を変えない
+        let Some(rect) = detected else { return false };
+        self.detected = Some(rect);
+
+        match self.candidate {
+            Some((candidate, _)) if approx_eq(&candidate, &rect) => {}
+            _ => {
+                self.candidate = Some((rect, now));
+                return false;
+            }
+        }
+
+        let (candidate, since) = self.candidate.unwrap();
+        let settled = now.duration_since(since) >= settle;
+        let changed = match self.applied {
+            Some(applied) => !approx_eq(&applied, &candidate),
+            None => true,
+        };
+        if settled && changed {
+            log::info!("黒帯を検出しました: {:?}", candidate);
+            self.applied = Some(candidate);
+            return true;
+        }
+        false
+    }
+}
+
+// ─── 読み戻し ────────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "macos")]
+pub use gpu::CropSampler;
+
+#[cfg(target_os = "macos")]
+mod gpu {
+    use crate::output::gl_util::{Frame, RenderTarget};
+
+    /// 解析用の縮小解像度（比率は崩れるが、正規化座標で扱うため問題ない）
+    const SAMPLE_WIDTH: u32 = 192;
+    const SAMPLE_HEIGHT: u32 = 108;
+
+    /// フレームを縮小して CPU に読み戻す
+    pub struct CropSampler {
+        target: RenderTarget,
+        pixels: Vec<u8>,
+    }
+
+    impl Default for CropSampler {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl CropSampler {
+        pub fn new() -> Self {
+            Self {
+                target: RenderTarget::new(SAMPLE_WIDTH, SAMPLE_HEIGHT),
+                pixels: vec![0; (SAMPLE_WIDTH * SAMPLE_HEIGHT * 3) as usize],
+            }
+        }
+
+        /// 縮小した RGB8（上の行から並ぶ）を返す（戻り値: ピクセル, 幅, 高さ）
+        pub fn sample(&mut self, source: Frame) -> (&[u8], u32, u32) {
+            unsafe {
+                // 上下を反転してコピーし、読み戻した行が上から並ぶようにする
+                gl::BindFramebuffer(gl::READ_FRAMEBUFFER, source.fbo);
+                gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.target.fbo);
+                gl::BlitFramebuffer(
+                    0, 0, source.width as _, source.height as _,
+                    0, SAMPLE_HEIGHT as _, SAMPLE_WIDTH as _, 0,
+                    gl::COLOR_BUFFER_BIT,
+                    gl::LINEAR,
+                );
+                gl::BindFramebuffer(gl::FRAMEBUFFER, self.target.fbo);
+                gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+                gl::ReadPixels(
+                    0, 0, SAMPLE_WIDTH as i32, SAMPLE_HEIGHT as i32,
+                    gl::RGB, gl::UNSIGNED_BYTE,
+                    self.pixels.as_mut_ptr() as *mut _,
+                );
+                gl::PixelStorei(gl::PACK_ALIGNMENT, 4);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            }
+            (&self.pixels, SAMPLE_WIDTH, SAMPLE_HEIGHT)
+        }
+
+        pub fn destroy(mut self) {
+            self.target.delete();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const W: u32 = 100;
+    const H: u32 = 50;
+
+    /// 帯の輝度 bar の中に、輝度 image の矩形 (x0, y0)–(x1, y1)（右下は含まない）を置いたフレーム
+    fn frame(bar: u8, image: u8, (x0, y0, x1, y1): (u32, u32, u32, u32)) -> Vec<u8> {
+        let mut pixels = vec![bar; (W * H * 3) as usize];
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let i = ((y * W + x) * 3) as usize;
+                pixels[i..i + 3].fill(image);
+            }
+        }
+        pixels
+    }
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    fn assert_rect(actual: Option<Rect>, expected: Rect) {
+        let actual = actual.expect("矩形を検出できません");
+        assert!(approx_eq(&actual, &expected), "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn detects_letterbox_and_pillarbox() {
+        let letterbox = frame(0, 200, (0, 10, W, 40));
+        assert_rect(detect_bars(&letterbox, W, H, 24), rect(0.0, 0.2, 1.0, 0.6));
+
+        let pillarbox = frame(0, 200, (20, 0, 80, H));
+        assert_rect(detect_bars(&pillarbox, W, H, 24), rect(0.2, 0.0, 0.6, 1.0));
+    }
+
+    #[test]
+    fn threshold_decides_what_counts_as_black() {
+        // 輝度 20 の帯は閾値 24 では黒、閾値 16 では映像の一部
+        let grey_bars = frame(20, 200, (0, 10, W, 40));
+        assert_rect(detect_bars(&grey_bars, W, H, 24), rect(0.0, 0.2, 1.0, 0.6));
+        assert_rect(detect_bars(&grey_bars, W, H, 16), rect(0.0, 0.0, 1.0, 1.0));
+
+        // 閾値ちょうどの輝度は黒として扱う
+        let dim_image = frame(0, 24, (0, 10, W, 40));
+        assert_eq!(detect_bars(&dim_image, W, H, 24), None);
+        assert_rect(detect_bars(&dim_image, W, H, 23), rect(0.0, 0.2, 1.0, 0.6));
+    }
+
+    #[test]
+    fn sparse_bright_pixels_do_not_count_as_image() {
+        // 帯の中の 1 ピクセルのノイズ（幅の 1/50 未満）では帯を映像とみなさない
+        let mut pixels = frame(0, 200, (0, 10, W, 40));
+        pixels[((2 * W + 50) * 3) as usize..][..3].fill(255);
+        assert_rect(detect_bars(&pixels, W, H, 24), rect(0.0, 0.2, 1.0, 0.6));
+
+        // 幅の 1/50 以上が明るい行は映像とみなす
+        for x in 50..52 {
+            pixels[((2 * W + x) * 3) as usize..][..3].fill(255);
+        }
+        assert_rect(detect_bars(&pixels, W, H, 24), rect(0.0, 0.04, 1.0, 0.76));
+    }
+
+    #[test]
+    fn undecidable_frames_return_none() {
+        // 真っ暗なフレーム
+        assert_eq!(detect_bars(&frame(0, 0, (0, 0, W, H)), W, H, 24), None);
+        // 映像部分が MIN_EXTENT より小さい（暗いシーンの誤検出）
+        assert_eq!(detect_bars(&frame(0, 200, (40, 20, 60, 30)), W, H, 24), None);
+        // 大きさが合わないバッファ
+        assert_eq!(detect_bars(&[255; 30], W, H, 24), None);
+        assert_eq!(detect_bars(&[], 0, 0, 24), None);
+    }
+
+    #[test]
+    fn cropper_applies_after_settle() {
+        let settle = Duration::from_secs(2);
+        let start = Instant::now();
+        let letterbox = rect(0.0, 0.2, 1.0, 0.6);
+        let mut cropper = AutoCropper::new();
+
+        assert!(!cropper.on_sample(Some(letterbox), settle, start));
+        // 許容差以内の揺れは同じ候補として扱う
+        let jitter = rect(0.0, 0.2 + TOLERANCE / 2.0, 1.0, 0.6);
+        assert!(!cropper.on_sample(Some(jitter), settle, start + Duration::from_secs(1)));
+        // 判定できないフレームは候補を変えない
+        assert!(!cropper.on_sample(None, settle, start + Duration::from_millis(1500)));
+        assert_eq!(cropper.applied(), None);
+
+        assert!(cropper.on_sample(Some(letterbox), settle, start + settle));
+        assert_eq!(cropper.applied(), Some(letterbox));
+        // 同じ矩形が続いても変化としては通知しない
+        assert!(!cropper.on_sample(Some(letterbox), settle, start + settle * 2));
+    }
+
+    #[test]
+    fn cropper_restarts_settle_when_detection_changes() {
+        let settle = Duration::from_secs(2);
+        let start = Instant::now();
+        let letterbox = rect(0.0, 0.2, 1.0, 0.6);
+        let dark_scene = rect(0.1, 0.3, 0.8, 0.4);
+        let mut cropper = AutoCropper::new();
+        cropper.on_sample(Some(letterbox), settle, start);
+        assert!(cropper.on_sample(Some(letterbox), settle, start + settle));
+
+        // 一時的に小さく検出されても、settle 未満なら適用中の矩形は変わらない
+        let changed = start + settle + Duration::from_secs(1);
+        assert!(!cropper.on_sample(Some(dark_scene), settle, changed));
+        assert!(!cropper.on_sample(Some(letterbox), settle, changed + Duration::from_secs(1)));
+        assert_eq!(cropper.applied(), Some(letterbox));
+        assert_eq!(cropper.detected(), Some(letterbox));
+
+        cropper.reset();
+        assert_eq!(cropper.applied(), None);
+        assert_eq!(cropper.detected(), None);
+    }
+
+    #[test]
+    fn effective_rect_follows_mode() {
+        let detected = Some(rect(0.0, 0.2, 1.0, 0.6));
+        let manual = rect(0.1, 0.1, 0.5, 0.5);
+        let settings = |mode| AutoCropSettings { mode, rect: Some(manual), ..Default::default() };
+        assert_eq!(settings(CropMode::Off).effective_rect(detected), None);
+        assert_eq!(settings(CropMode::Auto).effective_rect(detected), detected);
+        assert_eq!(settings(CropMode::Locked).effective_rect(detected), Some(manual));
+        assert_eq!(settings(CropMode::Manual).effective_rect(None), Some(manual));
+    }
+}
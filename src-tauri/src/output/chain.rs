@@ -0,0 +1,129 @@
+/// 出力ごとの映像処理チェーン（macOS 専用）
+///
+/// mpv が描画した FBO と Syphon への公開の間に入り、出力設定に従って映像を加工する。
+/// 各パスは 2 枚の描画先を交互に使い（ピンポン）、前のパスの出力を次のパスの入力にする。
+/// 何も加工しない設定ではパスを省略し、mpv の FBO をそのまま返す。
+///
+/// ## パスの順序
+/// 1. 変形（切り出し・パン/ズーム・反転・回転・スケーリング）
+/// 2. ユーザーシェーダ（設定順）
+/// 3. 色調整・LUT（プロジェクターとの色合わせ。キーイングより前に置き、補正後の色でキーを抜く）
+/// 4. キーイング（映像にアルファを書き込む。シェーダや色調整がアルファを壊さないよう後に置く）
+/// 5. オーバーレイ（キーで抜けないよう最後に重ね、レイヤー自身のアルファで合成する。
+///    キーイングした出力では透明な部分にもレイヤーが残る）
+///
+/// 1–4 は `process`、5 は `overlay` で行う。間で HoldLastFrame 用のフレームを保持し、
+/// 時計やタイトルのレイヤーが静止画に焼き込まれないようにする。
+use anyhow::Result;
+
+use crate::output::color::ColorPass;
+use crate::output::gl_util::{Frame, FullscreenQuad, RenderTarget};
+use crate::output::key::KeyPass;
+use crate::output::overlay::{OverlayInputs, OverlayLayer, OverlayPass};
+use crate::output::shader::{ShaderInputs, ShaderPass};
+use crate::output::transform::TransformPass;
+use crate::output::OutputSettings;
+
+pub struct VideoChain {
+    quad: FullscreenQuad,
+    transform: TransformPass,
+    color: ColorPass,
+    shaders: ShaderPass,
+    key: KeyPass,
+    overlay: OverlayPass,
+    /// プレビューにだけ重ねるレイヤー用（出力側とフェード状態を分ける）
+    preview_overlay: OverlayPass,
+    /// ピンポン用の描画先
+    targets: [RenderTarget; 2],
+    /// 直近の出力フレームのアルファが乗算済みか（プレビュー専用のレイヤーの合成に使う）
+    premultiplied: bool,
+}
+
+impl VideoChain {
+    /// GL コンテキストが current の状態で呼ぶ
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            quad: FullscreenQuad::new(),
+            transform: TransformPass::new()?,
+            color: ColorPass::new()?,
+            shaders: ShaderPass::new(),
+            key: KeyPass::new()?,
+            overlay: OverlayPass::new()?,
+            preview_overlay: OverlayPass::new()?,
+            targets: [RenderTarget::new(1, 1), RenderTarget::new(1, 1)],
+            premultiplied: false,
+        })
+    }
+
+    /// source に出力設定の映像のパス（変形〜キーイング）を順に適用し、オーバーレイ前のフレームを返す
+    pub fn process(&mut self, source: Frame, settings: &OutputSettings, inputs: ShaderInputs) -> Frame {
+        let mut frame = source;
+
+        if !settings.transform.is_identity() {
+            let target = next_target(&mut self.targets, frame);
+            frame = self.transform.draw(&self.quad, frame, target, &settings.transform);
+        }
+
+        self.shaders.update(&settings.shaders);
+        for index in 0..self.shaders.len() {
+            if !self.shaders.is_ready(index) {
+                continue;
+            }
+            let target = next_target(&mut self.targets, frame);
+            frame = self.shaders.draw(index, &self.quad, frame, target, inputs);
+        }
+
+        if !settings.color.is_identity() {
+            let target = next_target(&mut self.targets, frame);
+            frame = self.color.draw(&self.quad, frame, target, &settings.color);
+        }
+
+        if !settings.key.is_none() {
+            let target = next_target(&mut self.targets, frame);
+            frame = self.key.draw(&self.quad, frame, target, &settings.key);
+        }
+        self.premultiplied = settings.key.premultiplied();
+
+        frame
+    }
+
+    /// `process` の結果に出力のレイヤーを重ね、最終フレームを返す
+    pub fn overlay(&mut self, frame: Frame, layers: &[OverlayLayer], inputs: &OverlayInputs) -> Frame {
+        // フェードアウト中のレイヤーを描き切るため、空になるまで毎フレーム呼ぶ（見えなければ素通り）
+        if layers.is_empty() {
+            return frame;
+        }
+        let target = next_target(&mut self.targets, frame);
+        self.overlay.draw(&self.quad, frame, target, layers, inputs, self.premultiplied)
+    }
+
+    /// 公開済みのフレームにプレビュー専用のレイヤーを重ねる（出力には影響しない）
+    pub fn overlay_preview(&mut self, frame: Frame, layers: &[OverlayLayer], inputs: &OverlayInputs) -> Frame {
+        if layers.is_empty() {
+            return frame;
+        }
+        let target = next_target(&mut self.targets, frame);
+        self.preview_overlay.draw(&self.quad, frame, target, layers, inputs, self.premultiplied)
+    }
+
+    /// GL リソースを解放する（GL コンテキストが current の状態で呼ぶ）
+    pub fn destroy(self) {
+        let Self { quad, transform, color, shaders, key, overlay, preview_overlay, mut targets, .. } = self;
+        quad.destroy();
+        transform.destroy();
+        color.destroy();
+        shaders.destroy();
+        key.destroy();
+        overlay.destroy();
+        preview_overlay.destroy();
+        for target in targets.iter_mut() {
+            target.delete();
+        }
+    }
+}
+
+/// 入力に使われていない方の描画先を返す
+fn next_target(targets: &mut [RenderTarget; 2], input: Frame) -> &mut RenderTarget {
+    let index = if targets[0].fbo == input.fbo { 1 } else { 0 };
+    &mut targets[index]
+}
@@ -0,0 +1,278 @@
+/// 出力ごとの色調整（明るさ・コントラスト・彩度・ガンマ・色相）と 3D LUT
+///
+/// ## 処理の順序
+/// 明るさ/コントラスト → 彩度 → 色相 → ガンマ → LUT
+///
+/// LUT はプロジェクターとの色合わせに使うため最後に適用する。
+/// 調整はすべて出力チェーンのシェーダで行い、mpv の再生（他の出力）には影響しない。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::output::lut::CubeLut;
+
+/// 色調整の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorAdjust {
+    /// 明るさ（-1.0–1.0、0.0 で無調整）
+    pub brightness: f32,
+    /// コントラスト（0.0–4.0、1.0 で無調整）
+    pub contrast: f32,
+    /// 彩度（0.0–4.0、0.0 でモノクロ、1.0 で無調整）
+    pub saturation: f32,
+    /// ガンマ（0.1–10.0、1.0 で無調整。大きいほど中間調が明るくなる）
+    pub gamma: f32,
+    /// 色相の回転（度、-180–180）
+    pub hue: f32,
+    /// `.cube` 形式の 3D LUT のパス
+    pub lut: Option<PathBuf>,
+}
+
+impl Default for ColorAdjust {
+    fn default() -> Self {
+        Self {
+            brightness: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            gamma: 1.0,
+            hue: 0.0,
+            lut: None,
+        }
+    }
+}
+
+impl ColorAdjust {
+    /// 設定値の範囲を確認する（LUT は実際に読み込んで確認する）
+    pub fn validate(&self) -> Result<()> {
+        if !(-1.0..=1.0).contains(&self.brightness) {
+            return Err(anyhow::anyhow!("brightness は -1.0–1.0 で指定してください: {}", self.brightness));
+        }
+        if !(0.0..=4.0).contains(&self.contrast) {
+            return Err(anyhow::anyhow!("contrast は 0.0–4.0 で指定してください: {}", self.contrast));
+        }
+        if !(0.0..=4.0).contains(&self.saturation) {
+            return Err(anyhow::anyhow!("saturation は 0.0–4.0 で指定してください: {}", self.saturation));
+        }
+        if !(0.1..=10.0).contains(&self.gamma) {
+            return Err(anyhow::anyhow!("gamma は 0.1–10.0 で指定してください: {}", self.gamma));
+        }
+        if !(-180.0..=180.0).contains(&self.hue) {
+            return Err(anyhow::anyhow!("hue は -180–180 で指定してください: {}", self.hue));
+        }
+        if let Some(path) = &self.lut {
+            CubeLut::load(path)?;
+        }
+        Ok(())
+    }
+
+    /// 何も調整しない設定か（色調整パスを省略できる）
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub use gpu::ColorPass;
+
+#[cfg(target_os = "macos")]
+mod gpu {
+    use super::ColorAdjust;
+    use crate::output::gl_util::{self, Frame, FullscreenQuad, RenderTarget};
+    use crate::output::lut::CubeLut;
+    use anyhow::Result;
+    use std::path::PathBuf;
+
+    const FRAGMENT_SHADER: &str = r#"#version 150
+in vec2 v_uv;
+out vec4 frag_color;
+uniform sampler2D u_texture;
+uniform float u_brightness;
+uniform float u_contrast;
+uniform float u_saturation;
+uniform float u_gamma;
+uniform float u_hue;
+uniform bool u_lut_enabled;
+uniform sampler3D u_lut;
+uniform float u_lut_size;
+uniform vec3 u_lut_domain_min;
+uniform vec3 u_lut_domain_max;
+
+void main() {
+    vec4 color = texture(u_texture, v_uv);
+    vec3 rgb = (color.rgb - 0.5) * u_contrast + 0.5 + u_brightness;
+
+    float luma = dot(rgb, vec3(0.2126, 0.7152, 0.0722));
+    rgb = mix(vec3(luma), rgb, u_saturation);
+
+    // 灰色軸まわりの回転（ロドリゲスの回転公式）
+    if (u_hue != 0.0) {
+        vec3 k = vec3(0.57735026);
+        float c = cos(u_hue);
+        rgb = rgb * c + cross(k, rgb) * sin(u_hue) + k * dot(k, rgb) * (1.0 - c);
+    }
+
+    rgb = pow(clamp(rgb, 0.0, 1.0), vec3(1.0 / u_gamma));
+
+    if (u_lut_enabled) {
+        vec3 t = clamp((rgb - u_lut_domain_min) / (u_lut_domain_max - u_lut_domain_min), 0.0, 1.0);
+        // 格子点の中心を標本化するよう半テクセルずらす
+        rgb = texture(u_lut, t * ((u_lut_size - 1.0) / u_lut_size) + 0.5 / u_lut_size).rgb;
+    }
+
+    frag_color = vec4(rgb, color.a);
+}
+"#;
+
+    /// 読み込み済みの LUT テクスチャ
+    struct LutTexture {
+        texture: gl::types::GLuint,
+        size: usize,
+        domain_min: [f32; 3],
+        domain_max: [f32; 3],
+    }
+
+    /// 色調整と LUT をシェーダで行うパス
+    pub struct ColorPass {
+        program: gl::types::GLuint,
+        uniforms: Uniforms,
+        lut: Option<LutTexture>,
+        /// 最後に読み込みを試みた LUT のパス（失敗したファイルを毎フレーム読み直さないため）
+        lut_path: Option<PathBuf>,
+    }
+
+    struct Uniforms {
+        texture: gl::types::GLint,
+        brightness: gl::types::GLint,
+        contrast: gl::types::GLint,
+        saturation: gl::types::GLint,
+        gamma: gl::types::GLint,
+        hue: gl::types::GLint,
+        lut_enabled: gl::types::GLint,
+        lut: gl::types::GLint,
+        lut_size: gl::types::GLint,
+        lut_domain_min: gl::types::GLint,
+        lut_domain_max: gl::types::GLint,
+    }
+
+    impl ColorPass {
+        pub fn new() -> Result<Self> {
+            let program = gl_util::compile_program(gl_util::FULLSCREEN_VERTEX_SHADER, FRAGMENT_SHADER)?;
+            let location = |name| gl_util::uniform_location(program, name);
+            let uniforms = Uniforms {
+                texture: location("u_texture"),
+                brightness: location("u_brightness"),
+                contrast: location("u_contrast"),
+                saturation: location("u_saturation"),
+                gamma: location("u_gamma"),
+                hue: location("u_hue"),
+                lut_enabled: location("u_lut_enabled"),
+                lut: location("u_lut"),
+                lut_size: location("u_lut_size"),
+                lut_domain_min: location("u_lut_domain_min"),
+                lut_domain_max: location("u_lut_domain_max"),
+            };
+            Ok(Self { program, uniforms, lut: None, lut_path: None })
+        }
+
+        /// input の色を調整して target に描画する
+        pub fn draw(&mut self, quad: &FullscreenQuad, input: Frame, target: &mut RenderTarget, color: &ColorAdjust) -> Frame {
+            if color.lut != self.lut_path {
+                self.load_lut(color.lut.clone());
+            }
+            target.resize(input.width, input.height);
+
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+                gl::Viewport(0, 0, input.width as i32, input.height as i32);
+                gl::Disable(gl::BLEND);
+                gl::UseProgram(self.program);
+
+                gl::ActiveTexture(gl::TEXTURE0);
+                gl::BindTexture(gl::TEXTURE_2D, input.texture);
+                gl::Uniform1i(self.uniforms.texture, 0);
+                gl::Uniform1f(self.uniforms.brightness, color.brightness);
+                gl::Uniform1f(self.uniforms.contrast, color.contrast);
+                gl::Uniform1f(self.uniforms.saturation, color.saturation);
+                gl::Uniform1f(self.uniforms.gamma, color.gamma);
+                gl::Uniform1f(self.uniforms.hue, color.hue.to_radians());
+
+                match &self.lut {
+                    Some(lut) => {
+                        gl::ActiveTexture(gl::TEXTURE1);
+                        gl::BindTexture(gl::TEXTURE_3D, lut.texture);
+                        gl::Uniform1i(self.uniforms.lut, 1);
+                        gl::Uniform1i(self.uniforms.lut_enabled, 1);
+                        gl::Uniform1f(self.uniforms.lut_size, lut.size as f32);
+                        gl::Uniform3fv(self.uniforms.lut_domain_min, 1, lut.domain_min.as_ptr());
+                        gl::Uniform3fv(self.uniforms.lut_domain_max, 1, lut.domain_max.as_ptr());
+                    }
+                    None => gl::Uniform1i(self.uniforms.lut_enabled, 0),
+                }
+
+                quad.draw();
+
+                if self.lut.is_some() {
+                    gl::BindTexture(gl::TEXTURE_3D, 0);
+                    gl::ActiveTexture(gl::TEXTURE0);
+                }
+                gl::BindTexture(gl::TEXTURE_2D, 0);
+                gl::UseProgram(0);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            }
+            target.frame()
+        }
+
+        /// LUT を読み込んで 3D テクスチャに転送する（失敗した場合は LUT なしで続ける）
+        fn load_lut(&mut self, path: Option<PathBuf>) {
+            self.delete_lut();
+            self.lut_path = path.clone();
+            let Some(path) = path else { return };
+
+            let cube = match CubeLut::load(&path) {
+                Ok(cube) => cube,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    return;
+                }
+            };
+
+            let mut texture = 0;
+            let size = cube.size as i32;
+            unsafe {
+                gl::GenTextures(1, &mut texture);
+                gl::BindTexture(gl::TEXTURE_3D, texture);
+                gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+                gl::TexImage3D(
+                    gl::TEXTURE_3D, 0, gl::RGB16F as _, size, size, size,
+                    0, gl::RGB, gl::FLOAT, cube.data.as_ptr() as *const _,
+                );
+                gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+                gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as _);
+                gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as _);
+                gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as _);
+                gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as _);
+                gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_R, gl::CLAMP_TO_EDGE as _);
+                gl::BindTexture(gl::TEXTURE_3D, 0);
+            }
+            log::info!("LUT を読み込みました: {} ({}³)", path.display(), cube.size);
+            self.lut = Some(LutTexture {
+                texture,
+                size: cube.size,
+                domain_min: cube.domain_min,
+                domain_max: cube.domain_max,
+            });
+        }
+
+        fn delete_lut(&mut self) {
+            if let Some(lut) = self.lut.take() {
+                unsafe { gl::DeleteTextures(1, &lut.texture) };
+            }
+        }
+
+        pub fn destroy(mut self) {
+            self.delete_lut();
+            unsafe { gl::DeleteProgram(self.program) };
+        }
+    }
+}
@@ -0,0 +1,220 @@
+/// 出力の GL 処理で共通に使うヘルパー（macOS 専用）
+///
+/// シェーダのコンパイル・全画面矩形の描画・描画先（FBO + テクスチャ）の管理をまとめる。
+/// いずれも出力スレッドの GL コンテキストが current の状態で呼ぶこと。
+use anyhow::Result;
+
+/// 全画面矩形用の頂点シェーダ（v_uv に 0.0–1.0 のテクスチャ座標を渡す）
+pub const FULLSCREEN_VERTEX_SHADER: &str = r#"#version 150
+in vec2 a_position;
+out vec2 v_uv;
+
+void main() {
+    v_uv = a_position * 0.5 + 0.5;
+    gl_Position = vec4(a_position, 0.0, 1.0);
+}
+"#;
+
+/// 描画済みのフレーム（所有権を持たない参照）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    pub fbo: gl::types::GLuint,
+    pub texture: gl::types::GLuint,
+    pub width: u32,
+    pub height: u32,
+}
+
+// ─── 描画先 ──────────────────────────────────────────────────────────────────
+
+/// FBO とカラーテクスチャの組
+#[derive(Debug)]
+pub struct RenderTarget {
+    pub fbo: gl::types::GLuint,
+    pub texture: gl::types::GLuint,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl RenderTarget {
+    pub fn new(width: u32, height: u32) -> Self {
+        let (mut fbo, mut texture) = (0, 0);
+        unsafe {
+            gl::GenTextures(1, &mut texture);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D, 0, gl::RGBA as _, width as _, height as _,
+                0, gl::RGBA, gl::UNSIGNED_BYTE, std::ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as _);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as _);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as _);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as _);
+
+            gl::GenFramebuffers(1, &mut fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, texture, 0);
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                log::error!("FBO が不完全: 0x{:X}", status);
+            }
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        Self { fbo, texture, width, height }
+    }
+
+    /// 解像度が変わった場合だけ作り直す
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if self.width == width && self.height == height {
+            return;
+        }
+        self.delete();
+        *self = Self::new(width, height);
+    }
+
+    pub fn frame(&self) -> Frame {
+        Frame { fbo: self.fbo, texture: self.texture, width: self.width, height: self.height }
+    }
+
+    pub fn delete(&mut self) {
+        unsafe {
+            if self.fbo != 0 {
+                gl::DeleteFramebuffers(1, &self.fbo);
+            }
+            if self.texture != 0 {
+                gl::DeleteTextures(1, &self.texture);
+            }
+        }
+        self.fbo = 0;
+        self.texture = 0;
+    }
+}
+
+// ─── 全画面矩形 ──────────────────────────────────────────────────────────────
+
+/// 2 枚の三角形で画面全体を覆う頂点配列（a_position を location 0 に割り当てる）
+pub struct FullscreenQuad {
+    vao: gl::types::GLuint,
+    vbo: gl::types::GLuint,
+}
+
+impl Default for FullscreenQuad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FullscreenQuad {
+    pub fn new() -> Self {
+        const VERTICES: [f32; 12] = [
+            -1.0, -1.0, 1.0, -1.0, 1.0, 1.0,
+            -1.0, -1.0, 1.0, 1.0, -1.0, 1.0,
+        ];
+        let (mut vao, mut vbo) = (0, 0);
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::BindVertexArray(vao);
+            gl::GenBuffers(1, &mut vbo);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(&VERTICES) as isize,
+                VERTICES.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+            gl::BindVertexArray(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+        Self { vao, vbo }
+    }
+
+    pub fn draw(&self) {
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            gl::DrawArrays(gl::TRIANGLES, 0, 6);
+            gl::BindVertexArray(0);
+        }
+    }
+
+    pub fn destroy(self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+    }
+}
+
+// ─── シェーダ ────────────────────────────────────────────────────────────────
+
+/// 頂点・フラグメントシェーダをコンパイルしてリンクする
+///
+/// 頂点属性 `a_position` は location 0 に固定する（FullscreenQuad と合わせる）。
+pub fn compile_program(vertex_src: &str, fragment_src: &str) -> Result<gl::types::GLuint> {
+    unsafe {
+        let vertex = compile_shader(gl::VERTEX_SHADER, vertex_src)?;
+        let fragment = match compile_shader(gl::FRAGMENT_SHADER, fragment_src) {
+            Ok(shader) => shader,
+            Err(e) => {
+                gl::DeleteShader(vertex);
+                return Err(e);
+            }
+        };
+
+        let program = gl::CreateProgram();
+        gl::AttachShader(program, vertex);
+        gl::AttachShader(program, fragment);
+        let name = std::ffi::CString::new("a_position").unwrap();
+        gl::BindAttribLocation(program, 0, name.as_ptr());
+        gl::LinkProgram(program);
+        gl::DeleteShader(vertex);
+        gl::DeleteShader(fragment);
+
+        let mut status = 0;
+        gl::GetProgramiv(program, gl::LINK_STATUS, &mut status);
+        if status == 0 {
+            let log = info_log(program, gl::GetProgramiv, gl::GetProgramInfoLog);
+            gl::DeleteProgram(program);
+            return Err(anyhow::anyhow!("シェーダのリンクに失敗: {}", log));
+        }
+        Ok(program)
+    }
+}
+
+/// uniform の location を取得する（存在しない場合は -1。glUniform* は -1 を無視する）
+pub fn uniform_location(program: gl::types::GLuint, name: &str) -> gl::types::GLint {
+    let name = std::ffi::CString::new(name).unwrap();
+    unsafe { gl::GetUniformLocation(program, name.as_ptr()) }
+}
+
+unsafe fn compile_shader(kind: gl::types::GLenum, source: &str) -> Result<gl::types::GLuint> {
+    let shader = gl::CreateShader(kind);
+    let src = std::ffi::CString::new(source)
+        .map_err(|_| anyhow::anyhow!("シェーダに NUL 文字が含まれています"))?;
+    gl::ShaderSource(shader, 1, &src.as_ptr(), std::ptr::null());
+    gl::CompileShader(shader);
+
+    let mut status = 0;
+    gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut status);
+    if status == 0 {
+        let log = info_log(shader, gl::GetShaderiv, gl::GetShaderInfoLog);
+        gl::DeleteShader(shader);
+        let kind = if kind == gl::VERTEX_SHADER { "頂点" } else { "フラグメント" };
+        return Err(anyhow::anyhow!("{}シェーダのコンパイルに失敗: {}", kind, log));
+    }
+    Ok(shader)
+}
+
+type GetIv = unsafe fn(gl::types::GLuint, gl::types::GLenum, *mut gl::types::GLint);
+type GetLog = unsafe fn(gl::types::GLuint, gl::types::GLsizei, *mut gl::types::GLsizei, *mut gl::types::GLchar);
+
+unsafe fn info_log(object: gl::types::GLuint, get_iv: GetIv, get_log: GetLog) -> String {
+    let mut len = 0;
+    get_iv(object, gl::INFO_LOG_LENGTH, &mut len);
+    let mut buf = vec![0u8; len.max(1) as usize];
+    let mut written = 0;
+    get_log(object, len, &mut written, buf.as_mut_ptr() as *mut _);
+    buf.truncate(written.max(0) as usize);
+    String::from_utf8_lossy(&buf).trim().to_string()
+}
@@ -0,0 +1,238 @@
+/// キーイング（クロマキー / ルミナンスキー）で出力にアルファを書き込む
+///
+/// mpv が描画する FBO は不透明（アルファ 1.0）なので、グリーンバックなどの素材を
+/// 受信側で合成するにはこのパスで透明部分を作る。Syphon / Spout はアルファ付きの
+/// テクスチャをそのまま共有する。
+///
+/// ## 出力のアルファ
+/// ストレートアルファ（色にアルファを乗算しない）で書き込む。
+/// `premultiply` を有効にすると乗算済みアルファで書き込む（受信側の設定に合わせる）。
+///
+/// ## クロマキー
+/// YCbCr の色差平面で、キー色との距離が `tolerance` 以下を透明、
+/// `tolerance + softness` 以上を不透明とし、その間はなめらかにつなぐ。
+/// `spill` はキー色の色かぶり（髪の縁の緑など）をキー色の方向の色差を差し引いて抑える。
+///
+/// ## ルミナンスキー
+/// 輝度が `threshold` 以下を透明にする（`invert` で明るい部分を透明にする）。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// キーイングの種類と設定
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Key {
+    /// キーイングしない（不透明のまま）
+    #[default]
+    None,
+    Chroma {
+        /// キー色
+        r: u8,
+        g: u8,
+        b: u8,
+        /// 透明にする色差の範囲（0.0–1.0）
+        tolerance: f32,
+        /// 透明から不透明へのなめらかさ（0.0–1.0）
+        softness: f32,
+        /// 色かぶりの抑制量（0.0–1.0）
+        spill: f32,
+        #[serde(default)]
+        premultiply: bool,
+    },
+    Luma {
+        /// 透明にする輝度の上限（0.0–1.0）
+        threshold: f32,
+        /// 透明から不透明へのなめらかさ（0.0–1.0）
+        softness: f32,
+        /// true の場合は明るい部分を透明にする
+        #[serde(default)]
+        invert: bool,
+        #[serde(default)]
+        premultiply: bool,
+    },
+}
+
+impl Key {
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        let check = |name: &str, value: f32| {
+            if (0.0..=1.0).contains(&value) {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("{} は 0.0–1.0 で指定してください: {}", name, value))
+            }
+        };
+        match *self {
+            Key::None => Ok(()),
+            Key::Chroma { tolerance, softness, spill, .. } => {
+                check("tolerance", tolerance)?;
+                check("softness", softness)?;
+                check("spill", spill)
+            }
+            Key::Luma { threshold, softness, .. } => {
+                check("threshold", threshold)?;
+                check("softness", softness)
+            }
+        }
+    }
+
+    /// キーイングしない設定か（キーイングパスを省略できる）
+    pub fn is_none(&self) -> bool {
+        *self == Key::None
+    }
+
+    /// 乗算済みアルファで書き込む設定か（後段のオーバーレイの合成で使う）
+    pub fn premultiplied(&self) -> bool {
+        match *self {
+            Key::None => false,
+            Key::Chroma { premultiply, .. } | Key::Luma { premultiply, .. } => premultiply,
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub use gpu::KeyPass;
+
+#[cfg(target_os = "macos")]
+mod gpu {
+    use super::Key;
+    use crate::output::gl_util::{self, Frame, FullscreenQuad, RenderTarget};
+    use anyhow::Result;
+
+    const FRAGMENT_SHADER: &str = r#"#version 150
+in vec2 v_uv;
+out vec4 frag_color;
+uniform sampler2D u_texture;
+// 0 = クロマキー, 1 = ルミナンスキー
+uniform int u_mode;
+uniform vec3 u_key_color;
+uniform float u_tolerance;
+uniform float u_softness;
+uniform float u_spill;
+uniform bool u_invert;
+uniform bool u_premultiply;
+
+const vec3 LUMA = vec3(0.2126, 0.7152, 0.0722);
+
+vec2 chroma(vec3 rgb) {
+    float y = dot(rgb, LUMA);
+    return vec2((rgb.b - y) / 1.8556, (rgb.r - y) / 1.5748);
+}
+
+vec3 from_ycbcr(float y, vec2 c) {
+    float r = y + 1.5748 * c.y;
+    float b = y + 1.8556 * c.x;
+    float g = (y - 0.2126 * r - 0.0722 * b) / 0.7152;
+    return vec3(r, g, b);
+}
+
+void main() {
+    vec4 color = texture(u_texture, v_uv);
+    vec3 rgb = color.rgb;
+    float alpha;
+
+    if (u_mode == 0) {
+        vec2 key = chroma(u_key_color);
+        vec2 c = chroma(rgb);
+        // 色差平面の距離（最大で約 0.7 なので 0.0–1.0 に広げる）
+        float dist = distance(c, key) * 1.4142;
+        alpha = smoothstep(u_tolerance, u_tolerance + u_softness + 0.0001, dist);
+
+        // キー色の方向の色差を差し引いて色かぶりを抑える
+        if (u_spill > 0.0 && length(key) > 0.0) {
+            vec2 dir = normalize(key);
+            float along = dot(c, dir);
+            if (along > 0.0) {
+                c -= dir * along * u_spill;
+                rgb = clamp(from_ycbcr(dot(rgb, LUMA), c), 0.0, 1.0);
+            }
+        }
+    } else {
+        float luma = dot(rgb, LUMA);
+        alpha = smoothstep(u_tolerance, u_tolerance + u_softness + 0.0001, luma);
+        if (u_invert) {
+            alpha = 1.0 - alpha;
+        }
+    }
+
+    alpha *= color.a;
+    frag_color = vec4(u_premultiply ? rgb * alpha : rgb, alpha);
+}
+"#;
+
+    /// キーイングをシェーダで行うパス
+    pub struct KeyPass {
+        program: gl::types::GLuint,
+        u_texture: gl::types::GLint,
+        u_mode: gl::types::GLint,
+        u_key_color: gl::types::GLint,
+        u_tolerance: gl::types::GLint,
+        u_softness: gl::types::GLint,
+        u_spill: gl::types::GLint,
+        u_invert: gl::types::GLint,
+        u_premultiply: gl::types::GLint,
+    }
+
+    impl KeyPass {
+        pub fn new() -> Result<Self> {
+            let program = gl_util::compile_program(gl_util::FULLSCREEN_VERTEX_SHADER, FRAGMENT_SHADER)?;
+            let location = |name| gl_util::uniform_location(program, name);
+            Ok(Self {
+                program,
+                u_texture: location("u_texture"),
+                u_mode: location("u_mode"),
+                u_key_color: location("u_key_color"),
+                u_tolerance: location("u_tolerance"),
+                u_softness: location("u_softness"),
+                u_spill: location("u_spill"),
+                u_invert: location("u_invert"),
+                u_premultiply: location("u_premultiply"),
+            })
+        }
+
+        /// input をキーイングして target に描画する（Key::None では呼ばない）
+        pub fn draw(&self, quad: &FullscreenQuad, input: Frame, target: &mut RenderTarget, key: &Key) -> Frame {
+            // ルミナンスキーは threshold を u_tolerance に入れて同じ式で計算する
+            let (mode, color, tolerance, softness, spill, invert, premultiply) = match *key {
+                Key::None => return input,
+                Key::Chroma { r, g, b, tolerance, softness, spill, premultiply } => {
+                    let color = [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0];
+                    (0, color, tolerance, softness, spill, false, premultiply)
+                }
+                Key::Luma { threshold, softness, invert, premultiply } => {
+                    (1, [0.0; 3], threshold, softness, 0.0, invert, premultiply)
+                }
+            };
+            target.resize(input.width, input.height);
+
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+                gl::Viewport(0, 0, input.width as i32, input.height as i32);
+                gl::Disable(gl::BLEND);
+                gl::UseProgram(self.program);
+
+                gl::ActiveTexture(gl::TEXTURE0);
+                gl::BindTexture(gl::TEXTURE_2D, input.texture);
+                gl::Uniform1i(self.u_texture, 0);
+                gl::Uniform1i(self.u_mode, mode);
+                gl::Uniform3fv(self.u_key_color, 1, color.as_ptr());
+                gl::Uniform1f(self.u_tolerance, tolerance);
+                gl::Uniform1f(self.u_softness, softness);
+                gl::Uniform1f(self.u_spill, spill);
+                gl::Uniform1i(self.u_invert, invert as i32);
+                gl::Uniform1i(self.u_premultiply, premultiply as i32);
+
+                quad.draw();
+
+                gl::BindTexture(gl::TEXTURE_2D, 0);
+                gl::UseProgram(0);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            }
+            target.frame()
+        }
+
+        pub fn destroy(self) {
+            unsafe { gl::DeleteProgram(self.program) };
+        }
+    }
+}
@@ -0,0 +1,177 @@
+/// `.cube` 形式の 3D LUT の読み込み
+///
+/// Adobe / Resolve で書き出される `.cube`（3D のみ）に対応する。
+/// - `LUT_3D_SIZE N`（2–256）
+/// - `DOMAIN_MIN` / `DOMAIN_MAX`（省略時は 0.0 / 1.0）
+/// - `TITLE` と `#` で始まるコメントは無視する
+/// - データ行は R が最も速く変わる順（GL の 3D テクスチャと同じ並び）
+use anyhow::Result;
+use std::path::Path;
+
+/// 読み込んだ 3D LUT
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubeLut {
+    /// 1 辺の格子点数
+    pub size: usize,
+    pub domain_min: [f32; 3],
+    pub domain_max: [f32; 3],
+    /// size³ 個の RGB（R が最も速く変わる順）
+    pub data: Vec<[f32; 3]>,
+}
+
+impl CubeLut {
+    /// ファイルから読み込む
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("LUT を読み込めません ({}): {}", path.display(), e))?;
+        Self::parse(&text)
+            .map_err(|e| anyhow::anyhow!("LUT の形式が不正です ({}): {}", path.display(), e))
+    }
+
+    /// `.cube` の内容を解析する
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut data = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line_no = index + 1;
+            let mut fields = line.split_whitespace();
+            let keyword = fields.next().unwrap_or_default();
+
+            match keyword {
+                "TITLE" => {}
+                "LUT_1D_SIZE" => return Err(anyhow::anyhow!("1D LUT には対応していません")),
+                "LUT_3D_SIZE" => {
+                    let n: usize = fields
+                        .next()
+                        .and_then(|v| v.parse().ok())
+                        .ok_or_else(|| anyhow::anyhow!("{} 行目: LUT_3D_SIZE が不正です", line_no))?;
+                    if !(2..=256).contains(&n) {
+                        return Err(anyhow::anyhow!("LUT_3D_SIZE は 2–256 で指定してください: {}", n));
+                    }
+                    size = Some(n);
+                    data.reserve(n * n * n);
+                }
+                "DOMAIN_MIN" => domain_min = parse_triplet(fields, line_no)?,
+                "DOMAIN_MAX" => domain_max = parse_triplet(fields, line_no)?,
+                // 数値で始まる行はデータ行
+                _ if keyword.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') => {
+                    let rgb = parse_triplet(line.split_whitespace(), line_no)?;
+                    data.push(rgb);
+                }
+                // 未知のキーワードは読み飛ばす（ツール独自の拡張があるため）
+                _ => log::debug!("LUT の未知のキーワードを無視: {}", keyword),
+            }
+        }
+
+        let size = size.ok_or_else(|| anyhow::anyhow!("LUT_3D_SIZE がありません"))?;
+        if data.len() != size * size * size {
+            return Err(anyhow::anyhow!(
+                "データ数が LUT_3D_SIZE と一致しません: {} (期待値 {})",
+                data.len(),
+                size * size * size
+            ));
+        }
+        if (0..3).any(|i| domain_max[i] <= domain_min[i]) {
+            return Err(anyhow::anyhow!("DOMAIN_MIN / DOMAIN_MAX が不正です"));
+        }
+
+        Ok(Self { size, domain_min, domain_max, data })
+    }
+}
+
+fn parse_triplet<'a>(mut fields: impl Iterator<Item = &'a str>, line_no: usize) -> Result<[f32; 3]> {
+    let mut out = [0.0f32; 3];
+    for value in out.iter_mut() {
+        *value = fields
+            .next()
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| anyhow::anyhow!("{} 行目: 数値が 3 つ必要です", line_no))?;
+        // "inf" / "nan" も f32 として読めてしまうため、GL に渡す前に弾く
+        if !value.is_finite() {
+            return Err(anyhow::anyhow!("{} 行目: 有限の数値ではありません: {}", line_no, value));
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2x2x2 の恒等 LUT のデータ行（R が最も速く変わる順）
+    fn identity_rows() -> String {
+        let mut rows = String::new();
+        for b in 0..2 {
+            for g in 0..2 {
+                for r in 0..2 {
+                    rows.push_str(&format!("{} {} {}\n", r, g, b));
+                }
+            }
+        }
+        rows
+    }
+
+    fn error(text: &str) -> String {
+        CubeLut::parse(text).expect_err("エラーになりません").to_string()
+    }
+
+    #[test]
+    fn parses_header_comments_and_data() {
+        let text = format!(
+            "# comment\nTITLE \"identity\"\nLUT_3D_SIZE 2\nLUT_IN_VIDEO_RANGE\n\n{}",
+            identity_rows()
+        );
+        let lut = CubeLut::parse(&text).unwrap();
+        assert_eq!(lut.size, 2);
+        assert_eq!(lut.domain_min, [0.0; 3]);
+        assert_eq!(lut.domain_max, [1.0; 3]);
+        assert_eq!(lut.data.len(), 8);
+        assert_eq!(lut.data[1], [1.0, 0.0, 0.0]);
+        assert_eq!(lut.data[2], [0.0, 1.0, 0.0]);
+        assert_eq!(lut.data[4], [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn data_count_must_match_size() {
+        let rows = identity_rows();
+        let missing: String = rows.lines().take(7).map(|line| format!("{}\n", line)).collect();
+        assert!(error(&format!("LUT_3D_SIZE 2\n{}", missing)).contains("一致しません"));
+        assert!(error(&format!("LUT_3D_SIZE 2\n{}0 0 0\n", rows)).contains("一致しません"));
+        assert!(error(&format!("LUT_3D_SIZE 3\n{}", rows)).contains("一致しません"));
+        assert!(error(&rows).contains("LUT_3D_SIZE がありません"));
+    }
+
+    #[test]
+    fn rejects_out_of_range_size_and_values() {
+        let rows = identity_rows();
+        assert!(error(&format!("LUT_3D_SIZE 1\n{}", rows)).contains("2–256"));
+        assert!(error(&format!("LUT_3D_SIZE 257\n{}", rows)).contains("2–256"));
+        assert!(error(&format!("LUT_3D_SIZE abc\n{}", rows)).contains("LUT_3D_SIZE が不正"));
+        assert!(error("LUT_1D_SIZE 16\n").contains("1D LUT"));
+
+        // 有限でない値・数値が足りない行
+        let infinite = rows.replacen("1 1 1", "1 -inf 1", 1);
+        assert!(error(&format!("LUT_3D_SIZE 2\n{}", infinite)).contains("9 行目"));
+        let short = rows.replacen("1 1 1", "1 1", 1);
+        assert!(error(&format!("LUT_3D_SIZE 2\n{}", short)).contains("数値が 3 つ"));
+    }
+
+    #[test]
+    fn domain_is_parsed_and_validated() {
+        let rows = identity_rows();
+        let lut = CubeLut::parse(&format!("LUT_3D_SIZE 2\nDOMAIN_MIN 0 0 0\nDOMAIN_MAX 4 4 4\n{}", rows)).unwrap();
+        assert_eq!(lut.domain_max, [4.0; 3]);
+
+        assert!(error(&format!("LUT_3D_SIZE 2\nDOMAIN_MAX 1 0 1\n{}", rows)).contains("DOMAIN"));
+        assert!(error(&format!("LUT_3D_SIZE 2\nDOMAIN_MIN 1 1 1\n{}", rows)).contains("DOMAIN"));
+        assert!(error(&format!("LUT_3D_SIZE 2\nDOMAIN_MIN 0 0\n{}", rows)).contains("2 行目"));
+        assert!(error(&format!("LUT_3D_SIZE 2\nDOMAIN_MAX 1 nan 1\n{}", rows)).contains("2 行目"));
+    }
+}
@@ -17,6 +17,223 @@ pub mod preview;
 #[cfg(target_os = "windows")]
 pub mod preview;
 
+// GL を使えない環境向けのプレビュー（映像変形は CPU で適用する）
+#[cfg(target_os = "macos")]
+pub mod preview_sw;
+
+// 読み込み中・エラー時に出力へ送る待機画面
+pub mod standby;
+
+// 出力ごとの映像変形（CPU 実装はソフトウェア描画経路でも使う）
+pub mod transform;
+
+// 黒帯の自動検出と切り出し
+pub mod autocrop;
+
+// 出力ごとの色調整と 3D LUT
+pub mod color;
+pub mod lut;
+
+// ユーザー定義の GLSL シェーダ
+pub mod shader;
+
+// キーイング（出力にアルファを書き込む）
+pub mod key;
+
+// 文字・画像のオーバーレイ
+pub mod overlay;
+
+// 出力の GL 処理（Syphon のレンダリングスレッドで使う）
+#[cfg(target_os = "macos")]
+pub mod gl_util;
+#[cfg(target_os = "macos")]
+pub mod chain;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use autocrop::{AutoCropSettings, CropMode, CropStatus};
+use color::ColorAdjust;
+use key::Key;
+use overlay::OverlayLayer;
+use shader::ShaderEffect;
+use standby::StandbySource;
+use transform::Rect;
+use transform::Transform;
+
+/// 設定を持てる出力名（この環境で映像を送れる出力のみ。Spout は未実装のため含めない）
+#[cfg(target_os = "macos")]
+pub const OUTPUT_NAMES: &[&str] = &["syphon"];
+#[cfg(not(target_os = "macos"))]
+pub const OUTPUT_NAMES: &[&str] = &[];
+
+// ─── 出力ごとの設定 ──────────────────────────────────────────────────────────
+
+/// 出力ごとの設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputSettings {
+    /// 出力を使うか（無効にするとサーバーを停止する）
+    pub enabled: bool,
+    /// 映像が無い間に送るスタンバイ映像
+    pub standby: StandbySource,
+    /// 公開前に適用する映像変形
+    pub transform: Transform,
+    /// 黒帯の自動切り出し
+    pub autocrop: AutoCropSettings,
+    /// 色調整と LUT
+    pub color: ColorAdjust,
+    /// 変形の後に順に適用するユーザーシェーダ
+    pub shaders: Vec<ShaderEffect>,
+    /// クロマキー / ルミナンスキー
+    pub key: Key,
+    /// 映像の上に重ねるレイヤー（配列の順に下から重ねる）
+    pub overlays: Vec<OverlayLayer>,
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            standby: StandbySource::default(),
+            transform: Transform::default(),
+            autocrop: AutoCropSettings::default(),
+            color: ColorAdjust::default(),
+            shaders: Vec::new(),
+            key: Key::default(),
+            overlays: Vec::new(),
+        }
+    }
+}
+
+impl OutputSettings {
+    /// 自動切り出しの結果（applied = 安定した検出結果）を変形の crop に反映した設定を返す
+    pub fn effective(&self, applied: Option<Rect>) -> OutputSettings {
+        let mut settings = self.clone();
+        if let Some(rect) = self.autocrop.effective_rect(applied) {
+            settings.transform.crop = Some(rect);
+        }
+        settings
+    }
+}
+
+/// 出力名 → 設定の対応表
+///
+/// PlayerState と各出力スレッドで共有する。出力スレッドは `version()` の変化を見て
+/// 設定を読み直すため、再生中に変更してもすぐ反映される。
+#[derive(Clone, Default)]
+pub struct OutputRegistry {
+    settings: Arc<Mutex<HashMap<String, OutputSettings>>>,
+    /// プレビューにだけ重ねるレイヤー（出力には送らない字幕など）
+    preview_overlays: Arc<Mutex<Vec<OverlayLayer>>>,
+    version: Arc<AtomicU64>,
+}
+
+impl OutputRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 出力の設定を取得する（未設定なら既定値）
+    pub fn get(&self, output: &str) -> OutputSettings {
+        self.settings
+            .lock()
+            .ok()
+            .and_then(|settings| settings.get(output).cloned())
+            .unwrap_or_default()
+    }
+
+    /// 出力の設定を書き換える
+    pub fn update(&self, output: &str, f: impl FnOnce(&mut OutputSettings)) -> Result<()> {
+        if !OUTPUT_NAMES.contains(&output) {
+            return Err(anyhow::anyhow!("未知の出力: {}", output));
+        }
+        let mut settings = self.settings.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        f(settings.entry(output.to_string()).or_default());
+        self.version.fetch_add(1, Ordering::Release);
+        Ok(())
+    }
+
+    /// プレビューにだけ重ねるレイヤーを取得する
+    pub fn preview_overlays(&self) -> Vec<OverlayLayer> {
+        self.preview_overlays
+            .lock()
+            .map(|layers| layers.clone())
+            .unwrap_or_default()
+    }
+
+    /// プレビューにだけ重ねるレイヤーを設定する
+    pub fn set_preview_overlays(&self, layers: Vec<OverlayLayer>) -> Result<()> {
+        let mut current = self.preview_overlays.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        *current = layers;
+        self.version.fetch_add(1, Ordering::Release);
+        Ok(())
+    }
+
+    /// 設定が変わるたびに増える番号
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+}
+
+// ─── 稼働状況 ────────────────────────────────────────────────────────────────
+
+/// 出力スレッドの稼働状況
+///
+/// 出力スレッドが書き込み、プレイヤーのウォッチドッグが読み取ってフレーム停止を検知する。
+#[derive(Debug, Default)]
+pub struct OutputStats {
+    frames: AtomicU64,
+    render_failed: AtomicBool,
+    /// 黒帯の検出結果（直近の検出, 安定した検出）
+    crop: Mutex<(Option<Rect>, Option<Rect>)>,
+}
+
+impl OutputStats {
+    /// mpv が新しいフレームを出したことを記録する（同じフレームの描き直しでは呼ばない）
+    pub fn record_frame(&self) {
+        self.frames.fetch_add(1, Ordering::Relaxed);
+        self.render_failed.store(false, Ordering::Relaxed);
+    }
+
+    /// これまでに mpv が出した新しいフレームの数
+    pub fn frame_count(&self) -> u64 {
+        self.frames.load(Ordering::Relaxed)
+    }
+
+    /// 出力を止めたときにフレーム数を 0 に戻す（ウォッチドッグに出力の停止を障害と判定させない）
+    pub fn reset_frames(&self) {
+        self.frames.store(0, Ordering::Relaxed);
+    }
+
+    /// mpv の描画が連続して失敗しているかを設定する
+    pub fn set_render_failed(&self, failed: bool) {
+        self.render_failed.store(failed, Ordering::Relaxed);
+    }
+
+    pub fn render_failed(&self) -> bool {
+        self.render_failed.load(Ordering::Relaxed)
+    }
+
+    /// 黒帯の検出結果を記録する
+    pub fn set_crop(&self, detected: Option<Rect>, applied: Option<Rect>) {
+        if let Ok(mut crop) = self.crop.lock() {
+            *crop = (detected, applied);
+        }
+    }
+
+    /// 黒帯の検出状況（mode は出力設定から渡す）
+    pub fn crop_status(&self, mode: CropMode) -> CropStatus {
+        let (detected, applied) = self.crop.lock().map(|crop| *crop).unwrap_or_default();
+        CropStatus { mode, detected, applied }
+    }
+}
+
 /// OpenGL テクスチャを Spout/Syphon に送信する共通インターフェース
 /// Phase 3 で実装する
 #[allow(dead_code)]
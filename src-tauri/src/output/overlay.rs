@@ -0,0 +1,647 @@
+/// 文字・画像のオーバーレイ（タイトル、ロワーサード、残り時間、時計、ロゴ）
+///
+/// 出力ごとにレイヤーを重ねる。文字は ab_glyph で CPU ラスタライズしてテクスチャにし、
+/// 内容が変わったとき（時計なら毎秒）だけ作り直す。合成は出力チェーンの GL で行う。
+///
+/// ## 文字の置き換え
+/// テキストの中の次の記法は描画のたびに置き換える。
+/// - `{title}`: メディアのタイトル
+/// - `{elapsed}` / `{remaining}` / `{duration}`: 再生位置・残り時間・長さ（`H:MM:SS` / `M:SS`）
+/// - `{clock}`: 現在時刻（`HH:MM:SS`）
+/// - `{subtitle}`: 表示中の字幕テキスト
+///
+/// ## フェード
+/// `visible` を切り替えると `fade_in` / `fade_out` 秒かけて不透明度を変える。
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::output::standby::load_image;
+
+/// フォント未指定時に順に試すシステムフォント（日本語のタイトルを表示できるものを優先）
+const DEFAULT_FONTS: &[&str] = &[
+    "/System/Library/Fonts/ヒラギノ角ゴシック W6.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/System/Library/Fonts/Helvetica.ttc",
+    "C:\\Windows\\Fonts\\meiryo.ttc",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+];
+
+/// RGBA の色（0–255）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    #[serde(default = "opaque")]
+    pub a: u8,
+}
+
+fn opaque() -> u8 {
+    255
+}
+
+impl Rgba {
+    pub const WHITE: Rgba = Rgba { r: 255, g: 255, b: 255, a: 255 };
+}
+
+/// レイヤーのどの点を (x, y) に合わせるか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// レイヤーの左上から基準点までの割合（0.0–1.0）
+    pub fn offset(self) -> (f32, f32) {
+        match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Top => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::Bottom => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        }
+    }
+}
+
+/// レイヤーの中身
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum OverlayContent {
+    /// 文字（`{title}` などの置き換えに対応）
+    Text { text: String },
+    /// PNG / JPEG 画像（ロゴなど）
+    Image { path: String },
+}
+
+impl Default for OverlayContent {
+    fn default() -> Self {
+        OverlayContent::Text { text: String::new() }
+    }
+}
+
+/// オーバーレイのレイヤー 1 枚分の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlayLayer {
+    /// レイヤーの識別子（同じ id で設定すると置き換える）
+    pub id: String,
+    pub content: OverlayContent,
+    /// 基準点の位置（正規化座標、左上原点）
+    pub x: f32,
+    pub y: f32,
+    pub anchor: Anchor,
+    /// 文字の高さ / 画像の高さ（出力の高さに対する割合）
+    pub size: f32,
+    /// フォントファイルのパス（None の場合はシステムフォント）
+    pub font: Option<String>,
+    pub color: Rgba,
+    /// 文字の背景（ロワーサード用の帯）
+    pub background: Option<Rgba>,
+    pub visible: bool,
+    /// フェードイン / フェードアウトの秒数
+    pub fade_in: f32,
+    pub fade_out: f32,
+}
+
+impl Default for OverlayLayer {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            content: OverlayContent::default(),
+            x: 0.05,
+            y: 0.05,
+            anchor: Anchor::TopLeft,
+            size: 0.05,
+            font: None,
+            color: Rgba::WHITE,
+            background: None,
+            visible: true,
+            fade_in: 0.5,
+            fade_out: 0.5,
+        }
+    }
+}
+
+impl OverlayLayer {
+    /// 設定値の範囲を確認する（画像とフォントは実際に読み込んでみる）
+    pub fn validate(&self) -> Result<()> {
+        if self.id.is_empty() {
+            return Err(anyhow::anyhow!("id を指定してください"));
+        }
+        if !(-1.0..=2.0).contains(&self.x) || !(-1.0..=2.0).contains(&self.y) {
+            return Err(anyhow::anyhow!("x / y は -1.0–2.0 で指定してください"));
+        }
+        if !(0.005..=1.0).contains(&self.size) {
+            return Err(anyhow::anyhow!("size は 0.005–1.0 で指定してください: {}", self.size));
+        }
+        if !(0.0..=60.0).contains(&self.fade_in) || !(0.0..=60.0).contains(&self.fade_out) {
+            return Err(anyhow::anyhow!("fade_in / fade_out は 0–60 秒で指定してください"));
+        }
+        match &self.content {
+            OverlayContent::Image { path } => {
+                load_image(path)?;
+            }
+            OverlayContent::Text { .. } => {
+                if let Some(path) = &self.font {
+                    load_font(Path::new(path))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ─── 文字の置き換え ──────────────────────────────────────────────────────────
+
+/// 置き換えに使う再生中の情報
+#[derive(Debug, Clone, Default)]
+pub struct OverlayInputs {
+    pub title: String,
+    pub time_pos: f64,
+    pub duration: f64,
+    pub subtitle: String,
+}
+
+/// `{title}` などの記法を置き換える
+pub fn expand_text(text: &str, inputs: &OverlayInputs) -> String {
+    if !text.contains('{') {
+        return text.to_string();
+    }
+    let remaining = (inputs.duration - inputs.time_pos).max(0.0);
+    text.replace("{title}", &inputs.title)
+        .replace("{elapsed}", &format_time(inputs.time_pos))
+        .replace("{remaining}", &format_time(remaining))
+        .replace("{duration}", &format_time(inputs.duration))
+        .replace("{clock}", &chrono::Local::now().format("%H:%M:%S").to_string())
+        .replace("{subtitle}", &inputs.subtitle)
+}
+
+/// 秒数を `H:MM:SS`（1 時間未満は `M:SS`）にする
+fn format_time(seconds: f64) -> String {
+    let total = seconds.max(0.0).floor() as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+// ─── 文字のラスタライズ ──────────────────────────────────────────────────────
+
+/// フォントファイルを読み込む（.ttc は最初のフェイスを使う）
+pub fn load_font(path: &Path) -> Result<FontVec> {
+    let data = std::fs::read(path)
+        .map_err(|e| anyhow::anyhow!("フォントを読み込めません ({}): {}", path.display(), e))?;
+    FontVec::try_from_vec_and_index(data, 0)
+        .map_err(|e| anyhow::anyhow!("フォントの形式が不正です ({}): {}", path.display(), e))
+}
+
+/// 読み込んだフォントをパスごとに保持する（None = システムフォント）
+#[derive(Default)]
+pub struct FontCache {
+    fonts: HashMap<Option<String>, Option<FontVec>>,
+}
+
+impl FontCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// フォントを取得する（読み込みに失敗した場合は None。失敗も記録して読み直さない）
+    pub fn get(&mut self, path: Option<&str>) -> Option<&FontVec> {
+        self.fonts
+            .entry(path.map(str::to_string))
+            .or_insert_with(|| {
+                let result = match path {
+                    Some(path) => load_font(Path::new(path)),
+                    None => DEFAULT_FONTS
+                        .iter()
+                        .find_map(|path| load_font(Path::new(path)).ok())
+                        .ok_or_else(|| anyhow::anyhow!("使用できるシステムフォントが見つかりません")),
+                };
+                result.map_err(|e| log::warn!("{}", e)).ok()
+            })
+            .as_ref()
+    }
+}
+
+/// 文字列を RGBA8（上の行から並ぶ）に描画する（戻り値: 幅, 高さ, ピクセル）
+///
+/// 改行で複数行にできる。背景を指定した場合は文字の周囲に余白を付けて塗る。
+pub fn render_text(font: &FontVec, text: &str, px: f32, color: Rgba, background: Option<Rgba>) -> (u32, u32, Vec<u8>) {
+    let scale = PxScale::from(px.max(1.0));
+    let scaled = font.as_scaled(scale);
+    let line_height = scaled.ascent() - scaled.descent() + scaled.line_gap();
+    let padding = if background.is_some() { (px * 0.25).ceil() } else { 0.0 };
+
+    let line_width = |line: &str| {
+        let mut width = 0.0;
+        let mut previous = None;
+        for c in line.chars() {
+            let id = font.glyph_id(c);
+            if let Some(prev) = previous {
+                width += scaled.kern(prev, id);
+            }
+            width += scaled.h_advance(id);
+            previous = Some(id);
+        }
+        width
+    };
+    let lines: Vec<&str> = text.lines().collect();
+    let text_width = lines.iter().map(|line| line_width(line)).fold(0.0f32, f32::max);
+    let text_height = line_height * lines.len().max(1) as f32;
+
+    let width = (text_width + padding * 2.0).ceil().max(1.0) as u32;
+    let height = (text_height + padding * 2.0).ceil().max(1.0) as u32;
+    let mut pixels = vec![0u8; (width * height * 4) as usize];
+    if let Some(bg) = background {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[bg.r, bg.g, bg.b, bg.a]);
+        }
+    }
+
+    for (row, line) in lines.iter().enumerate() {
+        let baseline = padding + scaled.ascent() + line_height * row as f32;
+        let mut x = padding;
+        let mut previous = None;
+        for c in line.chars() {
+            let id = font.glyph_id(c);
+            if let Some(prev) = previous {
+                x += scaled.kern(prev, id);
+            }
+            let glyph = id.with_scale_and_position(scale, ab_glyph::point(x, baseline));
+            x += scaled.h_advance(id);
+            previous = Some(id);
+
+            let Some(outlined) = font.outline_glyph(glyph) else { continue };
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i32 + gx as i32;
+                let py = bounds.min.y as i32 + gy as i32;
+                if px < 0 || py < 0 || px >= width as i32 || py >= height as i32 {
+                    return;
+                }
+                let i = ((py as u32 * width + px as u32) * 4) as usize;
+                blend_over(&mut pixels[i..i + 4], color, coverage);
+            });
+        }
+    }
+    (width, height, pixels)
+}
+
+/// ストレートアルファで src を dst の上に重ねる
+fn blend_over(dst: &mut [u8], src: Rgba, coverage: f32) {
+    let src_a = coverage.clamp(0.0, 1.0) * src.a as f32 / 255.0;
+    let dst_a = dst[3] as f32 / 255.0;
+    let out_a = src_a + dst_a * (1.0 - src_a);
+    if out_a <= 0.0 {
+        return;
+    }
+    for (channel, value) in [src.r, src.g, src.b].into_iter().enumerate() {
+        let mixed = (value as f32 * src_a + dst[channel] as f32 * dst_a * (1.0 - src_a)) / out_a;
+        dst[channel] = mixed.round() as u8;
+    }
+    dst[3] = (out_a * 255.0).round() as u8;
+}
+
+#[cfg(target_os = "macos")]
+pub use gpu::OverlayPass;
+
+#[cfg(target_os = "macos")]
+mod gpu {
+    use super::{expand_text, FontCache, OverlayContent, OverlayInputs, OverlayLayer};
+    use crate::output::gl_util::{self, Frame, FullscreenQuad, RenderTarget};
+    use crate::output::standby::load_image;
+    use anyhow::Result;
+    use std::collections::HashMap;
+    use std::time::Instant;
+
+    /// u_rect（NDC の左下・右上）に合わせて全画面矩形を縮める頂点シェーダ
+    const VERTEX_SHADER: &str = r#"#version 150
+in vec2 a_position;
+out vec2 v_uv;
+uniform vec4 u_rect;
+
+void main() {
+    v_uv = a_position * 0.5 + 0.5;
+    gl_Position = vec4(mix(u_rect.xy, u_rect.zw, v_uv), 0.0, 1.0);
+}
+"#;
+
+    /// レイヤーの画像は上の行から並んでいるので v を反転して標本化する
+    /// （レイヤー同士は乗算済みアルファで透明な描画先に重ねる）
+    const FRAGMENT_SHADER: &str = r#"#version 150
+in vec2 v_uv;
+out vec4 frag_color;
+uniform sampler2D u_texture;
+uniform float u_opacity;
+
+void main() {
+    vec4 color = texture(u_texture, vec2(v_uv.x, 1.0 - v_uv.y));
+    float alpha = color.a * u_opacity;
+    frag_color = vec4(color.rgb * alpha, alpha);
+}
+"#;
+
+    /// 重ねたレイヤーを入力の上に over で合成する（入力のアルファを保ち、入力と同じ形式で書き込む）
+    const COMPOSITE_SHADER: &str = r#"#version 150
+in vec2 v_uv;
+out vec4 frag_color;
+uniform sampler2D u_frame;
+uniform sampler2D u_layers;
+// 入力のアルファが乗算済みか（キーイングの premultiply）
+uniform bool u_premultiplied;
+
+void main() {
+    vec4 frame = texture(u_frame, v_uv);
+    vec4 layers = texture(u_layers, v_uv);
+    vec3 base = u_premultiplied ? frame.rgb : frame.rgb * frame.a;
+    float alpha = layers.a + frame.a * (1.0 - layers.a);
+    vec3 rgb = layers.rgb + base * (1.0 - layers.a);
+    if (!u_premultiplied) {
+        rgb = alpha > 0.0 ? rgb / alpha : vec3(0.0);
+    }
+    frag_color = vec4(rgb, alpha);
+}
+"#;
+
+    /// レイヤーごとの描画状態
+    struct LayerState {
+        /// テクスチャの元になった内容（変わったら作り直す）
+        key: String,
+        texture: gl::types::GLuint,
+        width: u32,
+        height: u32,
+        /// 現在の不透明度（フェード中は 0.0–1.0 の間）
+        opacity: f32,
+    }
+
+    impl LayerState {
+        fn delete(&mut self) {
+            if self.texture != 0 {
+                unsafe { gl::DeleteTextures(1, &self.texture) };
+                self.texture = 0;
+            }
+        }
+    }
+
+    /// レイヤーを入力の上に合成するパス
+    pub struct OverlayPass {
+        program: gl::types::GLuint,
+        u_texture: gl::types::GLint,
+        u_rect: gl::types::GLint,
+        u_opacity: gl::types::GLint,
+        composite: gl::types::GLuint,
+        u_frame: gl::types::GLint,
+        u_layers: gl::types::GLint,
+        u_premultiplied: gl::types::GLint,
+        /// レイヤーだけを重ねる透明な描画先
+        layer_target: RenderTarget,
+        states: HashMap<String, LayerState>,
+        fonts: FontCache,
+        last_draw: Instant,
+    }
+
+    impl OverlayPass {
+        pub fn new() -> Result<Self> {
+            let program = gl_util::compile_program(VERTEX_SHADER, FRAGMENT_SHADER)?;
+            let composite = gl_util::compile_program(gl_util::FULLSCREEN_VERTEX_SHADER, COMPOSITE_SHADER)?;
+            Ok(Self {
+                program,
+                u_texture: gl_util::uniform_location(program, "u_texture"),
+                u_rect: gl_util::uniform_location(program, "u_rect"),
+                u_opacity: gl_util::uniform_location(program, "u_opacity"),
+                composite,
+                u_frame: gl_util::uniform_location(composite, "u_frame"),
+                u_layers: gl_util::uniform_location(composite, "u_layers"),
+                u_premultiplied: gl_util::uniform_location(composite, "u_premultiplied"),
+                layer_target: RenderTarget::new(1, 1),
+                states: HashMap::new(),
+                fonts: FontCache::new(),
+                last_draw: Instant::now(),
+            })
+        }
+
+        /// input にレイヤーを重ねて target に描画する（見えるレイヤーが無ければ input を返す）
+        ///
+        /// `premultiplied` は input のアルファが乗算済みか。キーイング後のフレームにも
+        /// アルファを壊さずに重ね、input と同じ形式で書き込む。
+        pub fn draw(
+            &mut self,
+            quad: &FullscreenQuad,
+            input: Frame,
+            target: &mut RenderTarget,
+            layers: &[OverlayLayer],
+            inputs: &OverlayInputs,
+            premultiplied: bool,
+        ) -> Frame {
+            let dt = self.last_draw.elapsed().as_secs_f32();
+            self.last_draw = Instant::now();
+
+            // 設定から消えたレイヤーを破棄する
+            self.states.retain(|id, state| {
+                let keep = layers.iter().any(|layer| &layer.id == id);
+                if !keep {
+                    state.delete();
+                }
+                keep
+            });
+
+            // フェードを進め、内容が変わったレイヤーのテクスチャを作り直す
+            for layer in layers {
+                let key = content_key(layer, inputs, input.height);
+                let state = self.states.entry(layer.id.clone()).or_insert_with(|| LayerState {
+                    key: String::new(),
+                    texture: 0,
+                    width: 0,
+                    height: 0,
+                    opacity: 0.0,
+                });
+                state.opacity = step_opacity(state.opacity, layer, dt);
+                if state.opacity > 0.0 && state.key != key {
+                    state.key = key;
+                    Self::rasterize(&mut self.fonts, state, layer, inputs, input.height);
+                }
+            }
+
+            let visible = layers.iter().any(|layer| {
+                self.states.get(&layer.id).is_some_and(|s| s.opacity > 0.0 && s.texture != 0 && s.width > 0)
+            });
+            if !visible {
+                return input;
+            }
+
+            target.resize(input.width, input.height);
+            self.layer_target.resize(input.width, input.height);
+            unsafe {
+                // レイヤーを透明な描画先に乗算済みアルファで重ねる
+                gl::BindFramebuffer(gl::FRAMEBUFFER, self.layer_target.fbo);
+                gl::Viewport(0, 0, input.width as i32, input.height as i32);
+                gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+                gl::Clear(gl::COLOR_BUFFER_BIT);
+                gl::Enable(gl::BLEND);
+                gl::BlendFunc(gl::ONE, gl::ONE_MINUS_SRC_ALPHA);
+                gl::UseProgram(self.program);
+                gl::ActiveTexture(gl::TEXTURE0);
+                gl::Uniform1i(self.u_texture, 0);
+
+                for layer in layers {
+                    let Some(state) = self.states.get(&layer.id) else { continue };
+                    if state.opacity <= 0.0 || state.texture == 0 || state.width == 0 {
+                        continue;
+                    }
+                    let rect = layer_rect(layer, state.width, state.height, input.width, input.height);
+                    gl::BindTexture(gl::TEXTURE_2D, state.texture);
+                    gl::Uniform4fv(self.u_rect, 1, rect.as_ptr());
+                    gl::Uniform1f(self.u_opacity, state.opacity);
+                    quad.draw();
+                }
+                gl::Disable(gl::BLEND);
+
+                // 入力の上にレイヤーを合成する
+                gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+                gl::UseProgram(self.composite);
+                gl::ActiveTexture(gl::TEXTURE1);
+                gl::BindTexture(gl::TEXTURE_2D, self.layer_target.texture);
+                gl::Uniform1i(self.u_layers, 1);
+                gl::ActiveTexture(gl::TEXTURE0);
+                gl::BindTexture(gl::TEXTURE_2D, input.texture);
+                gl::Uniform1i(self.u_frame, 0);
+                gl::Uniform1i(self.u_premultiplied, premultiplied as i32);
+                quad.draw();
+
+                gl::ActiveTexture(gl::TEXTURE1);
+                gl::BindTexture(gl::TEXTURE_2D, 0);
+                gl::ActiveTexture(gl::TEXTURE0);
+                gl::BindTexture(gl::TEXTURE_2D, 0);
+                gl::UseProgram(0);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            }
+            target.frame()
+        }
+
+        /// レイヤーの中身を RGBA にしてテクスチャへ転送する
+        fn rasterize(fonts: &mut FontCache, state: &mut LayerState, layer: &OverlayLayer, inputs: &OverlayInputs, out_height: u32) {
+            let px = layer.size * out_height as f32;
+            let image = match &layer.content {
+                OverlayContent::Text { text } => {
+                    let text = expand_text(text, inputs);
+                    // 字幕の切れ目など、空の文字列では背景の帯も描かない
+                    if text.trim().is_empty() {
+                        state.width = 0;
+                        state.height = 0;
+                        return;
+                    }
+                    let Some(font) = fonts.get(layer.font.as_deref()) else { return };
+                    Some(super::render_text(font, &text, px, layer.color, layer.background))
+                }
+                OverlayContent::Image { path } => match load_image(path) {
+                    Ok((width, height, pixels)) => scale_image(width, height, pixels, px),
+                    Err(e) => {
+                        log::warn!("オーバーレイ画像の読み込みに失敗: {}", e);
+                        None
+                    }
+                },
+            };
+            let Some((width, height, pixels)) = image else { return };
+
+            unsafe {
+                if state.texture == 0 {
+                    gl::GenTextures(1, &mut state.texture);
+                }
+                gl::BindTexture(gl::TEXTURE_2D, state.texture);
+                gl::TexImage2D(
+                    gl::TEXTURE_2D, 0, gl::RGBA as _, width as _, height as _,
+                    0, gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_ptr() as *const _,
+                );
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as _);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as _);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as _);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as _);
+                gl::BindTexture(gl::TEXTURE_2D, 0);
+            }
+            state.width = width;
+            state.height = height;
+        }
+
+        pub fn destroy(mut self) {
+            for state in self.states.values_mut() {
+                state.delete();
+            }
+            self.layer_target.delete();
+            unsafe {
+                gl::DeleteProgram(self.program);
+                gl::DeleteProgram(self.composite);
+            }
+        }
+    }
+
+    /// テクスチャを作り直すべきかの判定に使う文字列
+    fn content_key(layer: &OverlayLayer, inputs: &OverlayInputs, out_height: u32) -> String {
+        let px = (layer.size * out_height as f32).round();
+        match &layer.content {
+            OverlayContent::Text { text } => format!(
+                "text:{}:{:?}:{:?}:{:?}:{}",
+                px, layer.font, layer.color, layer.background, expand_text(text, inputs)
+            ),
+            OverlayContent::Image { path } => format!("image:{}:{}", px, path),
+        }
+    }
+
+    /// 不透明度を visible の方向へ dt 秒分進める
+    fn step_opacity(opacity: f32, layer: &OverlayLayer, dt: f32) -> f32 {
+        let (target, duration) = if layer.visible { (1.0, layer.fade_in) } else { (0.0, layer.fade_out) };
+        if duration <= 0.0 {
+            return target;
+        }
+        let step = dt / duration;
+        if target > opacity {
+            (opacity + step).min(target)
+        } else {
+            (opacity - step).max(target)
+        }
+    }
+
+    /// レイヤーを置く矩形を NDC（左下・右上）で返す
+    fn layer_rect(layer: &OverlayLayer, width: u32, height: u32, out_width: u32, out_height: u32) -> [f32; 4] {
+        let (ax, ay) = layer.anchor.offset();
+        let w = width as f32 / out_width as f32;
+        let h = height as f32 / out_height as f32;
+        // 左上原点の正規化座標で左上の位置を求め、GL の NDC（下が -1）に変換する
+        let left = layer.x - w * ax;
+        let top = layer.y - h * ay;
+        [left * 2.0 - 1.0, 1.0 - (top + h) * 2.0, (left + w) * 2.0 - 1.0, 1.0 - top * 2.0]
+    }
+
+    /// 画像を高さ px に合わせて縮小・拡大する（アスペクト比は保つ）
+    fn scale_image(width: u32, height: u32, pixels: Vec<u8>, px: f32) -> Option<(u32, u32, Vec<u8>)> {
+        let target_height = px.round().max(1.0) as u32;
+        if target_height == height {
+            return Some((width, height, pixels));
+        }
+        let target_width = ((width as f32 * target_height as f32 / height as f32).round() as u32).max(1);
+        let image = image::RgbaImage::from_raw(width, height, pixels)?;
+        let resized = image::imageops::resize(&image, target_width, target_height, image::imageops::FilterType::Triangle);
+        Some((target_width, target_height, resized.into_raw()))
+    }
+}
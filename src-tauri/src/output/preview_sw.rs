@@ -6,6 +6,8 @@
 /// OpenGL/Metal を使わないシンプルな実装。
 
 use anyhow::Result;
+use crate::output::transform;
+use crate::output::OutputRegistry;
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
@@ -36,17 +38,19 @@ unsafe impl Send for SendableMpvHandle {}
 /// * `mpv_handle` - mpv 内部ハンドルの生ポインタ
 /// * `app_handle` - Tauri AppHandle（Event 送信用）
 /// * `width` / `height` - プレビュー解像度
+/// * `outputs` - 出力設定（GL を使わない代わりに映像変形を CPU で適用する）
 pub fn spawn(
     mpv_handle: *mut libmpv2_sys::mpv_handle,
     app_handle: AppHandle,
     width: u32,
     height: u32,
+    outputs: OutputRegistry,
 ) -> Result<PreviewHandle> {
     let (cmd_tx, cmd_rx) = mpsc::channel::<RenderCommand>();
     let sendable = SendableMpvHandle(mpv_handle);
 
     std::thread::spawn(move || {
-        if let Err(e) = render_loop_sw(sendable, app_handle, cmd_rx, width, height) {
+        if let Err(e) = render_loop_sw(sendable, app_handle, cmd_rx, width, height, outputs) {
             log::error!("SW レンダリングループでエラー: {}", e);
         }
     });
@@ -63,15 +67,14 @@ fn render_loop_sw(
     cmd_rx: mpsc::Receiver<RenderCommand>,
     width: u32,
     height: u32,
+    outputs: OutputRegistry,
 ) -> Result<()> {
-    use libmpv2::Mpv;
-
-    // mpv インスタンスを取得
-    let mpv = unsafe { Mpv::from_raw(libmpv2_sys::mpv_create_client(mpv_handle.0, std::ptr::null())) };
+    // mpv のクライアントを作る（ループを抜けたら破棄する）
+    let client = SwClient::new(mpv_handle.0)?;
 
     // Software Rendering を設定
-    mpv.set_property("vo", "null")?; // ビデオ出力を無効化（スクリーンショットで代用）
-    mpv.set_property("hwdec", "no")?; // ハードウェアデコードを無効化
+    client.set_property("vo", "null")?; // ビデオ出力を無効化（スクリーンショットで代用）
+    client.set_property("hwdec", "no")?; // ハードウェアデコードを無効化
 
     log::info!("SW レンダリング開始: {}x{}", width, height);
 
@@ -100,9 +103,14 @@ fn render_loop_sw(
             // 現時点では空のフレームを送信（実装の骨組みとして）
             pixels.fill(0);
 
+            // Syphon 出力の代替経路なので、Syphon と同じ映像変形を CPU で適用する
+            let settings = outputs.get("syphon");
+            let transform = settings.effective(None).transform;
+            let (frame, frame_width, frame_height) = transform::apply_cpu(&pixels, width, height, &transform);
+
             // Tauri Event で WebView に送信（base64 エンコード）
-            let b64 = base64_encode_pixels(&pixels);
-            let _ = app_handle.emit("preview-frame", PreviewFramePayload { data: b64 });
+            let b64 = base64_encode_pixels(&frame);
+            let _ = app_handle.emit("preview-frame", PreviewFramePayload { data: b64, width: frame_width, height: frame_height });
 
             last_emit = Instant::now();
         }
@@ -115,6 +123,35 @@ fn render_loop_sw(
     Ok(())
 }
 
+/// 描画スレッド専用の mpv クライアント
+struct SwClient(*mut libmpv2_sys::mpv_handle);
+
+impl SwClient {
+    fn new(core: *mut libmpv2_sys::mpv_handle) -> Result<Self> {
+        let client = unsafe { libmpv2_sys::mpv_create_client(core, std::ptr::null()) };
+        if client.is_null() {
+            return Err(anyhow::anyhow!("mpv クライアントの作成に失敗"));
+        }
+        Ok(Self(client))
+    }
+
+    fn set_property(&self, name: &str, value: &str) -> Result<()> {
+        let c_name = std::ffi::CString::new(name)?;
+        let c_value = std::ffi::CString::new(value)?;
+        let code = unsafe { libmpv2_sys::mpv_set_property_string(self.0, c_name.as_ptr(), c_value.as_ptr()) };
+        if code < 0 {
+            return Err(anyhow::anyhow!("mpv エラー: {} = {} の設定に失敗 ({})", name, value, code));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SwClient {
+    fn drop(&mut self) {
+        unsafe { libmpv2_sys::mpv_destroy(self.0) };
+    }
+}
+
 /// ピクセルデータを base64 エンコードする（WebView 転送用）
 fn base64_encode_pixels(pixels: &[u8]) -> String {
     use base64::Engine;
@@ -126,4 +163,7 @@ fn base64_encode_pixels(pixels: &[u8]) -> String {
 struct PreviewFramePayload {
     /// base64 エンコードされた RGBA ピクセルデータ
     data: String,
+    /// 変形後の解像度（回転・出力解像度の指定で元の解像度と変わる）
+    width: u32,
+    height: u32,
 }
@@ -0,0 +1,242 @@
+/// ユーザー定義の GLSL シェーダによるエフェクト（ぼかし・モザイク・万華鏡など）
+///
+/// ## シェーダの書き方
+/// `#version` で始まらないファイルには次の宣言を先頭に補うので、`main()` だけ書けばよい。
+///
+/// ```glsl
+/// #version 150
+/// in vec2 v_uv;                // テクスチャ座標（0.0–1.0、左下原点）
+/// out vec4 frag_color;         // 出力色
+/// uniform sampler2D u_texture; // 前段までの映像
+/// uniform float u_time;        // 出力開始からの秒数
+/// uniform vec2 u_resolution;   // 出力解像度（ピクセル）
+/// uniform float u_audio_level; // 音声の RMS レベル（0.0–1.0）
+/// ```
+///
+/// `#version` で始まるファイルはそのまま使う（uniform は同じ名前で宣言すれば値が入る）。
+/// mpv の `glsl-shaders`（フック形式）には対応しない。
+///
+/// ## ホットリロード
+/// ファイルの更新日時を定期的に確認し、変わっていれば再コンパイルする。
+/// コンパイルに失敗した場合はログに出し、直前のプログラムを使い続ける。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// `#version` が無いシェーダの先頭に補う宣言
+pub const SHADER_PRELUDE: &str = "#version 150
+in vec2 v_uv;
+out vec4 frag_color;
+uniform sampler2D u_texture;
+uniform float u_time;
+uniform vec2 u_resolution;
+uniform float u_audio_level;
+#line 1
+";
+
+/// 出力に適用するシェーダ 1 つ分の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShaderEffect {
+    /// フラグメントシェーダのファイルパス
+    pub path: PathBuf,
+    /// false の場合は読み込んだまま適用を止める
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ShaderEffect {
+    /// ファイルを読めるか確認する（コンパイルは出力スレッドで行う）
+    pub fn validate(&self) -> Result<()> {
+        read_source(&self.path).map(|_| ())
+    }
+}
+
+/// シェーダのソースを読み込み、必要なら宣言を補う
+pub fn read_source(path: &std::path::Path) -> Result<String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("シェーダを読み込めません ({}): {}", path.display(), e))?;
+    if source.trim_start().starts_with("#version") {
+        Ok(source)
+    } else {
+        Ok(format!("{}{}", SHADER_PRELUDE, source))
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub use gpu::{ShaderInputs, ShaderPass};
+
+#[cfg(target_os = "macos")]
+mod gpu {
+    use super::ShaderEffect;
+    use crate::output::gl_util::{self, Frame, FullscreenQuad, RenderTarget};
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant, SystemTime};
+
+    /// ファイルの更新を確認する間隔
+    const RELOAD_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// 毎フレーム変わる uniform の値
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct ShaderInputs {
+        pub time: f32,
+        pub audio_level: f32,
+    }
+
+    /// コンパイル済みのシェーダ
+    struct LoadedShader {
+        path: PathBuf,
+        enabled: bool,
+        /// 最後に読み込んだときの更新日時
+        modified: Option<SystemTime>,
+        /// コンパイルに一度も成功していない場合は None
+        program: Option<gl::types::GLuint>,
+        u_texture: gl::types::GLint,
+        u_time: gl::types::GLint,
+        u_resolution: gl::types::GLint,
+        u_audio_level: gl::types::GLint,
+    }
+
+    impl LoadedShader {
+        fn new(effect: &ShaderEffect) -> Self {
+            let mut shader = Self {
+                path: effect.path.clone(),
+                enabled: effect.enabled,
+                modified: None,
+                program: None,
+                u_texture: -1,
+                u_time: -1,
+                u_resolution: -1,
+                u_audio_level: -1,
+            };
+            shader.reload();
+            shader
+        }
+
+        /// 更新日時が変わっていれば再コンパイルする
+        fn reload_if_modified(&mut self) {
+            let modified = std::fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+            if modified.is_some() && modified != self.modified {
+                log::info!("シェーダの変更を検知しました: {}", self.path.display());
+                self.reload();
+            }
+        }
+
+        fn reload(&mut self) {
+            self.modified = std::fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+            let program = super::read_source(&self.path)
+                .and_then(|source| gl_util::compile_program(gl_util::FULLSCREEN_VERTEX_SHADER, &source));
+            match program {
+                Ok(program) => {
+                    self.delete();
+                    self.u_texture = gl_util::uniform_location(program, "u_texture");
+                    self.u_time = gl_util::uniform_location(program, "u_time");
+                    self.u_resolution = gl_util::uniform_location(program, "u_resolution");
+                    self.u_audio_level = gl_util::uniform_location(program, "u_audio_level");
+                    self.program = Some(program);
+                    log::info!("シェーダを読み込みました: {}", self.path.display());
+                }
+                // 直前のプログラムがあればそのまま使い続ける
+                Err(e) => log::error!("シェーダの読み込みに失敗 ({}): {}", self.path.display(), e),
+            }
+        }
+
+        fn delete(&mut self) {
+            if let Some(program) = self.program.take() {
+                unsafe { gl::DeleteProgram(program) };
+            }
+        }
+    }
+
+    /// 設定されたシェーダを順に適用するパス
+    pub struct ShaderPass {
+        shaders: Vec<LoadedShader>,
+        last_check: Instant,
+    }
+
+    impl Default for ShaderPass {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl ShaderPass {
+        pub fn new() -> Self {
+            Self { shaders: Vec::new(), last_check: Instant::now() }
+        }
+
+        /// 設定と読み込み済みのシェーダを揃え、変更されたファイルを読み直す
+        pub fn update(&mut self, effects: &[ShaderEffect]) {
+            let same_files = self.shaders.len() == effects.len()
+                && self.shaders.iter().zip(effects).all(|(shader, effect)| shader.path == effect.path);
+            if !same_files {
+                for shader in self.shaders.iter_mut() {
+                    shader.delete();
+                }
+                self.shaders = effects.iter().map(LoadedShader::new).collect();
+                self.last_check = Instant::now();
+                return;
+            }
+
+            for (shader, effect) in self.shaders.iter_mut().zip(effects) {
+                shader.enabled = effect.enabled;
+            }
+            if self.last_check.elapsed() >= RELOAD_CHECK_INTERVAL {
+                self.last_check = Instant::now();
+                for shader in self.shaders.iter_mut() {
+                    shader.reload_if_modified();
+                }
+            }
+        }
+
+        pub fn len(&self) -> usize {
+            self.shaders.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.shaders.is_empty()
+        }
+
+        /// index 番目のシェーダが有効で、コンパイル済みか
+        pub fn is_ready(&self, index: usize) -> bool {
+            self.shaders.get(index).is_some_and(|s| s.enabled && s.program.is_some())
+        }
+
+        /// index 番目のシェーダで input を target に描画する（is_ready() を確認してから呼ぶ）
+        pub fn draw(&self, index: usize, quad: &FullscreenQuad, input: Frame, target: &mut RenderTarget, inputs: ShaderInputs) -> Frame {
+            let shader = &self.shaders[index];
+            let Some(program) = shader.program else { return input };
+            target.resize(input.width, input.height);
+
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+                gl::Viewport(0, 0, input.width as i32, input.height as i32);
+                gl::Disable(gl::BLEND);
+                gl::UseProgram(program);
+
+                gl::ActiveTexture(gl::TEXTURE0);
+                gl::BindTexture(gl::TEXTURE_2D, input.texture);
+                gl::Uniform1i(shader.u_texture, 0);
+                gl::Uniform1f(shader.u_time, inputs.time);
+                gl::Uniform2f(shader.u_resolution, input.width as f32, input.height as f32);
+                gl::Uniform1f(shader.u_audio_level, inputs.audio_level);
+
+                quad.draw();
+
+                gl::BindTexture(gl::TEXTURE_2D, 0);
+                gl::UseProgram(0);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            }
+            target.frame()
+        }
+
+        pub fn destroy(mut self) {
+            for shader in self.shaders.iter_mut() {
+                shader.delete();
+            }
+        }
+    }
+}
@@ -0,0 +1,426 @@
+/// スタンバイ（待機画面）出力
+///
+/// 読み込み中・エラー時・クリップの切り替え中など、mpv の映像が無い間に
+/// 受信側へ送る代替映像を出力ごとに設定する。
+///
+/// ## ソースの種類
+/// - `SolidColor`: 単色
+/// - `Image`: PNG / JPEG のスレート画像（アスペクト比を保って中央に配置）
+/// - `Clip`: ローカル動画のループ再生（専用の mpv インスタンスで描画。スタンバイの表示中だけ再生する）
+/// - `HoldLastFrame`: 最後に描画できたフレームを出し続ける
+///
+/// ## 表示の切り替え
+/// `StandbyGate` が `PlayerEvent` を見て表示/非表示を決める。
+/// START_FILE・END_FILE・IDLE・出力エラー・復旧待ちで表示し、PLAYBACK_RESTART で本編に戻す。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::player::events::{PlayerEvent, RecoveryPhase};
+
+/// スタンバイ中に送る映像
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum StandbySource {
+    SolidColor { r: u8, g: u8, b: u8 },
+    Image { path: String },
+    Clip { path: String },
+    HoldLastFrame,
+}
+
+impl Default for StandbySource {
+    fn default() -> Self {
+        StandbySource::SolidColor { r: 0, g: 0, b: 0 }
+    }
+}
+
+impl StandbySource {
+    /// 設定前にソースが使えるか確認する（画像は実際にデコードしてみる）
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            StandbySource::Image { path } => {
+                load_image(path)?;
+            }
+            StandbySource::Clip { path } => {
+                if !Path::new(path).is_file() {
+                    return Err(anyhow::anyhow!("クリップが見つかりません: {}", path));
+                }
+            }
+            StandbySource::SolidColor { .. } | StandbySource::HoldLastFrame => {}
+        }
+        Ok(())
+    }
+}
+
+/// 画像を RGBA8 で読み込む（戻り値: 幅, 高さ, ピクセル）
+pub fn load_image(path: &str) -> Result<(u32, u32, Vec<u8>)> {
+    let image = image::open(path)
+        .map_err(|e| anyhow::anyhow!("画像を読み込めません ({}): {}", path, e))?
+        .to_rgba8();
+    let (width, height) = image.dimensions();
+    Ok((width, height, image.into_raw()))
+}
+
+// ─── 表示の切り替え ──────────────────────────────────────────────────────────
+
+/// プレイヤーのイベントと描画状況からスタンバイを表示すべきかを判定する
+#[derive(Debug)]
+pub struct StandbyGate {
+    /// 本編の映像がまだ無い（読み込み中・終了後・エラー・復旧待ち）
+    waiting: bool,
+    /// mpv の描画が連続して失敗している
+    render_failed: bool,
+}
+
+impl Default for StandbyGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StandbyGate {
+    /// 開始直後は読み込み中なのでスタンバイを表示する
+    pub fn new() -> Self {
+        Self { waiting: true, render_failed: false }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.waiting || self.render_failed
+    }
+
+    /// イベントを適用する。表示状態が変化した場合は true を返す
+    pub fn on_event(&mut self, event: &PlayerEvent) -> bool {
+        let waiting = match event {
+            PlayerEvent::StartFile
+            | PlayerEvent::EndFile { .. }
+            | PlayerEvent::Idle
+            | PlayerEvent::OutputError { .. } => true,
+            PlayerEvent::Recovery { phase: RecoveryPhase::Scheduled | RecoveryPhase::GaveUp, .. } => true,
+            // 最初のフレームの準備ができた / 再読み込み後に再開した
+            PlayerEvent::PlaybackRestart => false,
+            _ => return false,
+        };
+        let was_active = self.is_active();
+        self.waiting = waiting;
+        self.log_change(was_active)
+    }
+
+    /// mpv の描画が失敗し続けている / 回復したことを反映する
+    pub fn set_render_failed(&mut self, failed: bool) -> bool {
+        let was_active = self.is_active();
+        self.render_failed = failed;
+        self.log_change(was_active)
+    }
+
+    fn log_change(&self, was_active: bool) -> bool {
+        let changed = was_active != self.is_active();
+        if changed {
+            log::info!("スタンバイ表示: {}", if self.is_active() { "開始" } else { "終了" });
+        }
+        changed
+    }
+}
+
+// ─── GL 描画 ─────────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "macos")]
+pub use renderer::StandbyRenderer;
+
+#[cfg(target_os = "macos")]
+mod renderer {
+    use super::{load_image, StandbySource};
+    use crate::output::gl_util::Frame;
+    use anyhow::Result;
+    use libmpv2::render::{OpenGLInitParams, RenderContext, RenderParam, RenderParamApiType};
+    use libmpv2::Mpv;
+
+    /// GL 関数ポインタの解決関数（mpv の OpenGLInitParams と同じ形）
+    pub type GetProcAddress = fn(&*const std::ffi::c_void, &str) -> *mut std::ffi::c_void;
+
+    /// スタンバイ映像を描画する
+    ///
+    /// 出力スレッドの GL コンテキストが current の状態で呼ぶこと。
+    /// GL リソースを持つため、破棄時は `destroy()` を明示的に呼ぶ。
+    pub struct StandbyRenderer {
+        source: StandbySource,
+        get_proc_address: GetProcAddress,
+        width: u32,
+        height: u32,
+        /// スタンバイ映像の描画先
+        fbo: gl::types::GLuint,
+        texture: gl::types::GLuint,
+        /// HoldLastFrame 用に本編の最終フレームを保持する
+        hold_fbo: gl::types::GLuint,
+        hold_texture: gl::types::GLuint,
+        has_held_frame: bool,
+        /// Image 用の画像テクスチャ（fbo, texture, 幅, 高さ）
+        image: Option<(gl::types::GLuint, gl::types::GLuint, u32, u32)>,
+        clip: Option<ClipPlayer>,
+        /// スタンバイを表示しているか（クリップは表示している間だけ再生する）
+        shown: bool,
+    }
+
+    impl StandbyRenderer {
+        pub fn new(source: StandbySource, width: u32, height: u32, get_proc_address: GetProcAddress) -> Self {
+            let mut renderer = Self {
+                source: StandbySource::default(),
+                get_proc_address,
+                width: 0,
+                height: 0,
+                fbo: 0,
+                texture: 0,
+                hold_fbo: 0,
+                hold_texture: 0,
+                has_held_frame: false,
+                image: None,
+                clip: None,
+                shown: false,
+            };
+            renderer.resize(width, height);
+            renderer.set_source(source);
+            renderer
+        }
+
+        /// ソースを切り替える。読み込みに失敗した場合は黒にフォールバックする
+        pub fn set_source(&mut self, source: StandbySource) {
+            self.release_source();
+
+            let loaded = match &source {
+                StandbySource::Image { path } => self.load_image(path),
+                StandbySource::Clip { path } => ClipPlayer::new(path, self.get_proc_address, !self.shown).map(|clip| {
+                    self.clip = Some(clip);
+                }),
+                StandbySource::SolidColor { .. } | StandbySource::HoldLastFrame => Ok(()),
+            };
+            match loaded {
+                Ok(()) => self.source = source,
+                Err(e) => {
+                    log::warn!("スタンバイソースを読み込めません（黒で代替）: {}", e);
+                    self.source = StandbySource::default();
+                }
+            }
+        }
+
+        /// 出力解像度に合わせて描画先を作り直す
+        pub fn resize(&mut self, width: u32, height: u32) {
+            if width == self.width && height == self.height {
+                return;
+            }
+            unsafe {
+                delete_target(self.fbo, self.texture);
+                delete_target(self.hold_fbo, self.hold_texture);
+            }
+            (self.fbo, self.texture) = create_target(width, height);
+            (self.hold_fbo, self.hold_texture) = create_target(width, height);
+            self.has_held_frame = false;
+            self.width = width;
+            self.height = height;
+        }
+
+        /// スタンバイを表示しているかを伝える（本編の再生中はクリップを一時停止してデコードを止める）
+        pub fn set_shown(&mut self, shown: bool) {
+            if shown == self.shown {
+                return;
+            }
+            self.shown = shown;
+            if let Some(clip) = &self.clip {
+                clip.set_paused(!shown);
+            }
+        }
+
+        /// 本編のフレームを保持する（HoldLastFrame のときのみコピーする）
+        pub fn capture(&mut self, src_fbo: gl::types::GLuint) {
+            if self.source != StandbySource::HoldLastFrame {
+                return;
+            }
+            unsafe {
+                blit(src_fbo, self.hold_fbo, (self.width, self.height), (0, 0, self.width as i32, self.height as i32));
+            }
+            self.has_held_frame = true;
+        }
+
+        /// スタンバイ映像を描画し、公開するフレームを返す
+        pub fn render(&mut self) -> Frame {
+            let (width, height) = (self.width, self.height);
+            unsafe {
+                match &self.source {
+                    StandbySource::SolidColor { r, g, b } => {
+                        clear(self.fbo, width, height, (*r, *g, *b));
+                    }
+                    StandbySource::Image { .. } => {
+                        clear(self.fbo, width, height, (0, 0, 0));
+                        if let Some((image_fbo, _, image_w, image_h)) = self.image {
+                            // 画像は上の行から並んでいるため、上下を反転して mpv の出力と向きを揃える
+                            let (x0, y0, x1, y1) = fit_rect(image_w, image_h, width, height);
+                            blit(image_fbo, self.fbo, (image_w, image_h), (x0, y1, x1, y0));
+                        }
+                    }
+                    StandbySource::Clip { .. } => {
+                        let rendered = self.clip.as_ref()
+                            .map(|clip| clip.render(self.fbo, width, height))
+                            .unwrap_or(false);
+                        if !rendered {
+                            clear(self.fbo, width, height, (0, 0, 0));
+                        }
+                    }
+                    StandbySource::HoldLastFrame => {
+                        if self.has_held_frame {
+                            return Frame { fbo: self.hold_fbo, texture: self.hold_texture, width, height };
+                        }
+                        clear(self.fbo, width, height, (0, 0, 0));
+                    }
+                }
+            }
+            Frame { fbo: self.fbo, texture: self.texture, width, height }
+        }
+
+        /// GL リソースとクリップ用 mpv を解放する
+        pub fn destroy(mut self) {
+            self.release_source();
+            unsafe {
+                delete_target(self.fbo, self.texture);
+                delete_target(self.hold_fbo, self.hold_texture);
+            }
+        }
+
+        fn load_image(&mut self, path: &str) -> Result<()> {
+            let (width, height, pixels) = load_image(path)?;
+            let (fbo, texture) = create_target(width, height);
+            unsafe {
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+                gl::TexSubImage2D(
+                    gl::TEXTURE_2D, 0, 0, 0, width as _, height as _,
+                    gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_ptr() as *const _,
+                );
+                gl::BindTexture(gl::TEXTURE_2D, 0);
+            }
+            log::info!("スタンバイ画像を読み込みました: {} ({}x{})", path, width, height);
+            self.image = Some((fbo, texture, width, height));
+            Ok(())
+        }
+
+        fn release_source(&mut self) {
+            if let Some((fbo, texture, _, _)) = self.image.take() {
+                unsafe { delete_target(fbo, texture) };
+            }
+            // RenderContext → mpv の順に破棄される
+            self.clip = None;
+        }
+    }
+
+    /// スタンバイ用クリップをループ再生する mpv インスタンス
+    struct ClipPlayer {
+        // フィールドは宣言順に破棄されるため、RenderContext を mpv より先に置く
+        render_ctx: RenderContext,
+        mpv: Mpv,
+    }
+
+    impl ClipPlayer {
+        fn new(path: &str, get_proc_address: GetProcAddress, paused: bool) -> Result<Self> {
+            let mpv_err = |e: libmpv2::Error| anyhow::anyhow!("mpv エラー: {:?}", e);
+
+            let mpv = Mpv::new().map_err(mpv_err)?;
+            mpv.set_property("vo", "libmpv").map_err(mpv_err)?;
+            mpv.set_property("hwdec", "auto-safe").map_err(mpv_err)?;
+            mpv.set_property("loop-file", "inf").map_err(mpv_err)?;
+            // 本編の音声と混ざらないよう音声は無効にする
+            mpv.set_property("audio", "no").map_err(mpv_err)?;
+            mpv.set_property("pause", paused).map_err(mpv_err)?;
+
+            // イベントは誰も読まないため購読しない（キューが溢れるのを防ぐ）
+            unsafe {
+                let handle = mpv.ctx.as_ptr();
+                for id in libmpv2_sys::mpv_event_id_MPV_EVENT_LOG_MESSAGE..=libmpv2_sys::mpv_event_id_MPV_EVENT_HOOK {
+                    libmpv2_sys::mpv_request_event(handle, id, 0);
+                }
+            }
+
+            let render_ctx = unsafe {
+                RenderContext::new(
+                    &mut *mpv.ctx.as_ptr(),
+                    [
+                        RenderParam::ApiType(RenderParamApiType::OpenGl),
+                        RenderParam::InitParams(OpenGLInitParams {
+                            get_proc_address,
+                            ctx: std::ptr::null(),
+                        }),
+                    ],
+                )
+                .map_err(|e| anyhow::anyhow!("クリップ用 RenderContext の作成に失敗: {:?}", e))?
+            };
+
+            mpv.command("loadfile", &[path, "replace"]).map_err(mpv_err)?;
+            log::info!("スタンバイクリップを再生します: {}", path);
+
+            Ok(Self { render_ctx, mpv })
+        }
+
+        fn set_paused(&self, paused: bool) {
+            if let Err(e) = self.mpv.set_property("pause", paused) {
+                log::warn!("スタンバイクリップの{}に失敗: {:?}", if paused { "一時停止" } else { "再開" }, e);
+            }
+        }
+
+        fn render(&self, fbo: gl::types::GLuint, width: u32, height: u32) -> bool {
+            self.render_ctx.render::<()>(fbo as i32, width as i32, height as i32, true).is_ok()
+        }
+    }
+
+    fn create_target(width: u32, height: u32) -> (gl::types::GLuint, gl::types::GLuint) {
+        let (mut fbo, mut texture) = (0, 0);
+        unsafe {
+            gl::GenTextures(1, &mut texture);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D, 0, gl::RGBA as _, width as _, height as _,
+                0, gl::RGBA, gl::UNSIGNED_BYTE, std::ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as _);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as _);
+            gl::GenFramebuffers(1, &mut fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, texture, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        (fbo, texture)
+    }
+
+    unsafe fn delete_target(fbo: gl::types::GLuint, texture: gl::types::GLuint) {
+        if fbo != 0 {
+            gl::DeleteFramebuffers(1, &fbo);
+        }
+        if texture != 0 {
+            gl::DeleteTextures(1, &texture);
+        }
+    }
+
+    unsafe fn clear(fbo: gl::types::GLuint, width: u32, height: u32, (r, g, b): (u8, u8, u8)) {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+        gl::Viewport(0, 0, width as i32, height as i32);
+        gl::ClearColor(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+    }
+
+    /// src の全体を dst の矩形 (x0, y0, x1, y1) にコピーする（座標を入れ替えると反転）
+    unsafe fn blit(src: gl::types::GLuint, dst: gl::types::GLuint, (src_w, src_h): (u32, u32), (x0, y0, x1, y1): (i32, i32, i32, i32)) {
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, src);
+        gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, dst);
+        gl::BlitFramebuffer(
+            0, 0, src_w as _, src_h as _,
+            x0, y0, x1, y1,
+            gl::COLOR_BUFFER_BIT,
+            gl::LINEAR,
+        );
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+    }
+
+    /// アスペクト比を保ったまま出力の中央に収める矩形
+    fn fit_rect(src_w: u32, src_h: u32, dst_w: u32, dst_h: u32) -> (i32, i32, i32, i32) {
+        let scale = (dst_w as f32 / src_w as f32).min(dst_h as f32 / src_h as f32);
+        let (w, h) = ((src_w as f32 * scale) as i32, (src_h as f32 * scale) as i32);
+        let (x, y) = ((dst_w as i32 - w) / 2, (dst_h as i32 - h) / 2);
+        (x, y, x + w, y + h)
+    }
+}
@@ -4,15 +4,29 @@
 /// 1. mpv を OpenGL でレンダリング（FBO にテクスチャを描画）
 /// 2. Syphon Server を作成してテクスチャ ID を共有
 /// 3. TouchDesigner / VDMX などの Syphon Client で受信
+///
+/// ## サーバーの寿命
+/// サーバーはアプリの起動時に作成し、アプリの終了時か出力を無効にしたときにだけ停止する。
+/// 再生のたびに mpv へ接続（`attach`）・切り離し（`detach`）し、
+/// 停止中（Idle）もスタンバイ映像を送り続けるため、受信側の接続は切れない。
 
 use anyhow::Result;
+use crate::output::autocrop::{self, AutoCropper, CropMode, CropSampler};
+use crate::output::chain::VideoChain;
+use crate::output::gl_util::Frame;
+use crate::output::overlay::OverlayInputs;
+use crate::output::shader::ShaderInputs;
+use crate::output::standby::{StandbyGate, StandbyRenderer};
+use crate::output::{OutputRegistry, OutputStats};
+use crate::player::events::{EventBus, PlayerEvent, PropertyChange};
+use crate::player::meter;
 use libmpv2::render::{OpenGLInitParams, RenderContext, RenderParam, RenderParamApiType};
 use objc2::rc::Retained;
 use objc2::runtime::AnyObject;
 use objc2::{msg_send, Encode, Encoding};
 use objc2_foundation::NSString;
-use std::sync::mpsc;
-use std::time::Duration;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
 use tauri::Emitter;
 
 // ─── macOS ネイティブ API の FFI 宣言 ──────────────────────────────────────
@@ -128,11 +142,28 @@ fn load_syphon_framework() -> Result<()> {
 #[link(name = "Syphon", kind = "framework")]
 extern "C" {}
 
+/// 出力設定（OutputRegistry）で使う出力名
+const OUTPUT_NAME: &str = "syphon";
+
+/// 黒帯検出のために読み戻す間隔（フレーム数）
+const CROP_SAMPLE_INTERVAL: u64 = 15;
+
 /// レンダリングスレッドへの制御コマンド
 pub enum SyphonCommand {
+    /// mpv に接続する（RenderContext を作成し、結果を返す）
+    Attach(SyphonSession, mpsc::Sender<Result<()>>),
+    /// mpv から切り離す（RenderContext を破棄してから応答する。以後はスタンバイ映像を送る）
+    Detach(mpsc::Sender<()>),
+    /// Syphon Server を停止してスレッドを終了する
     Stop,
 }
 
+/// 出力に接続する再生セッション
+pub struct SyphonSession {
+    mpv_handle: SendableMpvHandle,
+    stats: Arc<OutputStats>,
+}
+
 /// Syphon 出力ハンドル
 pub struct SyphonHandle {
     pub cmd_tx: mpsc::Sender<SyphonCommand>,
@@ -140,11 +171,41 @@ pub struct SyphonHandle {
 }
 
 impl SyphonHandle {
+    /// mpv に接続する（RenderContext の作成を待つ。loadfile はこの後に実行する）
+    ///
+    /// # 引数
+    /// * `mpv_handle` - mpv 内部ハンドルの生ポインタ（切り離すまで破棄しないこと）
+    /// * `stats` - 出力フレーム数などの稼働状況（ウォッチドッグが参照する）
+    pub fn attach(&self, mpv_handle: *mut libmpv2_sys::mpv_handle, stats: Arc<OutputStats>) -> Result<()> {
+        let (done_tx, done_rx) = mpsc::channel();
+        let session = SyphonSession { mpv_handle: SendableMpvHandle(mpv_handle), stats };
+        self.cmd_tx
+            .send(SyphonCommand::Attach(session, done_tx))
+            .map_err(|_| anyhow::anyhow!("Syphon スレッドが終了しています"))?;
+        done_rx
+            .recv()
+            .map_err(|_| anyhow::anyhow!("Syphon スレッドが終了しています"))?
+    }
+
+    /// mpv から切り離す（mpv を破棄する前に呼ぶ。Syphon Server とスタンバイ映像はそのまま）
+    pub fn detach(&self) {
+        let (done_tx, done_rx) = mpsc::channel();
+        if self.cmd_tx.send(SyphonCommand::Detach(done_tx)).is_ok() {
+            // スレッドが終了していれば RenderContext も破棄済み
+            let _ = done_rx.recv();
+        }
+    }
+
+    /// レンダリングスレッドが終了しているか（起動時のエラーなど）
+    pub fn is_finished(&self) -> bool {
+        self.thread_handle.as_ref().map_or(true, |handle| handle.is_finished())
+    }
+
+    /// Syphon Server を停止し、スレッドの終了を待つ（アプリ終了時・出力の無効化時）
     pub fn stop(mut self) {
         // 停止コマンドを送信
         let _ = self.cmd_tx.send(SyphonCommand::Stop);
 
-        // スレッドの終了を待つ（最大3秒）
         if let Some(handle) = self.thread_handle.take() {
             log::info!("Syphon スレッドの終了を待機中...");
             let _ = handle.join();
@@ -157,33 +218,45 @@ impl SyphonHandle {
 struct SendableMpvHandle(*mut libmpv2_sys::mpv_handle);
 unsafe impl Send for SendableMpvHandle {}
 
+/// 接続中の mpv の RenderContext
+struct Attached {
+    render_ctx: RenderContext,
+    mpv_handle: *mut libmpv2_sys::mpv_handle,
+}
+
 /// Syphon 出力を別スレッドで起動する
 ///
+/// 再生していない間もサーバーは残り、スタンバイ映像を送り続ける。
+/// 再生するときは `SyphonHandle::attach` で mpv に接続する。
+///
 /// # 引数
-/// * `mpv_handle` - mpv 内部ハンドルの生ポインタ（loadfile 未実行）
 /// * `server_name` - Syphon サーバー名（TouchDesigner で識別用）
-/// * `url` - 再生する URL（RenderContext 作成後に loadfile を実行）
 /// * `width` / `height` - 初期出力解像度（動画ロード後に実際の解像度に調整される）
 /// * `app_handle` - Tauri AppHandle（プレビュー用、None の場合はプレビュー無効）
+/// * `events` - イベントバス（解像度変更の検知と、出力エラーの通知に使う）
+/// * `outputs` - 出力設定（スタンバイ映像・変形など。"syphon" のエントリを使う）
 pub fn spawn(
-    mpv_handle: *mut libmpv2_sys::mpv_handle,
     server_name: &str,
-    url: &str,
     width: u32,
     height: u32,
     app_handle: Option<tauri::AppHandle>,
-    is_playing: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    events: EventBus,
+    outputs: OutputRegistry,
 ) -> Result<SyphonHandle> {
     let (cmd_tx, cmd_rx) = mpsc::channel::<SyphonCommand>();
-    let sendable = SendableMpvHandle(mpv_handle);
+    // 接続より前に購読しておく（loadfile 直後の解像度の変更通知を取りこぼさないため）
+    let events_rx = events.subscribe();
     let server_name = server_name.to_string();
-    let url = url.to_string();
 
     let thread_handle = std::thread::spawn(move || {
         println!("=== Syphon thread started ===");
-        if let Err(e) = syphon_loop(sendable, &server_name, &url, cmd_rx, width, height, app_handle, is_playing) {
+        if let Err(e) = syphon_loop(&server_name, cmd_rx, width, height, app_handle, events_rx, outputs) {
             println!("!!! Syphon レンダリングループでエラー: {}", e);
             log::error!("Syphon レンダリングループでエラー: {}", e);
+            events.inject(PlayerEvent::OutputError {
+                output: OUTPUT_NAME.to_string(),
+                message: e.to_string(),
+            });
         }
         println!("=== Syphon thread finished ===");
     });
@@ -194,181 +267,55 @@ pub fn spawn(
     })
 }
 
+/// mpv の RenderContext を作成する（GL コンテキストを有効にしてから呼ぶ）
+unsafe fn create_render_context(mpv_handle: *mut libmpv2_sys::mpv_handle, gl_ctx: &CGLContextObj) -> Result<RenderContext> {
+    log::info!("RenderContext を作成します (mpv_handle: {:?})", mpv_handle);
+    let ctx_ptr = gl_ctx as *const _ as *const std::ffi::c_void;
+    let render_ctx = RenderContext::new(
+        &mut *mpv_handle,
+        [
+            RenderParam::ApiType(RenderParamApiType::OpenGl),
+            RenderParam::InitParams(OpenGLInitParams {
+                get_proc_address: get_proc_addr,
+                ctx: ctx_ptr,
+            }),
+        ],
+    )
+    .map_err(|e| anyhow::anyhow!("RenderContext の作成に失敗: {:?}", e))?;
+    log::info!("RenderContext を作成しました");
+    Ok(render_ctx)
+}
+
 /// Syphon レンダリングループ
 ///
-/// CGL コンテキストで mpv → FBO → Syphon Server → プレビュー送信
+/// CGL コンテキストで mpv → FBO → Syphon Server → プレビュー送信。
+/// mpv に接続していない間はスタンバイ映像を送る。
 fn syphon_loop(
-    sendable_handle: SendableMpvHandle,
     server_name: &str,
-    url: &str,
     cmd_rx: mpsc::Receiver<SyphonCommand>,
     initial_width: u32,
     initial_height: u32,
     app_handle: Option<tauri::AppHandle>,  // プレビュー機能用
-    is_playing: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    events: mpsc::Receiver<PlayerEvent>,
+    outputs: OutputRegistry,
 ) -> Result<()> {
-    println!("=== syphon_loop started: {} ===", url);
-
-    // 既存の mpv ハンドルを取得
-    let mpv_handle = sendable_handle.0;
-    println!("Using existing mpv handle: {:?}", mpv_handle);
+    println!("=== syphon_loop started: {} ===", server_name);
 
     // CGL コンテキストを作成
     println!("Creating CGL context...");
     let gl_ctx = create_cgl_context()?;
     println!("CGL context created: {:?}", gl_ctx);
+    unsafe { CGLSetCurrentContext(gl_ctx) };
 
-    // RenderContext を作成
-    let render_ctx = unsafe {
-        println!("Setting CGL context...");
-        CGLSetCurrentContext(gl_ctx);
-
-        println!("Creating RenderContext...");
-        log::info!("RenderContext を作成します...");
-        log::info!("GL コンテキスト: {:?}", gl_ctx);
-
-        fn get_proc_addr(_ctx: &*const std::ffi::c_void, name: &str) -> *mut std::ffi::c_void {
-            unsafe {
-                let name_cstr = std::ffi::CString::new(name).unwrap();
-                dlsym(RTLD_DEFAULT, name_cstr.as_ptr())
-            }
-        }
-
-        let ctx_ptr = &gl_ctx as *const _ as *const std::ffi::c_void;
-        println!("Calling RenderContext::new...");
-        log::info!("RenderContext::new を呼び出します (mpv_handle: {:?}, ctx_ptr: {:?})", mpv_handle, ctx_ptr);
-
-        let render_ctx = RenderContext::new(
-            &mut *mpv_handle,
-            [
-                RenderParam::ApiType(RenderParamApiType::OpenGl),
-                RenderParam::InitParams(OpenGLInitParams {
-                    get_proc_address: get_proc_addr,
-                    ctx: ctx_ptr,
-                }),
-            ],
-        )
-        .map_err(|e| anyhow::anyhow!("RenderContext の作成に失敗: {:?}", e))?;
-
-        println!("RenderContext created successfully");
-        log::info!("RenderContext を作成しました");
-
-        render_ctx
-    };
-
-    // RenderContext 作成後に loadfile を実行
-    println!("Executing loadfile command...");
-    unsafe {
-        let loadfile_cstr = std::ffi::CString::new("loadfile").unwrap();
-        let url_cstr = std::ffi::CString::new(url).unwrap();
-        let replace_cstr = std::ffi::CString::new("replace").unwrap();
-        let mut args: Vec<*const std::ffi::c_char> = vec![
-            loadfile_cstr.as_ptr(),
-            url_cstr.as_ptr(),
-            replace_cstr.as_ptr(),
-            std::ptr::null(),
-        ];
-        let ret = libmpv2_sys::mpv_command(mpv_handle, args.as_mut_ptr());
-        if ret < 0 {
-            println!("loadfile command failed: {}", ret);
-            return Err(anyhow::anyhow!("loadfile コマンドに失敗: {} (エラーコード: {})", url, ret));
-        } else {
-            println!("loadfile command executed successfully");
-            log::info!("loadfile コマンドを実行: {}", url);
-        }
-    }
-
-    // observe_property で width/height の変更通知を登録する
-    // （video-params/w は vo=libmpv では取れないが、watch 経由なら取れる可能性がある）
-    unsafe {
-        const MPV_FORMAT_INT64: u32 = 4;
-        let width_cstr  = std::ffi::CString::new("width").unwrap();
-        let height_cstr = std::ffi::CString::new("height").unwrap();
-        libmpv2_sys::mpv_observe_property(mpv_handle, 1, width_cstr.as_ptr(), MPV_FORMAT_INT64);
-        libmpv2_sys::mpv_observe_property(mpv_handle, 2, height_cstr.as_ptr(), MPV_FORMAT_INT64);
-        println!("Registered observe_property for width/height");
-    }
-
-    // PROPERTY_CHANGE (id=22) または VIDEO_RECONFIG (id=17) が来るまで待つ
-    // observe_property で幅・高さを監視し、解像度が確定したら FBO を作成する。
-    // タイムアウト時は initial_width/height にフォールバック。
-    println!("Waiting for resolution via observe_property...");
-    let (mut observed_width, mut observed_height) = (0i64, 0i64);
-    unsafe {
-        // 正しいイベント ID（libmpv2-sys bindings から確認済み）
-        // MPV_EVENT_IDLE=11, MPV_EVENT_START_FILE=6, MPV_EVENT_VIDEO_RECONFIG=17, MPV_EVENT_PROPERTY_CHANGE=22
-        use libmpv2_sys::{
-            mpv_event_id_MPV_EVENT_VIDEO_RECONFIG as EV_VIDEO_RECONFIG,
-            mpv_event_id_MPV_EVENT_PROPERTY_CHANGE as EV_PROPERTY_CHANGE,
-            mpv_format_MPV_FORMAT_INT64 as FMT_INT64,
-        };
-
-        let mut total_attempts = 0;
-        loop {
-            let event = libmpv2_sys::mpv_wait_event(mpv_handle, 0.1); // 100ms ブロッキング
-            if !event.is_null() {
-                let event_id = (*event).event_id;
-                if event_id != 0 {
-                    println!("pre-render event: id={}", event_id);
-                }
-
-                if event_id == EV_PROPERTY_CHANGE {
-                    // mpv_event_property 構造体を解析
-                    let data_ptr = (*event).data as *mut libmpv2_sys::mpv_event_property;
-                    if !data_ptr.is_null() {
-                        let prop = &*data_ptr;
-                        if !prop.name.is_null() {
-                            let name = std::ffi::CStr::from_ptr(prop.name).to_string_lossy();
-                            if prop.format == FMT_INT64 && !prop.data.is_null() {
-                                let val = *(prop.data as *const i64);
-                                println!("PROPERTY_CHANGE: {}={}", name, val);
-                                if name == "width" && val > 0 { observed_width = val; }
-                                if name == "height" && val > 0 { observed_height = val; }
-                            } else {
-                                println!("PROPERTY_CHANGE: {} format={} (not INT64)", name, prop.format);
-                            }
-                        }
-                    }
-                }
-
-                if event_id == EV_VIDEO_RECONFIG {
-                    println!("Got VIDEO_RECONFIG (id=17)");
-                }
+    // 接続中の mpv（切り離している間は None）
+    let mut attached: Option<Attached> = None;
+    // 接続中のセッションの稼働状況（切り離すと使い捨ての値に差し替える）
+    let mut stats = Arc::new(OutputStats::default());
 
-                // 幅・高さが両方揃ったら開始
-                if observed_width > 0 && observed_height > 0 {
-                    println!("Resolution confirmed via observe_property: {}x{}", observed_width, observed_height);
-                    break;
-                }
-            }
-
-            total_attempts += 1;
-            if total_attempts >= 300 { // 最大30秒
-                println!("Timeout waiting for resolution, using fallback {}x{}", initial_width, initial_height);
-                break;
-            }
-        }
-    }
-
-    // playing 状態を通知（最初のフレーム描画前に通知して UI をアンブロック）
-    if let Some(app) = &app_handle {
-        #[derive(Clone, serde::Serialize)]
-        struct PlayingEvent { status: String, syphon_active: bool }
-        let _ = app.emit("player-status", PlayingEvent {
-            status: "playing".to_string(),
-            syphon_active: true,
-        });
-    }
-    is_playing.store(true, std::sync::atomic::Ordering::SeqCst);
-
-    // 初期 FBO（observe_property で取得できた場合はその解像度を使用、なければ initial_width/height）
-    let mut current_width = if observed_width > 0 { observed_width as u32 } else { initial_width };
-    let mut current_height = if observed_height > 0 { observed_height as u32 } else { initial_height };
-    if observed_width > 0 {
-        println!("Using observed resolution: {}x{}", current_width, current_height);
-    } else {
-        println!("Using fallback resolution: {}x{}", current_width, current_height);
-    }
+    // 動画の解像度が確定するまでは初期解像度の FBO でスタンバイ映像を送る。
+    // width/height の変更通知はレンダリングループで受け取り、FBO を作り直す。
+    let mut current_width = initial_width;
+    let mut current_height = initial_height;
     println!("Creating initial FBO with resolution: {}x{}", current_width, current_height);
     let (mut fbo, mut texture) = create_fbo(current_width, current_height);
     println!("FBO created: fbo={}, texture={}", fbo, texture);
@@ -378,6 +325,35 @@ fn syphon_loop(
     let syphon_server = create_syphon_server(server_name, gl_ctx)?;
     println!("Syphon server created");
 
+    // Syphon 出力が有効になったことを通知（再生ステータスは PlayerState が mpv イベントから決める）
+    if let Some(app) = &app_handle {
+        #[derive(Clone, serde::Serialize)]
+        struct OutputActiveEvent { syphon_active: bool }
+        let _ = app.emit("player-status", OutputActiveEvent { syphon_active: true });
+    }
+
+    // 出力設定（再生中の変更は version の変化で検知して読み直す）
+    let mut settings_version = outputs.version();
+    let mut settings = outputs.get(OUTPUT_NAME);
+
+    // 黒帯の自動検出（検出結果を反映した設定を effective に保持する）
+    let mut cropper = AutoCropper::new();
+    let mut crop_sampler = CropSampler::new();
+    let mut effective = settings.effective(None);
+
+    // 映像処理チェーン（mpv の FBO → 変形など → Syphon）
+    let mut chain = VideoChain::new()?;
+    // シェーダの u_time の起点
+    let chain_started = Instant::now();
+    // オーバーレイの {title} / {remaining} などに使う再生情報（イベントから更新する）
+    let mut overlay_inputs = OverlayInputs::default();
+    // プレビューにだけ重ねるレイヤー（PreviewOnly の字幕など）
+    let mut preview_overlays = outputs.preview_overlays();
+
+    // スタンバイ映像（読み込み中・エラー時・停止時に本編の代わりに送る）
+    let mut standby_renderer = StandbyRenderer::new(settings.standby.clone(), current_width, current_height, get_proc_addr);
+    let mut gate = StandbyGate::new();
+
     println!("Starting Syphon rendering loop...");
     log::info!("Syphon レンダリング開始: {} (初期解像度: {}x{})", server_name, current_width, current_height);
 
@@ -388,72 +364,121 @@ fn syphon_loop(
         CGLSetCurrentContext(gl_ctx);
         gl::GenFramebuffers(1, &mut preview_fbo);
         gl::GenTextures(1, &mut preview_texture);
+        resize_preview_texture(preview_texture, preview_width, current_width, current_height);
         gl::BindTexture(gl::TEXTURE_2D, preview_texture);
-        let preview_height = ((current_height as f32 / current_width as f32) * preview_width as f32).max(1.0) as u32;
-        gl::TexImage2D(
-            gl::TEXTURE_2D, 0, gl::RGB as _, preview_width as _, preview_height as _,
-            0, gl::RGB, gl::UNSIGNED_BYTE, std::ptr::null(),
-        );
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as _);
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as _);
         gl::BindFramebuffer(gl::FRAMEBUFFER, preview_fbo);
         gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, preview_texture, 0);
         gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
     }
+    // プレビュー用テクスチャを確保したときの出力解像度
+    let mut preview_source_size = (current_width, current_height);
 
     // レンダリングループ
     let mut consecutive_errors = 0;
-    let max_consecutive_errors = 30; // 約0.5秒分のエラーで停止
+    let max_consecutive_errors = 30; // 約0.5秒分のエラーで失敗扱い（復旧はウォッチドッグが行う）
     let mut frame_count = 0u64;
 
-    // レンダリングループ内の PROPERTY_CHANGE 検知（解像度変更対応）
-    use libmpv2_sys::{
-        mpv_event_id_MPV_EVENT_VIDEO_RECONFIG as EV_VIDEO_RECONFIG,
-        mpv_event_id_MPV_EVENT_PROPERTY_CHANGE as EV_PROPERTY_CHANGE,
-        mpv_format_MPV_FORMAT_INT64 as FMT_INT64,
-    };
+    // mpv の描画失敗でスタンバイに切り替えたか
+    let mut render_failed = false;
+    // 直前に見た mpv のフレーム番号（新しいフレームが出たときだけ stats に数える）
+    let mut last_frame_number: Option<i64> = None;
+
+    // レンダリングループ内の解像度変更検知
     let mut prop_width = current_width as i64;
     let mut prop_height = current_height as i64;
 
-    loop {
-        // 停止コマンドが届いたら終了
-        if let Ok(SyphonCommand::Stop) = cmd_rx.try_recv() {
-            log::info!("停止コマンドを受信、レンダリングを終了します");
-            break;
-        }
-
-        // mpv イベントをドレインして PROPERTY_CHANGE / VIDEO_RECONFIG を検知
-        unsafe {
-            loop {
-                let event = libmpv2_sys::mpv_wait_event(mpv_handle, 0.0);
-                if event.is_null() { break; }
-                let event_id = (*event).event_id;
-                if event_id == 0 { break; } // MPV_EVENT_NONE
-
-                if event_id == EV_PROPERTY_CHANGE {
-                    let data_ptr = (*event).data as *mut libmpv2_sys::mpv_event_property;
-                    if !data_ptr.is_null() {
-                        let prop = &*data_ptr;
-                        if !prop.name.is_null() && prop.format == FMT_INT64 && !prop.data.is_null() {
-                            let name = std::ffi::CStr::from_ptr(prop.name).to_string_lossy();
-                            let val = *(prop.data as *const i64);
-                            if name == "width" && val > 0 {
-                                println!("render loop PROPERTY_CHANGE: width={}", val);
-                                prop_width = val;
-                            }
-                            if name == "height" && val > 0 {
-                                println!("render loop PROPERTY_CHANGE: height={}", val);
-                                prop_height = val;
-                            }
+    'render: loop {
+        // 接続・切り離し・停止のコマンドを処理する（ハンドルが破棄された場合も終了する）
+        loop {
+            let command = match cmd_rx.try_recv() {
+                Ok(command) => command,
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => SyphonCommand::Stop,
+            };
+            match command {
+                SyphonCommand::Attach(session, done) => {
+                    unsafe { CGLSetCurrentContext(gl_ctx) };
+                    // 前のセッションが残っていれば先に破棄する
+                    attached = None;
+                    match unsafe { create_render_context(session.mpv_handle.0, &gl_ctx) } {
+                        Ok(render_ctx) => {
+                            attached = Some(Attached { render_ctx, mpv_handle: session.mpv_handle.0 });
+                            stats = session.stats;
+                            consecutive_errors = 0;
+                            frame_count = 0;
+                            last_frame_number = None;
+                            render_failed = false;
+                            gate.set_render_failed(false);
+                            cropper.reset();
+                            effective = settings.effective(None);
+                            log::info!("mpv に接続しました");
+                            let _ = done.send(Ok(()));
                         }
+                        Err(e) => {
+                            log::error!("mpv への接続に失敗: {}", e);
+                            let _ = done.send(Err(e));
+                        }
+                    }
+                }
+                SyphonCommand::Detach(done) => {
+                    if attached.take().is_some() {
+                        // RenderContext の破棄は GL コンテキストが有効な状態で行う
+                        unsafe { CGLSetCurrentContext(gl_ctx) };
+                        log::info!("mpv から切り離しました。スタンバイ映像を送ります");
                     }
+                    stats = Arc::new(OutputStats::default());
+                    let _ = done.send(());
+                }
+                SyphonCommand::Stop => {
+                    log::info!("停止コマンドを受信、レンダリングを終了します");
+                    break 'render;
                 }
+            }
+        }
 
-                if event_id == EV_VIDEO_RECONFIG {
-                    println!("render loop VIDEO_RECONFIG (id=17)");
+        // ディスパッチャからのイベントをドレインして width/height の変更を検知
+        while let Ok(event) = events.try_recv() {
+            gate.on_event(&event);
+            match event {
+                // 新しい動画では黒帯を検出し直す
+                PlayerEvent::StartFile => {
+                    cropper.reset();
+                    stats.set_crop(None, None);
+                    effective = settings.effective(None);
+                }
+                PlayerEvent::PropertyChange(PropertyChange::Width(w)) if w > 0 => {
+                    println!("render loop PROPERTY_CHANGE: width={}", w);
+                    prop_width = w;
+                }
+                PlayerEvent::PropertyChange(PropertyChange::Height(h)) if h > 0 => {
+                    println!("render loop PROPERTY_CHANGE: height={}", h);
+                    prop_height = h;
                 }
+                PlayerEvent::PropertyChange(PropertyChange::TimePos(pos)) => overlay_inputs.time_pos = pos,
+                PlayerEvent::PropertyChange(PropertyChange::Duration(dur)) => overlay_inputs.duration = dur,
+                PlayerEvent::PropertyChange(PropertyChange::MediaTitle(title)) => overlay_inputs.title = title,
+                PlayerEvent::PropertyChange(PropertyChange::SubText(text)) => overlay_inputs.subtitle = text,
+                PlayerEvent::VideoReconfig => println!("render loop VIDEO_RECONFIG"),
+                _ => {}
+            }
+        }
+
+        // 出力設定が変わっていれば読み直す（スタンバイ映像は変わったときだけ読み込み直す）
+        if outputs.version() != settings_version {
+            settings_version = outputs.version();
+            let updated = outputs.get(OUTPUT_NAME);
+            if updated.standby != settings.standby {
+                unsafe { CGLSetCurrentContext(gl_ctx) };
+                standby_renderer.set_source(updated.standby.clone());
             }
+            settings = updated;
+            effective = settings.effective(cropper.applied());
+            preview_overlays = outputs.preview_overlays();
+        }
 
+        unsafe {
             // 解像度が変わっていれば FBO を再作成
             if prop_width > 0 && prop_height > 0
                 && (prop_width as u32 != current_width || prop_height as u32 != current_height)
@@ -476,44 +501,107 @@ fn syphon_loop(
         unsafe {
             CGLSetCurrentContext(gl_ctx);
 
-            // mpv に FBO へ描画させる
-            match render_ctx.render::<()>(fbo as i32, current_width as i32, current_height as i32, true) {
-                Ok(_) => {
+            // mpv に FBO へ描画させる（スタンバイ表示中も描画は続け、mpv の再生を進める）
+            let render = attached.as_ref()
+                .map(|a| a.render_ctx.render::<()>(fbo as i32, current_width as i32, current_height as i32, true));
+            let rendered = match render {
+                // 再生していない間はスタンバイ映像だけを送る
+                None => false,
+                Some(Ok(_)) => {
                     consecutive_errors = 0;
+                    // 描画自体は前のフレームでも成功するため、フレーム番号が進んだときだけ数える
+                    // （止まったストリームをウォッチドッグが no-frames として検知できるように）
+                    let frame_number = attached.as_ref().and_then(|a| estimated_frame_number(a.mpv_handle));
+                    if frame_number.is_some() && frame_number != last_frame_number {
+                        last_frame_number = frame_number;
+                        stats.record_frame();
+                    }
+                    if render_failed {
+                        render_failed = false;
+                        stats.set_render_failed(false);
+                        gate.set_render_failed(false);
+                    }
 
                     // 最初のフレームでログ出力
                     if frame_count == 0 {
                         println!("First frame rendered successfully! size={}x{}", current_width, current_height);
                         log::info!("最初のフレームを描画しました: {}x{}", current_width, current_height);
                     }
-
-                    // Syphon にテクスチャを公開
-                    publish_syphon_frame(&syphon_server, texture, current_width, current_height);
-
                     frame_count += 1;
-
-                    // プレビューを送信（毎フレーム、再利用 FBO を使う）
-                    if let Some(ref app) = app_handle {
-                        send_preview_frame_blit(app, fbo, current_width, current_height, preview_fbo, preview_texture);
-                    }
+                    true
                 }
-                Err(e) => {
+                // 読み込み中などスタンバイ表示中の描画失敗は想定内
+                Some(Err(_)) if gate.is_active() => false,
+                Some(Err(e)) => {
                     consecutive_errors += 1;
                     log::warn!("mpv render エラー ({}/{}): {:?}", consecutive_errors, max_consecutive_errors, e);
 
+                    // ループは止めずにスタンバイへ切り替え、ウォッチドッグによる再読み込みを待つ
                     if consecutive_errors >= max_consecutive_errors {
-                        log::error!("連続エラーが上限に達したため、レンダリングを停止します");
-                        break;
+                        log::error!("連続エラーが上限に達しました。ストリームの復旧を待ちます");
+                        stats.set_render_failed(true);
+                        render_failed = true;
+                        gate.set_render_failed(true);
                     }
+                    false
+                }
+            };
 
-                    std::thread::sleep(Duration::from_millis(16));
-                    continue;
+            // 数フレームごとに縮小して読み戻し、黒帯を検出する（Locked / Manual でも表示用に続ける）
+            if rendered && !gate.is_active()
+                && settings.autocrop.mode != CropMode::Off
+                && frame_count % CROP_SAMPLE_INTERVAL == 0
+            {
+                let source = Frame { fbo, texture, width: current_width, height: current_height };
+                let (pixels, width, height) = crop_sampler.sample(source);
+                let detected = autocrop::detect_bars(pixels, width, height, settings.autocrop.threshold);
+                let settle = Duration::from_secs_f32(settings.autocrop.settle_secs.max(0.0));
+                if cropper.on_sample(detected, settle, Instant::now()) {
+                    effective = settings.effective(cropper.applied());
                 }
+                stats.set_crop(cropper.detected(), cropper.applied());
+            }
+
+            // スタンバイ映像は変形後の出力解像度に合わせる
+            let (out_width, out_height) = effective.transform.output_size(current_width, current_height);
+            standby_renderer.resize(out_width, out_height);
+
+            // Syphon にテクスチャを公開（映像が無い間はスタンバイ映像）
+            let standby = gate.is_active() || attached.is_none();
+            standby_renderer.set_shown(standby);
+            let out = if standby {
+                standby_renderer.render()
+            } else if rendered {
+                let source = Frame { fbo, texture, width: current_width, height: current_height };
+                // 音声レベルはシェーダを使うときだけ読む
+                let inputs = ShaderInputs {
+                    time: chain_started.elapsed().as_secs_f32(),
+                    audio_level: if effective.shaders.is_empty() { 0.0 } else { attached.as_ref().map_or(0.0, |a| meter::audio_level(a.mpv_handle)) },
+                };
+                let processed = chain.process(source, &effective, inputs);
+                // 時計やタイトルが静止画に残らないよう、オーバーレイを重ねる前に保持する
+                standby_renderer.capture(processed.fbo);
+                chain.overlay(processed, &effective.overlays, &overlay_inputs)
+            } else {
+                std::thread::sleep(Duration::from_millis(16));
+                continue;
+            };
+            publish_syphon_frame(&syphon_server, out.texture, out.width, out.height);
+
+            // プレビューを送信（毎フレーム、再利用 FBO を使う。出力のアスペクト比が変わったら確保し直す）
+            if let Some(ref app) = app_handle {
+                if (out.width, out.height) != preview_source_size {
+                    preview_source_size = (out.width, out.height);
+                    resize_preview_texture(preview_texture, preview_width, out.width, out.height);
+                }
+                let preview = chain.overlay_preview(out, &preview_overlays, &overlay_inputs);
+                send_preview_frame_blit(app, preview.fbo, preview.width, preview.height, preview_fbo, preview_texture);
             }
         }
 
-        // 60fps ターゲット
-        std::thread::sleep(Duration::from_millis(16));
+        // 60fps ターゲット（描画失敗中はスタンバイ映像のみなので間隔を空ける）
+        let interval = if render_failed { 250 } else { 16 };
+        std::thread::sleep(Duration::from_millis(interval));
     }
 
     // クリーンアップ（重要: 順序を守る）
@@ -523,33 +611,23 @@ fn syphon_loop(
         // 1. GL コンテキストをアクティブにする
         CGLSetCurrentContext(gl_ctx);
 
-        // 2. バッファをクリアするために黒いフレームを複数回送信
-        log::info!("バッファクリア用の黒いフレームを送信します");
-
-        // テクスチャを黒でクリア
-        gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
-        gl::Viewport(0, 0, current_width as i32, current_height as i32);
-        gl::ClearColor(0.0, 0.0, 0.0, 1.0);
-        gl::Clear(gl::COLOR_BUFFER_BIT);
-        gl::Flush();
-
-        // 黒いフレームを複数回送信（TouchDesigner が確実に受信できるように）
+        // 2. サーバーを止める前に、受信側に残る最後のフレームをスタンバイ映像にするため複数回送信
+        //    （再生中に終了した場合でも TouchDesigner が確実に受信できるように）
+        log::info!("停止用のスタンバイフレームを送信します");
+        standby_renderer.set_shown(true);
         for i in 0..10 {
-            // 毎回クリアして確実に黒にする
-            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
-            gl::Clear(gl::COLOR_BUFFER_BIT);
-
-            publish_syphon_frame(&syphon_server, texture, current_width, current_height);
+            let standby_frame = standby_renderer.render();
+            publish_syphon_frame(&syphon_server, standby_frame.texture, standby_frame.width, standby_frame.height);
             gl::Flush();
             std::thread::sleep(Duration::from_millis(50)); // 少し長めに待つ
-            log::debug!("黒フレーム送信 {}/10", i + 1);
+            log::debug!("スタンバイフレーム送信 {}/10", i + 1);
         }
 
         // GL 操作が完了するまで待機
         gl::Finish();
 
-        // クライアント側が黒フレームを受信・処理する時間を確保
-        log::info!("黒いフレームの送信が完了しました (クライアント受信待機中...)");
+        // クライアント側がスタンバイフレームを受信・処理する時間を確保
+        log::info!("スタンバイフレームの送信が完了しました (クライアント受信待機中...)");
         std::thread::sleep(Duration::from_millis(300));
 
         // 3. Syphon Server を停止して解放
@@ -565,9 +643,13 @@ fn syphon_loop(
         drop(syphon_server);
         log::info!("Syphon Server を解放しました");
 
-        // 4. RenderContext を明示的に破棄（GL コンテキストが有効な状態で）
+        // 4. スタンバイ映像のリソース（クリップ用 mpv を含む）・処理チェーン・黒帯検出・RenderContext を
+        //    明示的に破棄（GL コンテキストが有効な状態で）
+        standby_renderer.destroy();
+        chain.destroy();
+        crop_sampler.destroy();
         log::info!("RenderContext を破棄します");
-        drop(render_ctx);
+        drop(attached);
 
         // 5. GL リソースを削除
         log::info!("GL リソースを削除します");
@@ -587,6 +669,29 @@ fn syphon_loop(
     Ok(())
 }
 
+/// mpv の RenderContext に渡す GL 関数ポインタの解決関数
+fn get_proc_addr(_ctx: &*const std::ffi::c_void, name: &str) -> *mut std::ffi::c_void {
+    unsafe {
+        let name_cstr = std::ffi::CString::new(name).unwrap();
+        dlsym(RTLD_DEFAULT, name_cstr.as_ptr())
+    }
+}
+
+/// mpv が表示しているフレームの番号（`estimated-frame-number`。映像が無い場合は None）
+fn estimated_frame_number(handle: *mut libmpv2_sys::mpv_handle) -> Option<i64> {
+    let name = std::ffi::CString::new("estimated-frame-number").unwrap();
+    let mut value: i64 = 0;
+    let ret = unsafe {
+        libmpv2_sys::mpv_get_property(
+            handle,
+            name.as_ptr(),
+            libmpv2_sys::mpv_format_MPV_FORMAT_INT64,
+            &mut value as *mut i64 as *mut std::ffi::c_void,
+        )
+    };
+    (ret >= 0).then_some(value)
+}
+
 /// CGL コンテキストを作成
 fn create_cgl_context() -> Result<CGLContextObj> {
     unsafe {
@@ -770,6 +875,17 @@ unsafe impl Encode for NSRect {
     const ENCODING: Encoding = Encoding::Struct("CGRect", &[NSPoint::ENCODING, NSSize::ENCODING]);
 }
 
+/// プレビュー用テクスチャを出力のアスペクト比に合わせて確保する
+unsafe fn resize_preview_texture(preview_texture: gl::types::GLuint, preview_width: u32, width: u32, height: u32) {
+    let preview_height = ((height as f32 / width as f32) * preview_width as f32).max(1.0) as u32;
+    gl::BindTexture(gl::TEXTURE_2D, preview_texture);
+    gl::TexImage2D(
+        gl::TEXTURE_2D, 0, gl::RGB as _, preview_width as _, preview_height as _,
+        0, gl::RGB, gl::UNSIGNED_BYTE, std::ptr::null(),
+    );
+    gl::BindTexture(gl::TEXTURE_2D, 0);
+}
+
 /// プレビューフレームを WebView に送信（glBlitFramebuffer で GPU リサイズ）
 /// preview_fbo / preview_texture はループ外で確保済みのものを再利用する
 unsafe fn send_preview_frame_blit(
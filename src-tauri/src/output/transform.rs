@@ -0,0 +1,472 @@
+/// 出力ごとの映像変形（切り出し・パン/ズーム・反転・回転・スケーリング）
+///
+/// ## 座標系
+/// - 設定値（`crop`・`pan_y`）は映像の左上を原点とする正規化座標で指定する
+/// - 内部では GL のテクスチャ座標（左下原点、v が上向き）で計算する
+///
+/// ## 変形の順序
+/// 元映像 → 切り出し → パン/ズーム → 回転（時計回り）→ 反転 → 出力解像度へ拡縮
+///
+/// 出力座標から元映像の座標への対応は `uv_matrix()` の 3x3 行列 1 つにまとめ、
+/// GPU（シェーダ）と CPU（ソフトウェア描画経路）のどちらも同じ行列で標本化する。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// 正規化座標の矩形（左上原点、0.0–1.0）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    /// 大きさがあり、映像の範囲に収まっているか
+    pub fn is_inside_frame(&self) -> bool {
+        self.x >= 0.0 && self.y >= 0.0
+            && self.width > 0.0 && self.height > 0.0
+            && self.x + self.width <= 1.0 + f32::EPSILON
+            && self.y + self.height <= 1.0 + f32::EPSILON
+    }
+}
+
+/// 出力解像度（ピクセル）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 拡縮時の補間方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScaleFilter {
+    /// 最近傍（LED ウォールなどでドットを保ちたい場合）
+    Nearest,
+    #[default]
+    Linear,
+}
+
+/// 映像変形の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Transform {
+    /// 切り出し範囲（None = 全体）
+    pub crop: Option<Rect>,
+    /// ズーム倍率（1.0 = 等倍、0.1–16.0）
+    pub zoom: f32,
+    /// ズーム時の表示位置（-1.0 = 左端、1.0 = 右端）
+    pub pan_x: f32,
+    /// ズーム時の表示位置（-1.0 = 上端、1.0 = 下端）
+    pub pan_y: f32,
+    pub flip_h: bool,
+    pub flip_v: bool,
+    /// 時計回りの回転角（0 / 90 / 180 / 270）
+    pub rotation: u16,
+    pub filter: ScaleFilter,
+    /// 出力解像度（None = 切り出し範囲を回転させた解像度）
+    pub size: Option<Size>,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            crop: None,
+            zoom: 1.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
+            flip_h: false,
+            flip_v: false,
+            rotation: 0,
+            filter: ScaleFilter::Linear,
+            size: None,
+        }
+    }
+}
+
+/// 2x3 のアフィン変換（行優先、最終行は 0 0 1）
+type Affine = [[f32; 3]; 2];
+
+const IDENTITY: Affine = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+/// a ∘ b（b を適用してから a を適用する）
+fn compose(a: &Affine, b: &Affine) -> Affine {
+    let mut out = [[0.0; 3]; 2];
+    for (row, out_row) in out.iter_mut().enumerate() {
+        for (col, value) in out_row.iter_mut().enumerate() {
+            *value = a[row][0] * b[0][col] + a[row][1] * b[1][col];
+        }
+        out_row[2] += a[row][2];
+    }
+    out
+}
+
+impl Transform {
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        if !(0.1..=16.0).contains(&self.zoom) {
+            return Err(anyhow::anyhow!("zoom は 0.1–16.0 で指定してください: {}", self.zoom));
+        }
+        if !(-1.0..=1.0).contains(&self.pan_x) || !(-1.0..=1.0).contains(&self.pan_y) {
+            return Err(anyhow::anyhow!("pan は -1.0–1.0 で指定してください"));
+        }
+        if !matches!(self.rotation, 0 | 90 | 180 | 270) {
+            return Err(anyhow::anyhow!("rotation は 0 / 90 / 180 / 270 のいずれかです: {}", self.rotation));
+        }
+        if let Some(crop) = &self.crop {
+            if !crop.is_inside_frame() {
+                return Err(anyhow::anyhow!("crop が映像の範囲外です: {:?}", crop));
+            }
+        }
+        if let Some(size) = &self.size {
+            if !(16..=8192).contains(&size.width) || !(16..=8192).contains(&size.height) {
+                return Err(anyhow::anyhow!("size は 16–8192 で指定してください: {}x{}", size.width, size.height));
+            }
+        }
+        Ok(())
+    }
+
+    /// 何も変形しない設定か（変形パスを省略できる）
+    pub fn is_identity(&self) -> bool {
+        *self == Transform { filter: self.filter, ..Transform::default() }
+    }
+
+    /// 元映像の解像度から出力解像度を決める
+    pub fn output_size(&self, src_width: u32, src_height: u32) -> (u32, u32) {
+        if let Some(size) = self.size {
+            return (size.width, size.height);
+        }
+        let (width, height) = match &self.crop {
+            Some(crop) => (
+                ((crop.width * src_width as f32).round() as u32).max(1),
+                ((crop.height * src_height as f32).round() as u32).max(1),
+            ),
+            None => (src_width, src_height),
+        };
+        match self.rotation {
+            90 | 270 => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    /// 出力のテクスチャ座標 (u, v) を元映像のテクスチャ座標に写す 3x3 行列（行優先）
+    pub fn uv_matrix(&self) -> [f32; 9] {
+        // 1. 出力座標を中心原点にする
+        let mut m: Affine = [[1.0, 0.0, -0.5], [0.0, 1.0, -0.5]];
+
+        // 2. 反転（自身が逆変換）
+        let flip: Affine = [
+            [if self.flip_h { -1.0 } else { 1.0 }, 0.0, 0.0],
+            [0.0, if self.flip_v { -1.0 } else { 1.0 }, 0.0],
+        ];
+        m = compose(&flip, &m);
+
+        // 3. 回転の逆変換（出力 = 元映像を時計回りに回したもの）
+        let rotate: Affine = match self.rotation {
+            90 => [[0.0, -1.0, 0.0], [1.0, 0.0, 0.0]],
+            180 => [[-1.0, 0.0, 0.0], [0.0, -1.0, 0.0]],
+            270 => [[0.0, 1.0, 0.0], [-1.0, 0.0, 0.0]],
+            _ => IDENTITY,
+        };
+        m = compose(&rotate, &m);
+
+        // 4. パン/ズーム（pan = ±1 でズームした範囲の端に届く）
+        let reach = 0.5 * (1.0 - 1.0 / self.zoom).abs();
+        let pan_zoom: Affine = [
+            [1.0 / self.zoom, 0.0, self.pan_x * reach],
+            [0.0, 1.0 / self.zoom, -self.pan_y * reach],
+        ];
+        m = compose(&pan_zoom, &m);
+
+        // 5. 切り出し範囲へ写す（左上原点 → 左下原点）
+        let crop = self.crop.unwrap_or(Rect { x: 0.0, y: 0.0, width: 1.0, height: 1.0 });
+        let origin_v = 1.0 - crop.y - crop.height;
+        let to_crop: Affine = [
+            [crop.width, 0.0, crop.x + 0.5 * crop.width],
+            [0.0, crop.height, origin_v + 0.5 * crop.height],
+        ];
+        m = compose(&to_crop, &m);
+
+        [m[0][0], m[0][1], m[0][2], m[1][0], m[1][1], m[1][2], 0.0, 0.0, 1.0]
+    }
+}
+
+// ─── CPU フォールバック ──────────────────────────────────────────────────────
+
+/// RGBA8（上の行から並ぶ）のフレームを変形する（ソフトウェア描画経路用）
+///
+/// 戻り値: (ピクセル, 幅, 高さ)。元映像の範囲外は黒になる。
+pub fn apply_cpu(src: &[u8], src_width: u32, src_height: u32, transform: &Transform) -> (Vec<u8>, u32, u32) {
+    if transform.is_identity() {
+        return (src.to_vec(), src_width, src_height);
+    }
+
+    let (width, height) = transform.output_size(src_width, src_height);
+    let m = transform.uv_matrix();
+    let mut out = vec![0u8; (width * height * 4) as usize];
+
+    for y in 0..height {
+        // 行は上から並ぶが、行列は v が上向きの座標で定義している
+        let v = 1.0 - (y as f32 + 0.5) / height as f32;
+        for x in 0..width {
+            let u = (x as f32 + 0.5) / width as f32;
+            let su = m[0] * u + m[1] * v + m[2];
+            let sv = m[3] * u + m[4] * v + m[5];
+
+            let offset = ((y * width + x) * 4) as usize;
+            let pixel = &mut out[offset..offset + 4];
+            if !(0.0..=1.0).contains(&su) || !(0.0..=1.0).contains(&sv) {
+                pixel.copy_from_slice(&[0, 0, 0, 255]);
+                continue;
+            }
+
+            let sx = su * src_width as f32 - 0.5;
+            let sy = (1.0 - sv) * src_height as f32 - 0.5;
+            match transform.filter {
+                ScaleFilter::Nearest => {
+                    let px = (sx.round().max(0.0) as u32).min(src_width - 1);
+                    let py = (sy.round().max(0.0) as u32).min(src_height - 1);
+                    let src_offset = ((py * src_width + px) * 4) as usize;
+                    pixel.copy_from_slice(&src[src_offset..src_offset + 4]);
+                }
+                ScaleFilter::Linear => {
+                    sample_bilinear(src, src_width, src_height, sx, sy, pixel);
+                }
+            }
+        }
+    }
+
+    (out, width, height)
+}
+
+fn sample_bilinear(src: &[u8], width: u32, height: u32, x: f32, y: f32, out: &mut [u8]) {
+    let x = x.clamp(0.0, (width - 1) as f32);
+    let y = y.clamp(0.0, (height - 1) as f32);
+    let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+    let at = |px: u32, py: u32, c: usize| src[((py * width + px) * 4) as usize + c] as f32;
+    for (c, value) in out.iter_mut().enumerate() {
+        let top = at(x0, y0, c) * (1.0 - fx) + at(x1, y0, c) * fx;
+        let bottom = at(x0, y1, c) * (1.0 - fx) + at(x1, y1, c) * fx;
+        *value = (top * (1.0 - fy) + bottom * fy).round() as u8;
+    }
+}
+
+// ─── GPU パス ────────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "macos")]
+pub use gpu::TransformPass;
+
+#[cfg(target_os = "macos")]
+mod gpu {
+    use super::{ScaleFilter, Transform};
+    use crate::output::gl_util::{self, Frame, FullscreenQuad, RenderTarget};
+    use anyhow::Result;
+
+    const FRAGMENT_SHADER: &str = r#"#version 150
+in vec2 v_uv;
+out vec4 frag_color;
+uniform sampler2D u_texture;
+uniform mat3 u_uv_matrix;
+
+void main() {
+    vec2 uv = (u_uv_matrix * vec3(v_uv, 1.0)).xy;
+    if (any(lessThan(uv, vec2(0.0))) || any(greaterThan(uv, vec2(1.0)))) {
+        frag_color = vec4(0.0, 0.0, 0.0, 1.0);
+    } else {
+        frag_color = texture(u_texture, uv);
+    }
+}
+"#;
+
+    /// 変形をシェーダで行うパス
+    pub struct TransformPass {
+        program: gl::types::GLuint,
+        u_texture: gl::types::GLint,
+        u_uv_matrix: gl::types::GLint,
+    }
+
+    impl TransformPass {
+        pub fn new() -> Result<Self> {
+            let program = gl_util::compile_program(gl_util::FULLSCREEN_VERTEX_SHADER, FRAGMENT_SHADER)?;
+            Ok(Self {
+                program,
+                u_texture: gl_util::uniform_location(program, "u_texture"),
+                u_uv_matrix: gl_util::uniform_location(program, "u_uv_matrix"),
+            })
+        }
+
+        /// input を変形して target に描画する（target は出力解像度に合わせて作り直す）
+        pub fn draw(&self, quad: &FullscreenQuad, input: Frame, target: &mut RenderTarget, transform: &Transform) -> Frame {
+            let (width, height) = transform.output_size(input.width, input.height);
+            target.resize(width, height);
+
+            let filter = match transform.filter {
+                ScaleFilter::Nearest => gl::NEAREST,
+                ScaleFilter::Linear => gl::LINEAR,
+            };
+            let matrix = transform.uv_matrix();
+
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+                gl::Viewport(0, 0, width as i32, height as i32);
+                gl::Disable(gl::BLEND);
+                gl::UseProgram(self.program);
+
+                gl::ActiveTexture(gl::TEXTURE0);
+                gl::BindTexture(gl::TEXTURE_2D, input.texture);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filter as _);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filter as _);
+                gl::Uniform1i(self.u_texture, 0);
+                // 行優先で持っているので転置して渡す
+                gl::UniformMatrix3fv(self.u_uv_matrix, 1, gl::TRUE, matrix.as_ptr());
+
+                quad.draw();
+
+                gl::BindTexture(gl::TEXTURE_2D, 0);
+                gl::UseProgram(0);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            }
+            target.frame()
+        }
+
+        pub fn destroy(self) {
+            unsafe { gl::DeleteProgram(self.program) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    /// 出力の (u, v) を行列で元映像の (u, v) に写す
+    fn map(transform: &Transform, u: f32, v: f32) -> (f32, f32) {
+        let m = transform.uv_matrix();
+        (m[0] * u + m[1] * v + m[2], m[3] * u + m[4] * v + m[5])
+    }
+
+    fn assert_maps(transform: &Transform, from: (f32, f32), to: (f32, f32)) {
+        let (u, v) = map(transform, from.0, from.1);
+        assert!((u - to.0).abs() < 1e-6 && (v - to.1).abs() < 1e-6, "{:?} → ({}, {}) != {:?}", from, u, v, to);
+    }
+
+    fn pixels(colors: &[[u8; 4]]) -> Vec<u8> {
+        colors.concat()
+    }
+
+    #[test]
+    fn identity_matrix_keeps_coordinates() {
+        let transform = Transform::default();
+        assert!(transform.is_identity());
+        assert!(Transform { filter: ScaleFilter::Nearest, ..Transform::default() }.is_identity());
+        assert_maps(&transform, (0.25, 0.75), (0.25, 0.75));
+        assert_eq!(transform.uv_matrix()[6..], [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn matrix_flips_and_rotates_clockwise() {
+        assert_maps(&Transform { flip_h: true, ..Transform::default() }, (0.25, 0.75), (0.75, 0.75));
+        assert_maps(&Transform { flip_v: true, ..Transform::default() }, (0.25, 0.75), (0.25, 0.25));
+        // 時計回りに 90° 回すと、元映像の左下が出力の左上に来る
+        let rotated = Transform { rotation: 90, ..Transform::default() };
+        assert_maps(&rotated, (0.0, 1.0), (0.0, 0.0));
+        assert_maps(&rotated, (1.0, 1.0), (0.0, 1.0));
+        assert_maps(&Transform { rotation: 180, ..Transform::default() }, (0.0, 0.0), (1.0, 1.0));
+        assert_maps(&Transform { rotation: 270, ..Transform::default() }, (0.0, 1.0), (1.0, 1.0));
+    }
+
+    #[test]
+    fn matrix_maps_into_crop_and_pan() {
+        // 右上の 1/4 を切り出す（設定は左上原点、行列は左下原点）
+        let cropped = Transform { crop: Some(rect(0.5, 0.0, 0.5, 0.5)), ..Transform::default() };
+        assert_maps(&cropped, (0.0, 0.0), (0.5, 0.5));
+        assert_maps(&cropped, (1.0, 1.0), (1.0, 1.0));
+
+        // 2 倍にズームして右端・下端へパンすると、ズームした範囲が元映像の端に届く
+        let panned = Transform { zoom: 2.0, pan_x: 1.0, pan_y: 1.0, ..Transform::default() };
+        assert_maps(&panned, (0.5, 0.5), (0.75, 0.25));
+        assert_maps(&panned, (1.0, 0.0), (1.0, 0.0));
+    }
+
+    #[test]
+    fn output_size_follows_crop_rotation_and_override() {
+        assert_eq!(Transform::default().output_size(1920, 1080), (1920, 1080));
+        let cropped = Transform { crop: Some(rect(0.0, 0.0, 0.5, 0.25)), ..Transform::default() };
+        assert_eq!(cropped.output_size(1920, 1080), (960, 270));
+        assert_eq!(Transform { rotation: 90, ..cropped.clone() }.output_size(1920, 1080), (270, 960));
+        assert_eq!(Transform { rotation: 180, ..cropped.clone() }.output_size(1920, 1080), (960, 270));
+        // 極端に小さい切り出しでも 1 ピクセルは残す
+        let tiny = Transform { crop: Some(rect(0.0, 0.0, 0.0001, 0.0001)), ..Transform::default() };
+        assert_eq!(tiny.output_size(100, 100), (1, 1));
+        let sized = Transform { size: Some(Size { width: 1280, height: 720 }), rotation: 90, ..cropped };
+        assert_eq!(sized.output_size(1920, 1080), (1280, 720));
+    }
+
+    #[test]
+    fn validates_ranges() {
+        assert!(Transform::default().validate().is_ok());
+        assert!(Transform { zoom: 0.05, ..Transform::default() }.validate().is_err());
+        assert!(Transform { zoom: 16.5, ..Transform::default() }.validate().is_err());
+        assert!(Transform { pan_x: 1.5, ..Transform::default() }.validate().is_err());
+        assert!(Transform { pan_y: -1.5, ..Transform::default() }.validate().is_err());
+        assert!(Transform { rotation: 45, ..Transform::default() }.validate().is_err());
+        assert!(Transform { crop: Some(rect(0.5, 0.0, 0.6, 1.0)), ..Transform::default() }.validate().is_err());
+        assert!(Transform { crop: Some(rect(0.0, 0.0, 0.0, 1.0)), ..Transform::default() }.validate().is_err());
+        assert!(Transform { crop: Some(rect(0.25, 0.25, 0.75, 0.75)), ..Transform::default() }.validate().is_ok());
+        assert!(Transform { size: Some(Size { width: 8, height: 720 }), ..Transform::default() }.validate().is_err());
+        assert!(Transform { size: Some(Size { width: 1280, height: 9000 }), ..Transform::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn cpu_flips_and_rotates_pixels() {
+        let src = pixels(&[RED, BLUE]);
+        assert_eq!(apply_cpu(&src, 2, 1, &Transform::default()), (src.clone(), 2, 1));
+
+        for filter in [ScaleFilter::Nearest, ScaleFilter::Linear] {
+            let flipped = Transform { flip_h: true, filter, ..Transform::default() };
+            assert_eq!(apply_cpu(&src, 2, 1, &flipped), (pixels(&[BLUE, RED]), 2, 1));
+
+            // 横並びを時計回りに回すと、左が上になる
+            let rotated = Transform { rotation: 90, filter, ..Transform::default() };
+            assert_eq!(apply_cpu(&src, 2, 1, &rotated), (pixels(&[RED, BLUE]), 1, 2));
+        }
+    }
+
+    #[test]
+    fn cpu_fills_outside_of_the_source_with_black() {
+        let src = pixels(&[RED; 16]);
+        let transform = Transform { zoom: 0.5, filter: ScaleFilter::Nearest, ..Transform::default() };
+        let (out, width, height) = apply_cpu(&src, 4, 4, &transform);
+        assert_eq!((width, height), (4, 4));
+        let at = |x: usize, y: usize| &out[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4];
+        assert_eq!(at(0, 0), BLACK);
+        assert_eq!(at(3, 3), BLACK);
+        assert_eq!(at(1, 1), RED);
+        assert_eq!(at(2, 2), RED);
+    }
+
+    #[test]
+    fn cpu_scales_with_the_selected_filter() {
+        // 2 ピクセルを 4 ピクセルに引き伸ばす
+        let src = pixels(&[[0, 0, 0, 255], [200, 200, 200, 255]]);
+        let size = Some(Size { width: 4, height: 1 });
+        let first_channel = |filter| {
+            let (out, _, _) = apply_cpu(&src, 2, 1, &Transform { size, filter, ..Transform::default() });
+            out.chunks(4).map(|pixel| pixel[0]).collect::<Vec<_>>()
+        };
+        assert_eq!(first_channel(ScaleFilter::Nearest), [0, 0, 200, 200]);
+        assert_eq!(first_channel(ScaleFilter::Linear), [0, 50, 150, 200]);
+    }
+}
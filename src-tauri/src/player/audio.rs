@@ -1,7 +1,28 @@
 /// オーディオデバイス列挙
 ///
 /// macOS: CoreAudio の AudioObjectGetPropertyData を使って列挙
+/// Linux: PulseAudio のシンク（pactl）・PipeWire のシンク（pw-dump）・ALSA の PCM（デバイス名のヒント）・
+///        JACK のクライアントを列挙。ID は mpv の audio-device の形式（`pulse/…`・`pipewire/…`・`alsa/…`）
 /// mpv が起動していない状態でも使用可能
+///
+/// デバイスの増減は `spawn_monitor` で監視する
+/// （macOS は CoreAudio のプロパティリスナー、Linux は `pactl subscribe` のイベントと `/dev/snd` の inotify、
+/// 通知が取れない環境では定期的に列挙し直して比べる）。
+///
+/// ## JACK の出力先
+/// mpv の audio-device には JACK の接続先を指定する形式が無いため、次の ID を独自に使う。
+/// - `jack`: JACK に出力し、ポートは自動接続しない（ミキサー側で結線する）
+/// - `jack/<クライアント名>`: そのクライアントの入力ポートに自動接続する（例: `jack/system`）
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// イベントが取れない環境で一覧を取り直す間隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// 1 回の抜き差しで続けて届くイベントをまとめる時間（この間イベントが途切れたら列挙し直す）
+const DEBOUNCE: Duration = Duration::from_millis(300);
+/// イベントが途切れなくても、最初のイベントからこの時間が経ったら列挙し直す
+const MAX_DEBOUNCE: Duration = Duration::from_secs(2);
 
 pub fn enumerate_devices() -> Vec<(String, String)> {
     #[cfg(target_os = "macos")]
@@ -9,12 +30,466 @@ pub fn enumerate_devices() -> Vec<(String, String)> {
         enumerate_coreaudio()
     }
 
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
+    {
+        let mut devices = vec![("auto".to_string(), "デフォルト".to_string())];
+        devices.extend(enumerate_pulse());
+        devices.extend(enumerate_pipewire());
+        devices.extend(enumerate_alsa());
+        devices.extend(enumerate_jack());
+        devices
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         vec![("auto".to_string(), "デフォルト".to_string())]
     }
 }
 
+/// mpv の audio-device-list に現れない出力先（JACK）
+///
+/// mpv から取得した一覧に追加して使う。
+pub fn extra_devices() -> Vec<(String, String)> {
+    #[cfg(target_os = "linux")]
+    {
+        enumerate_jack()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Vec::new()
+    }
+}
+
+/// JACK の出力先 ID を解析する
+///
+/// JACK でなければ None、`jack` なら Some(None)、`jack/<クライアント名>` なら Some(Some(クライアント名))。
+pub fn parse_jack_device(device_id: &str) -> Option<Option<&str>> {
+    match device_id.strip_prefix("jack") {
+        Some("") => Some(None),
+        Some(rest) => rest.strip_prefix('/').filter(|client| !client.is_empty()).map(Some),
+        None => None,
+    }
+}
+
+// ─── 出力の設定 ──────────────────────────────────────────────────────────────
+
+/// JACK / PipeWire に見せる出力の設定（再生開始時に適用）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioOutputSettings {
+    /// JACK のクライアント名・PipeWire / PulseAudio のノード名
+    #[serde(default = "default_client_name")]
+    pub client_name: String,
+    /// 出力のチャンネル数（None は mpv に任せる。JACK では out_0, out_1, … のポートになる）
+    #[serde(default)]
+    pub channels: Option<u8>,
+}
+
+fn default_client_name() -> String {
+    "yt-spout-syphon-bridge".to_string()
+}
+
+impl Default for AudioOutputSettings {
+    fn default() -> Self {
+        Self { client_name: default_client_name(), channels: None }
+    }
+}
+
+impl AudioOutputSettings {
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        let valid_name = !self.client_name.is_empty()
+            && self.client_name.len() <= 63
+            && self.client_name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ' ' | '.'));
+        if !valid_name {
+            return Err(anyhow::anyhow!(
+                "client_name は英数字と - _ . 空白の 1–63 文字で指定してください: {}",
+                self.client_name
+            ));
+        }
+        if let Some(channels) = self.channels {
+            if !(1..=64).contains(&channels) {
+                return Err(anyhow::anyhow!("channels は 1–64 で指定してください: {}", channels));
+            }
+        }
+        Ok(())
+    }
+
+    /// mpv の audio-channels の値
+    pub fn mpv_channels(&self) -> String {
+        match self.channels {
+            None => "auto-safe".to_string(),
+            Some(1) => "mono".to_string(),
+            Some(2) => "stereo".to_string(),
+            // チャンネル数だけを指定すると mpv が既定の配置を選ぶ
+            Some(n) => n.to_string(),
+        }
+    }
+}
+
+/// 音声のチャンネルの割り当て（mpv の af の pan フィルタで行う）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChannelRouting {
+    /// そのまま出力する
+    #[default]
+    Stereo,
+    /// L と R を混ぜて両方に出す
+    Mono,
+    /// L と R を入れ替える
+    Swap,
+    /// L・R をマルチチャンネルのインターフェースの 3・4 チャンネルに出す（1・2 は無音）
+    Channels34,
+}
+
+impl ChannelRouting {
+    /// af に追加するフィルタ（そのまま出力する場合は None）
+    pub fn filter(self) -> Option<&'static str> {
+        match self {
+            Self::Stereo => None,
+            Self::Mono => Some("@route:lavfi=[pan=stereo|c0=0.5*c0+0.5*c1|c1=0.5*c0+0.5*c1]"),
+            Self::Swap => Some("@route:lavfi=[pan=stereo|c0=c1|c1=c0]"),
+            Self::Channels34 => Some("@route:lavfi=[pan=quad|c2=c0|c3=c1]"),
+        }
+    }
+
+    /// 出力に必要なチャンネル数
+    pub fn min_channels(self) -> u8 {
+        match self {
+            Self::Channels34 => 4,
+            _ => 2,
+        }
+    }
+}
+
+/// 出力デバイスごとの遅延とチャンネルの割り当て
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct DeviceAudio {
+    /// 音声の遅延（秒。正の値で映像より遅らせる。mpv の audio-delay）
+    #[serde(default)]
+    pub delay: f64,
+    #[serde(default)]
+    pub routing: ChannelRouting,
+}
+
+/// 遅延の上限（秒）
+const MAX_DELAY: f64 = 10.0;
+
+impl DeviceAudio {
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        if !self.delay.is_finite() || self.delay.abs() > MAX_DELAY {
+            return Err(anyhow::anyhow!("delay は -{0}–{0} 秒で指定してください: {1}", MAX_DELAY, self.delay));
+        }
+        Ok(())
+    }
+}
+
+// ─── デバイスの監視 ──────────────────────────────────────────────────────────
+
+/// デバイスの増減を監視するスレッドを起動する
+///
+/// 一覧が変わるたびに新しい一覧で on_change を呼ぶ（アプリの終了まで動き続ける）。
+pub fn spawn_monitor(on_change: impl Fn(&[(String, String)]) + Send + 'static) {
+    std::thread::spawn(move || {
+        let mut known = enumerate_devices();
+        let mut events = device_events();
+        if events.is_none() {
+            log::info!("デバイスの変化を {} 秒ごとに確認します", POLL_INTERVAL.as_secs());
+        }
+
+        loop {
+            match &events {
+                Some(rx) => {
+                    if rx.recv().is_err() {
+                        log::warn!("デバイスの変化の通知が止まったため、定期的な確認に切り替えます");
+                        events = None;
+                        continue;
+                    }
+                    // 列挙のたびに JACK のクライアントを開くため、続けて届くイベントはまとめる
+                    wait_quiet(rx);
+                }
+                None => std::thread::sleep(POLL_INTERVAL),
+            }
+
+            let devices = enumerate_devices();
+            if devices != known {
+                log::info!("オーディオデバイスが変わりました: {} 件 → {} 件", known.len(), devices.len());
+                on_change(&devices);
+                known = devices;
+            }
+        }
+    });
+}
+
+/// イベントが DEBOUNCE の間途切れるまで（最長 MAX_DEBOUNCE）待ち、届いたイベントを捨てる
+fn wait_quiet(rx: &std::sync::mpsc::Receiver<()>) {
+    let deadline = std::time::Instant::now() + MAX_DEBOUNCE;
+    loop {
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        match rx.recv_timeout(DEBOUNCE.min(remaining)) {
+            Ok(()) => continue,
+            Err(_) => break,
+        }
+    }
+    while rx.try_recv().is_ok() {}
+}
+
+/// デバイスの増減の通知を受け取る（通知の仕組みが無い環境では None）
+fn device_events() -> Option<std::sync::mpsc::Receiver<()>> {
+    #[cfg(target_os = "macos")]
+    {
+        coreaudio_events()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let (tx, rx) = std::sync::mpsc::channel();
+        // PulseAudio のシンクの変化と、ALSA のデバイスファイルの増減の両方を見る
+        let pulse = pulse_events(tx.clone());
+        let snd = snd_events(tx);
+        (pulse || snd).then_some(rx)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+// ─── Linux: PulseAudio / PipeWire / ALSA / JACK ──────────────────────────────
+
+/// PulseAudio（PipeWire の PulseAudio 互換を含む）のシンクを列挙する（mpv の `pulse/<名前>` 形式）
+///
+/// libpulse に依存しないよう pactl を使う。JSON 出力（pactl 16 以降）が使えなければ short 形式を読む。
+#[cfg(target_os = "linux")]
+fn enumerate_pulse() -> Vec<(String, String)> {
+    let json = std::process::Command::new("pactl")
+        .args(["--format=json", "list", "sinks"])
+        .output();
+    let devices: Vec<(String, String)> = match json {
+        Ok(output) if output.status.success() => {
+            let sinks: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap_or_default();
+            sinks
+                .iter()
+                .filter_map(|sink| {
+                    let name = sink["name"].as_str()?;
+                    let description = sink["description"].as_str().unwrap_or(name);
+                    Some((format!("pulse/{}", name), format!("PulseAudio: {}", description)))
+                })
+                .collect()
+        }
+        Ok(_) => {
+            // 古い pactl: 番号・名前・ドライバ・サンプル形式・状態のタブ区切り
+            let Ok(output) = std::process::Command::new("pactl").args(["list", "short", "sinks"]).output() else {
+                return Vec::new();
+            };
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.split('\t').nth(1))
+                .map(|name| (format!("pulse/{}", name), format!("PulseAudio: {}", name)))
+                .collect()
+        }
+        Err(e) => {
+            log::debug!("pactl を実行できません（PulseAudio なし）: {}", e);
+            return Vec::new();
+        }
+    };
+    log::info!("PulseAudio のシンク: {} 件", devices.len());
+    devices
+}
+
+/// `pactl subscribe` を起動し、シンク・カード・サーバーの変化を tx に通知する（起動できれば true）
+#[cfg(target_os = "linux")]
+fn pulse_events(tx: std::sync::mpsc::Sender<()>) -> bool {
+    use std::io::BufRead;
+
+    let mut child = match std::process::Command::new("pactl")
+        .arg("subscribe")
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            log::debug!("pactl subscribe を起動できません: {}", e);
+            return false;
+        }
+    };
+    let Some(stdout) = child.stdout.take() else { return false };
+
+    std::thread::spawn(move || {
+        log::info!("PulseAudio のデバイスの変化を監視します");
+        // "Event 'new' on sink #57" などの行が届く
+        for line in std::io::BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if is_device_event(&line) && tx.send(()).is_err() {
+                break;
+            }
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+        log::info!("PulseAudio の監視を終了しました");
+    });
+    true
+}
+
+/// `pactl subscribe` の行がデバイスの増減か
+///
+/// 音量の変更でも "Event 'change' on sink" が届くため、シンク・カードは new / remove だけを見る。
+/// サーバーの change は既定のシンクの変更なので含める。
+#[cfg(any(target_os = "linux", test))]
+fn is_device_event(line: &str) -> bool {
+    let added_or_removed = line.starts_with("Event 'new'") || line.starts_with("Event 'remove'");
+    let device = line.contains(" on sink ") || line.contains(" on card ");
+    (added_or_removed && device) || line.ends_with(" on server")
+}
+
+/// `/dev/snd` のデバイスファイルの増減（USB オーディオの抜き差しなど）を inotify で tx に通知する
+///
+/// udev がデバイスファイルを作り直すため、PulseAudio を使わない環境でも抜き差しが分かる。
+#[cfg(target_os = "linux")]
+fn snd_events(tx: std::sync::mpsc::Sender<()>) -> bool {
+    use inotify::{Inotify, WatchMask};
+
+    let mut inotify = match Inotify::init() {
+        Ok(inotify) => inotify,
+        Err(e) => {
+            log::debug!("inotify を使えません: {}", e);
+            return false;
+        }
+    };
+    if let Err(e) = inotify.watches().add("/dev/snd", WatchMask::CREATE | WatchMask::DELETE) {
+        log::debug!("/dev/snd を監視できません: {}", e);
+        return false;
+    }
+
+    std::thread::spawn(move || {
+        log::info!("/dev/snd のデバイスの増減を監視します");
+        let mut buffer = [0u8; 1024];
+        loop {
+            match inotify.read_events_blocking(&mut buffer) {
+                Ok(events) => {
+                    if events.count() > 0 && tx.send(()).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    log::warn!("/dev/snd の監視に失敗: {}", e);
+                    break;
+                }
+            }
+        }
+        log::info!("/dev/snd の監視を終了しました");
+    });
+    true
+}
+
+/// ALSA の PCM を列挙する（mpv の `alsa/<名前>` 形式。再生できるものだけ）
+#[cfg(target_os = "linux")]
+fn enumerate_alsa() -> Vec<(String, String)> {
+    let hints = match alsa::device_name::HintIter::new_str(None, "pcm") {
+        Ok(hints) => hints,
+        Err(e) => {
+            log::debug!("ALSA のデバイスを列挙できません: {}", e);
+            return Vec::new();
+        }
+    };
+    let devices: Vec<(String, String)> = hints
+        .filter(|hint| hint.direction != Some(alsa::Direction::Capture))
+        .filter_map(|hint| {
+            let name = hint.name?;
+            if name == "null" {
+                return None;
+            }
+            // 説明は "カード名\n用途" の 2 行になっている
+            let description = hint.desc.map(|desc| desc.replace('\n', " / ")).unwrap_or_else(|| name.clone());
+            Some((format!("alsa/{}", name), format!("ALSA: {}", description)))
+        })
+        .collect();
+    log::info!("ALSA の PCM: {} 件", devices.len());
+    devices
+}
+
+/// PipeWire のシンクを列挙する（mpv の `pipewire/<node.name>` 形式）
+///
+/// libpipewire に依存しないよう pw-dump の JSON を読む。PipeWire が無い環境では空になる。
+#[cfg(target_os = "linux")]
+fn enumerate_pipewire() -> Vec<(String, String)> {
+    let output = match std::process::Command::new("pw-dump").output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            log::debug!("pw-dump が失敗しました: {}", output.status);
+            return Vec::new();
+        }
+        Err(e) => {
+            log::debug!("pw-dump を実行できません（PipeWire なし）: {}", e);
+            return Vec::new();
+        }
+    };
+    let Ok(objects) = serde_json::from_slice::<Vec<serde_json::Value>>(&output.stdout) else {
+        log::warn!("pw-dump の出力を解析できません");
+        return Vec::new();
+    };
+
+    let devices: Vec<(String, String)> = objects
+        .iter()
+        .filter(|object| object["type"] == "PipeWire:Interface:Node")
+        .map(|object| &object["info"]["props"])
+        .filter(|props| props["media.class"] == "Audio/Sink")
+        .filter_map(|props| {
+            let name = props["node.name"].as_str()?;
+            let description = props["node.description"].as_str().unwrap_or(name);
+            let label = match props["audio.channels"].as_u64() {
+                Some(channels) => format!("PipeWire: {} ({} ch)", description, channels),
+                None => format!("PipeWire: {}", description),
+            };
+            Some((format!("pipewire/{}", name), label))
+        })
+        .collect();
+    log::info!("PipeWire のシンク: {} 件", devices.len());
+    devices
+}
+
+/// JACK のクライアントのうち入力ポート（再生先）を持つものを列挙する
+///
+/// JACK サーバー（PipeWire の JACK 互換を含む）が動いていなければ空になる。
+/// mpv のクライアントは出力ポートしか持たないため、ここには現れない。
+#[cfg(target_os = "linux")]
+fn enumerate_jack() -> Vec<(String, String)> {
+    const AUDIO_PORT_TYPE: &str = "32 bit float mono audio";
+
+    let client = match jack::Client::new("yt-spout-syphon-bridge-list", jack::ClientOptions::NO_START_SERVER) {
+        Ok((client, _)) => client,
+        Err(e) => {
+            log::debug!("JACK に接続できません: {:?}", e);
+            return Vec::new();
+        }
+    };
+
+    // "クライアント名:ポート名" をクライアントごとに数える
+    let mut clients: Vec<(String, usize)> = Vec::new();
+    for port in client.ports(None, Some(AUDIO_PORT_TYPE), jack::PortFlags::IS_INPUT) {
+        let Some((name, _)) = port.split_once(':') else { continue };
+        match clients.iter_mut().find(|(client, _)| client == name) {
+            Some((_, count)) => *count += 1,
+            None => clients.push((name.to_string(), 1)),
+        }
+    }
+
+    let mut devices = vec![("jack".to_string(), "JACK（自動接続なし）".to_string())];
+    devices.extend(
+        clients
+            .into_iter()
+            .map(|(name, ports)| (format!("jack/{}", name), format!("JACK: {} ({} ch)", name, ports))),
+    );
+    log::info!("JACK の出力先: {} 件", devices.len());
+    devices
+}
+
 #[cfg(target_os = "macos")]
 fn enumerate_coreaudio() -> Vec<(String, String)> {
     use std::ffi::CStr;
@@ -206,3 +681,159 @@ fn enumerate_coreaudio() -> Vec<(String, String)> {
     log::info!("CoreAudio デバイス列挙完了: {} 件", devices.len());
     devices
 }
+
+/// CoreAudio のデバイス一覧（kAudioHardwarePropertyDevices）の変化をプロパティリスナーで受け取る
+#[cfg(target_os = "macos")]
+fn coreaudio_events() -> Option<std::sync::mpsc::Receiver<()>> {
+    use std::ffi::c_void;
+    use std::sync::mpsc::Sender;
+    use std::sync::Mutex;
+
+    type AudioObjectID = u32;
+    type OSStatus = i32;
+
+    #[repr(C)]
+    struct AudioObjectPropertyAddress {
+        selector: u32,
+        scope: u32,
+        element: u32,
+    }
+
+    type AudioObjectPropertyListenerProc = extern "C" fn(
+        object_id: AudioObjectID,
+        number_addresses: u32,
+        addresses: *const AudioObjectPropertyAddress,
+        client_data: *mut c_void,
+    ) -> OSStatus;
+
+    const K_AUDIO_OBJECT_SYSTEM_OBJECT: AudioObjectID = 1;
+    const K_AUDIO_HARDWARE_PROPERTY_DEVICES: u32 = u32::from_be_bytes(*b"dev#");
+    const K_AUDIO_OBJECT_PROPERTY_SCOPE_GLOBAL: u32 = u32::from_be_bytes(*b"glob");
+    const K_AUDIO_OBJECT_PROPERTY_ELEMENT_MAIN: u32 = 0;
+
+    #[link(name = "CoreAudio", kind = "framework")]
+    extern "C" {
+        fn AudioObjectAddPropertyListener(
+            object_id: AudioObjectID,
+            address: *const AudioObjectPropertyAddress,
+            listener: AudioObjectPropertyListenerProc,
+            client_data: *mut c_void,
+        ) -> OSStatus;
+    }
+
+    // CoreAudio の通知スレッドから呼ばれる
+    extern "C" fn on_devices_changed(
+        _object_id: AudioObjectID,
+        _number_addresses: u32,
+        _addresses: *const AudioObjectPropertyAddress,
+        client_data: *mut c_void,
+    ) -> OSStatus {
+        let tx = unsafe { &*(client_data as *const Mutex<Sender<()>>) };
+        if let Ok(tx) = tx.lock() {
+            let _ = tx.send(());
+        }
+        0
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    // リスナーはアプリの終了まで外さないため、送信側はリークさせて使い続ける
+    let client_data = Box::into_raw(Box::new(Mutex::new(tx)));
+    let address = AudioObjectPropertyAddress {
+        selector: K_AUDIO_HARDWARE_PROPERTY_DEVICES,
+        scope: K_AUDIO_OBJECT_PROPERTY_SCOPE_GLOBAL,
+        element: K_AUDIO_OBJECT_PROPERTY_ELEMENT_MAIN,
+    };
+    let status = unsafe {
+        AudioObjectAddPropertyListener(
+            K_AUDIO_OBJECT_SYSTEM_OBJECT,
+            &address,
+            on_devices_changed,
+            client_data as *mut c_void,
+        )
+    };
+    if status != 0 {
+        log::warn!("CoreAudio のデバイスの監視を開始できません (OSStatus {})", status);
+        drop(unsafe { Box::from_raw(client_data) });
+        return None;
+    }
+    log::info!("CoreAudio のデバイスの変化を監視します");
+    Some(rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_jack_device_ids() {
+        assert_eq!(parse_jack_device("jack"), Some(None));
+        assert_eq!(parse_jack_device("jack/system"), Some(Some("system")));
+        assert_eq!(parse_jack_device("jack/PulseAudio JACK Sink"), Some(Some("PulseAudio JACK Sink")));
+        assert_eq!(parse_jack_device("jack/"), None);
+        assert_eq!(parse_jack_device("jackd"), None);
+        assert_eq!(parse_jack_device("pipewire/jack"), None);
+        assert_eq!(parse_jack_device("auto"), None);
+    }
+
+    #[test]
+    fn maps_channel_counts_to_mpv() {
+        let with = |channels| AudioOutputSettings { channels, ..Default::default() };
+        assert_eq!(with(None).mpv_channels(), "auto-safe");
+        assert_eq!(with(Some(1)).mpv_channels(), "mono");
+        assert_eq!(with(Some(2)).mpv_channels(), "stereo");
+        assert_eq!(with(Some(8)).mpv_channels(), "8");
+    }
+
+    #[test]
+    fn validates_output_settings() {
+        assert!(AudioOutputSettings::default().validate().is_ok());
+        let named = |name: &str| AudioOutputSettings { client_name: name.to_string(), channels: None };
+        assert!(named("mixer in 1").validate().is_ok());
+        assert!(named("").validate().is_err());
+        assert!(named("a:b").validate().is_err());
+        assert!(named(&"a".repeat(64)).validate().is_err());
+        let channels = |n| AudioOutputSettings { channels: Some(n), ..Default::default() };
+        assert!(channels(64).validate().is_ok());
+        assert!(channels(0).validate().is_err());
+        assert!(channels(65).validate().is_err());
+    }
+
+    #[test]
+    fn routing_filters_and_channel_needs() {
+        assert_eq!(ChannelRouting::Stereo.filter(), None);
+        assert!(ChannelRouting::Swap.filter().unwrap().contains("c0=c1|c1=c0"));
+        assert!(ChannelRouting::Channels34.filter().unwrap().contains("pan=quad|c2=c0|c3=c1"));
+        assert_eq!(ChannelRouting::Channels34.min_channels(), 4);
+        assert_eq!(ChannelRouting::Mono.min_channels(), 2);
+    }
+
+    #[test]
+    fn validates_device_delay() {
+        assert!(DeviceAudio { delay: -MAX_DELAY, ..Default::default() }.validate().is_ok());
+        assert!(DeviceAudio { delay: MAX_DELAY + 0.5, ..Default::default() }.validate().is_err());
+        assert!(DeviceAudio { delay: f64::NAN, ..Default::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn only_device_changes_wake_the_monitor() {
+        assert!(is_device_event("Event 'new' on sink #57"));
+        assert!(is_device_event("Event 'remove' on card #3"));
+        assert!(is_device_event("Event 'change' on server"));
+        // 音量の変更・再生ストリームの増減では列挙し直さない
+        assert!(!is_device_event("Event 'change' on sink #57"));
+        assert!(!is_device_event("Event 'new' on sink-input #812"));
+        assert!(!is_device_event("Event 'remove' on source-output #12"));
+    }
+
+    #[test]
+    fn bursts_of_events_are_collapsed() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        for _ in 0..5 {
+            tx.send(()).unwrap();
+        }
+        let started = std::time::Instant::now();
+        wait_quiet(&rx);
+        assert!(rx.try_recv().is_err());
+        assert!(started.elapsed() >= DEBOUNCE && started.elapsed() < MAX_DEBOUNCE);
+    }
+}
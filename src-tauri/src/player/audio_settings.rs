@@ -0,0 +1,202 @@
+/// オーディオ出力の設定の保存と、出力デバイスの抜き差しへの対応
+///
+/// 選んだ出力デバイス（優先デバイス）と出力の設定はアプリのデータディレクトリの
+/// `audio.json` に保存し、次回の起動でも使う。
+///
+/// ## 優先デバイスが消えたとき（`DevicePolicy`）
+/// - `fallback`: 既定のデバイスに切り替え、戻ってきても切り替えない。保存した優先デバイスは
+///   書き換えず、次回の起動・デバイスの選び直しで再び使う
+/// - `pause`: 再生を一時停止する。戻ってきたら優先デバイスに戻す（再開は手動）
+/// - `retry`: 既定のデバイスで再生を続け、戻ってきたら優先デバイスに戻す
+///
+/// 遅延とチャンネルの割り当て（`DeviceAudio`）は出力デバイスごとに保存し、
+/// 出力先が切り替わるとそのデバイスの設定に切り替える。
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::audio::{AudioOutputSettings, ChannelRouting, DeviceAudio};
+use super::json_store::JsonStore;
+
+/// 優先デバイスが消えたときの動作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DevicePolicy {
+    Fallback,
+    Pause,
+    #[default]
+    Retry,
+}
+
+/// 保存するオーディオ出力の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioSettings {
+    /// 優先デバイス（mpv の audio-device の形式。"auto" は既定のデバイス）
+    #[serde(default = "default_device")]
+    pub device: String,
+    #[serde(default)]
+    pub policy: DevicePolicy,
+    #[serde(default)]
+    pub output: AudioOutputSettings,
+    /// 出力デバイスごとの遅延とチャンネルの割り当て（キーはデバイスの ID）
+    #[serde(default)]
+    pub devices: BTreeMap<String, DeviceAudio>,
+}
+
+fn default_device() -> String {
+    "auto".to_string()
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            device: default_device(),
+            policy: DevicePolicy::default(),
+            output: AudioOutputSettings::default(),
+            devices: BTreeMap::new(),
+        }
+    }
+}
+
+impl AudioSettings {
+    /// デバイスの遅延とチャンネルの割り当て（未設定なら既定値）
+    pub fn device_audio(&self, device: &str) -> DeviceAudio {
+        self.devices.get(device).copied().unwrap_or_default()
+    }
+
+    /// チャンネルの割り当てに必要なチャンネル数を満たす出力の設定
+    pub fn output_for(&self, routing: ChannelRouting) -> AudioOutputSettings {
+        let mut output = self.output.clone();
+        let channels = output.channels.unwrap_or(2);
+        if channels < routing.min_channels() {
+            output.channels = Some(routing.min_channels());
+        }
+        output
+    }
+}
+
+/// UI に返す優先デバイスの状況
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AudioDeviceStatus {
+    /// 優先デバイス
+    pub preferred: String,
+    pub policy: DevicePolicy,
+    /// 優先デバイスが接続されているか
+    pub available: bool,
+}
+
+/// デバイスの一覧が変わったときに行う操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceChange {
+    /// 優先デバイスが消えた
+    Lost,
+    /// 優先デバイスが戻った
+    Returned,
+}
+
+/// 優先デバイスの有無の変化を判定する（"auto" は常にあるものとして扱う）
+pub fn detect_change(preferred: &str, missing: bool, devices: &[(String, String)]) -> Option<DeviceChange> {
+    let present = preferred == "auto" || devices.iter().any(|(id, _)| id == preferred);
+    match (missing, present) {
+        (false, false) => Some(DeviceChange::Lost),
+        (true, true) => Some(DeviceChange::Returned),
+        _ => None,
+    }
+}
+
+/// 優先デバイスの有無が変わったときに出力先に対して行う操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceAction {
+    /// 既定のデバイスに切り替える
+    UseDefault,
+    /// 一時停止する（出力先は変えない）
+    Pause,
+    /// 優先デバイスに戻す
+    UsePreferred,
+    /// 何もしない
+    Keep,
+}
+
+/// 変化と動作の設定から出力先に対する操作を決める
+///
+/// fell_back は `fallback` で既定のデバイスに切り替えた後か（その間は戻ってきても追わない）。
+pub fn device_action(change: &DeviceChange, policy: DevicePolicy, fell_back: bool) -> DeviceAction {
+    match change {
+        DeviceChange::Lost => match policy {
+            DevicePolicy::Pause => DeviceAction::Pause,
+            DevicePolicy::Fallback | DevicePolicy::Retry => DeviceAction::UseDefault,
+        },
+        DeviceChange::Returned if fell_back => DeviceAction::Keep,
+        DeviceChange::Returned => DeviceAction::UsePreferred,
+    }
+}
+
+/// オーディオ出力の設定の保存先（`audio.json`）
+pub type AudioSettingsStore = JsonStore<AudioSettings>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn devices() -> Vec<(String, String)> {
+        vec![
+            ("auto".to_string(), "既定のデバイス".to_string()),
+            ("coreaudio/BuiltInSpeakerDevice".to_string(), "内蔵スピーカー".to_string()),
+        ]
+    }
+
+    #[test]
+    fn detects_lost_and_returned_devices() {
+        let usb = "coreaudio/USBAudio";
+        // 使っていた優先デバイスが一覧から消えた
+        assert_eq!(detect_change(usb, false, &devices()), Some(DeviceChange::Lost));
+        // 消えたままなら何もしない
+        assert_eq!(detect_change(usb, true, &devices()), None);
+
+        let mut with_usb = devices();
+        with_usb.push((usb.to_string(), "USB オーディオ".to_string()));
+        assert_eq!(detect_change(usb, true, &with_usb), Some(DeviceChange::Returned));
+        assert_eq!(detect_change(usb, false, &with_usb), None);
+    }
+
+    #[test]
+    fn auto_is_never_lost() {
+        assert_eq!(detect_change("auto", false, &[]), None);
+        // 以前に消えたと判定していても auto なら戻ったとみなす
+        assert_eq!(detect_change("auto", true, &[]), Some(DeviceChange::Returned));
+    }
+
+    #[test]
+    fn lost_device_follows_the_policy() {
+        let lost = DeviceChange::Lost;
+        assert_eq!(device_action(&lost, DevicePolicy::Fallback, false), DeviceAction::UseDefault);
+        assert_eq!(device_action(&lost, DevicePolicy::Retry, false), DeviceAction::UseDefault);
+        assert_eq!(device_action(&lost, DevicePolicy::Pause, false), DeviceAction::Pause);
+    }
+
+    #[test]
+    fn returned_device_is_used_again_unless_fallen_back() {
+        let returned = DeviceChange::Returned;
+        assert_eq!(device_action(&returned, DevicePolicy::Retry, false), DeviceAction::UsePreferred);
+        // pause は一時停止のまま出力先だけ戻す
+        assert_eq!(device_action(&returned, DevicePolicy::Pause, false), DeviceAction::UsePreferred);
+        assert_eq!(device_action(&returned, DevicePolicy::Fallback, true), DeviceAction::Keep);
+    }
+
+    #[test]
+    fn fallback_keeps_the_saved_preferred_device() {
+        let mut store = AudioSettingsStore::default();
+        store.update(|settings| {
+            settings.device = "coreaudio/USBAudio".to_string();
+            settings.policy = DevicePolicy::Fallback;
+        }).unwrap();
+        let change = detect_change(&store.get().device, false, &devices()).unwrap();
+        assert_eq!(device_action(&change, store.get().policy, false), DeviceAction::UseDefault);
+        // 切り替えは実行時の状態だけで行い、保存した優先デバイスは残る
+        assert_eq!(store.get().device, "coreaudio/USBAudio");
+    }
+
+    #[test]
+    fn matches_device_ids_not_descriptions() {
+        assert_eq!(detect_change("内蔵スピーカー", false, &devices()), Some(DeviceChange::Lost));
+    }
+}
@@ -0,0 +1,371 @@
+/// ネットワークのテンポに合わせた再生速度の自動調整（ビート同期）
+///
+/// クリップの BPM とセッションのテンポの比を基本の速度とし、クリップの拍の位相を
+/// セッションの拍の位相（`loop_beats` 拍の周期）に合わせ続ける。
+///
+/// ## 位相の合わせ方
+/// - クリップの拍位置 = (再生位置 - first_beat) × clip_bpm / 60
+/// - 位相差を秒に直し、チェイスと同じ `DriftCorrector`（drift.rs）で詰める
+/// - 位相差が `seek_threshold` 拍を超えたら、位相が合う位置へシークする
+/// - それ以内なら基本の速度から最大 ±`max_nudge` の範囲で速度を変えて差を縮める
+///
+/// テンポの受信は tempo.rs が行う。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::drift::{Correction, DriftAction, DriftCorrector, TICK};
+use super::events::{EventBus, PlayerEvent};
+use super::PlayerInner;
+use crate::tempo::{self, TempoReading};
+
+/// テンポがこの時間届かなければ Waiting とする
+const TEMPO_TIMEOUT: Duration = Duration::from_secs(2);
+/// Locked とみなす位相差（拍）
+const LOCK_TOLERANCE: f64 = 0.03;
+
+/// ビート同期の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeatSyncSettings {
+    /// テンポを受信する UDP ポート
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// クリップのテンポ
+    pub clip_bpm: f64,
+    /// 位相を合わせる周期（拍。4 なら 1 小節）
+    #[serde(default = "default_loop_beats")]
+    pub loop_beats: f64,
+    /// クリップの最初の拍の位置（秒）
+    #[serde(default)]
+    pub first_beat: f64,
+    /// 位相合わせの速度補正の上限（0.1 = ±10%）
+    #[serde(default = "default_max_nudge")]
+    pub max_nudge: f64,
+    /// これ以上位相がずれたらシークする（拍）
+    #[serde(default = "default_seek_threshold")]
+    pub seek_threshold: f64,
+}
+
+fn default_port() -> u16 {
+    tempo::DEFAULT_PORT
+}
+
+fn default_loop_beats() -> f64 {
+    4.0
+}
+
+fn default_max_nudge() -> f64 {
+    0.1
+}
+
+fn default_seek_threshold() -> f64 {
+    1.0
+}
+
+impl BeatSyncSettings {
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        if !(20.0..=999.0).contains(&self.clip_bpm) {
+            return Err(anyhow::anyhow!("clip_bpm は 20–999 で指定してください: {}", self.clip_bpm));
+        }
+        if !(1.0..=256.0).contains(&self.loop_beats) {
+            return Err(anyhow::anyhow!("loop_beats は 1–256 で指定してください: {}", self.loop_beats));
+        }
+        if !self.first_beat.is_finite() || self.first_beat < 0.0 {
+            return Err(anyhow::anyhow!("first_beat が不正です: {}", self.first_beat));
+        }
+        if !(0.0..=0.5).contains(&self.max_nudge) {
+            return Err(anyhow::anyhow!("max_nudge は 0.0–0.5 で指定してください: {}", self.max_nudge));
+        }
+        if !(0.1..=self.loop_beats).contains(&self.seek_threshold) {
+            return Err(anyhow::anyhow!(
+                "seek_threshold は 0.1–loop_beats で指定してください: {}",
+                self.seek_threshold
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// ビート同期の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BeatSyncState {
+    #[default]
+    Off,
+    /// テンポを待っている（未受信・途切れた）
+    Waiting,
+    /// 位相が大きくずれたためシークした
+    Seeking,
+    /// 速度を調整して位相を合わせている
+    Syncing,
+    /// 位相が合っている
+    Locked,
+}
+
+/// UI に返すビート同期の状況
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BeatSyncStatus {
+    pub state: BeatSyncState,
+    /// セッションのテンポ
+    pub session_bpm: Option<f64>,
+    /// クリップの位相 - セッションの位相（拍）
+    pub phase_error: Option<f64>,
+    /// ビート同期が設定している再生速度
+    pub speed: f64,
+}
+
+/// テンポと再生位置から操作を決める
+struct BeatSyncer {
+    settings: BeatSyncSettings,
+    tempo: Option<TempoReading>,
+    corrector: DriftCorrector,
+    phase_error: Option<f64>,
+    state: BeatSyncState,
+}
+
+impl BeatSyncer {
+    fn new(settings: BeatSyncSettings) -> Self {
+        // シークの閾値は拍で設定し、秒に直して渡す
+        let corrector = DriftCorrector::new(settings.seek_threshold * 60.0 / settings.clip_bpm, settings.max_nudge);
+        Self {
+            settings,
+            tempo: None,
+            corrector,
+            phase_error: None,
+            state: BeatSyncState::Waiting,
+        }
+    }
+
+    fn update(&mut self, now: Instant, position: f64) -> DriftAction {
+        let mut action = DriftAction::default();
+        let Some(tempo) = self.tempo.filter(|t| now.duration_since(t.received) < TEMPO_TIMEOUT) else {
+            // 途切れても直前の速度のまま再生を続ける
+            self.state = BeatSyncState::Waiting;
+            self.phase_error = None;
+            return action;
+        };
+
+        let settings = &self.settings;
+        let period = settings.loop_beats;
+        let session_phase = tempo.beat_at(now).rem_euclid(period);
+        let clip_phase = ((position - settings.first_beat) * settings.clip_bpm / 60.0).rem_euclid(period);
+        // -period/2 〜 period/2 に折り返す
+        let error = (clip_phase - session_phase + period / 2.0).rem_euclid(period) - period / 2.0;
+        self.phase_error = Some(error);
+
+        // 位相差を秒に直す（クリップの再生位置で 1 拍 = 60 / clip_bpm 秒）
+        let drift = error * 60.0 / settings.clip_bpm;
+        let target = (position - drift).max(0.0);
+        let nominal = tempo.bpm / settings.clip_bpm;
+        match self.corrector.correct(&mut action, now, drift, target, nominal) {
+            Correction::Settling => {}
+            Correction::Seeked => {
+                log::info!("位相差が {:.2} 拍のためシークします", error);
+                self.state = BeatSyncState::Seeking;
+            }
+            Correction::Nudged => {
+                self.state = if error.abs() <= LOCK_TOLERANCE { BeatSyncState::Locked } else { BeatSyncState::Syncing };
+            }
+        }
+        action
+    }
+
+    fn status(&self) -> BeatSyncStatus {
+        BeatSyncStatus {
+            state: self.state,
+            session_bpm: self.tempo.map(|t| t.bpm),
+            phase_error: self.phase_error,
+            speed: self.corrector.speed(),
+        }
+    }
+}
+
+// ─── 同期スレッド ────────────────────────────────────────────────────────────
+
+/// 動作中のビート同期
+pub struct BeatSyncHandle {
+    stop: Arc<AtomicBool>,
+    status: Arc<Mutex<BeatSyncStatus>>,
+}
+
+impl BeatSyncHandle {
+    /// 受信と同期のスレッドを止める（再生速度は 1.0 に戻る）
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn status(&self) -> BeatSyncStatus {
+        self.status.lock().map(|status| status.clone()).unwrap_or_default()
+    }
+}
+
+/// テンポの受信を開始し、ビート同期のスレッドを起動する
+///
+/// 再生セッションをまたいで動き続ける（mpv が無い間は何もしない）。
+pub(super) fn spawn(inner: Arc<Mutex<PlayerInner>>, settings: BeatSyncSettings, bus: EventBus) -> Result<BeatSyncHandle> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = std::sync::mpsc::channel();
+    tempo::spawn_receiver(settings.port, tx, stop.clone())?;
+
+    let status = Arc::new(Mutex::new(BeatSyncStatus {
+        state: BeatSyncState::Waiting,
+        speed: 1.0,
+        ..Default::default()
+    }));
+    let handle = BeatSyncHandle { stop: stop.clone(), status: status.clone() };
+
+    std::thread::spawn(move || {
+        log::info!("ビート同期を開始しました: {:?}", settings);
+        let mut syncer = BeatSyncer::new(settings);
+        bus.inject(PlayerEvent::BeatSync { state: BeatSyncState::Waiting });
+
+        while !stop.load(Ordering::Relaxed) {
+            match rx.recv_timeout(TICK) {
+                Ok(reading) => {
+                    // 溜まっている分は最新の値だけ使う
+                    syncer.tempo = Some(rx.try_iter().last().unwrap_or(reading));
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            let Ok(guard) = inner.lock() else { break };
+            let Some(mpv) = &guard.mpv else { continue };
+            if guard.props.paused {
+                continue;
+            }
+            let position = mpv.get_time_pos().unwrap_or(0.0);
+            let previous = syncer.state;
+            let action = syncer.update(Instant::now(), position);
+
+            if let Err(e) = action.apply(mpv) {
+                log::warn!("ビート同期の操作に失敗: {}", e);
+            }
+            drop(guard);
+
+            if let Ok(mut status) = status.lock() {
+                *status = syncer.status();
+            }
+            if syncer.state != previous {
+                log::info!("ビート同期の状態: {:?} → {:?}", previous, syncer.state);
+                bus.inject(PlayerEvent::BeatSync { state: syncer.state });
+            }
+        }
+
+        // 速度を戻す
+        if let Ok(guard) = inner.lock() {
+            if let Some(mpv) = &guard.mpv {
+                let _ = mpv.nudge_speed(1.0);
+            }
+        }
+        if let Ok(mut status) = status.lock() {
+            *status = BeatSyncStatus { speed: 1.0, ..Default::default() };
+        }
+        bus.inject(PlayerEvent::BeatSync { state: BeatSyncState::Off });
+        log::info!("ビート同期を終了しました");
+    });
+
+    Ok(handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::drift::CORRECTION_GAIN;
+
+    fn settings() -> BeatSyncSettings {
+        BeatSyncSettings {
+            port: tempo::DEFAULT_PORT,
+            clip_bpm: 120.0,
+            loop_beats: 4.0,
+            first_beat: 0.0,
+            max_nudge: 0.1,
+            seek_threshold: 1.0,
+        }
+    }
+
+    /// now の時点でセッションが beat 拍目にいるテンポ
+    fn syncer(bpm: f64, beat: f64, now: Instant) -> BeatSyncer {
+        let mut syncer = BeatSyncer::new(settings());
+        syncer.tempo = Some(TempoReading { bpm, beat, received: now });
+        syncer
+    }
+
+    #[test]
+    fn waits_without_tempo_or_after_timeout() {
+        let now = Instant::now();
+        let mut syncer = BeatSyncer::new(settings());
+        assert_eq!(syncer.update(now, 1.0), DriftAction::default());
+        assert_eq!(syncer.state, BeatSyncState::Waiting);
+
+        let mut syncer = self::syncer(120.0, 0.0, now);
+        syncer.update(now + TEMPO_TIMEOUT, 1.0);
+        assert_eq!(syncer.state, BeatSyncState::Waiting);
+        assert_eq!(syncer.phase_error, None);
+    }
+
+    #[test]
+    fn locks_when_in_phase_at_the_tempo_ratio() {
+        let now = Instant::now();
+        // セッション 126 BPM / クリップ 120 BPM → 1.05 倍速
+        let mut syncer = syncer(126.0, 2.0, now);
+        // クリップの 1 秒 = 2 拍目
+        let action = syncer.update(now, 1.0);
+        assert_eq!(action.seek, None);
+        assert!((action.speed.unwrap() - 1.05).abs() < 1e-9);
+        assert_eq!(syncer.state, BeatSyncState::Locked);
+        assert!(syncer.phase_error.unwrap().abs() < 1e-9);
+    }
+
+    #[test]
+    fn nudges_speed_within_max_nudge() {
+        let now = Instant::now();
+        // クリップが 0.1 拍（0.05 秒）進んでいる → 遅くする
+        let mut syncer = syncer(120.0, 2.0, now);
+        let action = syncer.update(now, 1.05);
+        assert_eq!(action.seek, None);
+        assert!((action.speed.unwrap() - (1.0 - 0.05 * CORRECTION_GAIN)).abs() < 1e-9);
+        assert_eq!(syncer.state, BeatSyncState::Syncing);
+
+        // 補正は max_nudge で頭打ちになる
+        let mut syncer = self::syncer(120.0, 2.0, now);
+        let action = syncer.update(now, 0.6);
+        assert!((action.speed.unwrap() - 1.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn seeks_on_large_phase_error_and_settles() {
+        let now = Instant::now();
+        let mut syncer = syncer(120.0, 0.0, now);
+        // 1.5 拍進んでいる → 0.75 秒戻して位相を合わせる
+        let action = syncer.update(now, 0.75);
+        assert!(action.seek.unwrap().abs() < 1e-9);
+        assert_eq!(syncer.state, BeatSyncState::Seeking);
+
+        // 位置が落ち着くまでは位相がずれていても何もしない
+        let action = syncer.update(now + Duration::from_millis(250), 0.75);
+        assert_eq!(action, DriftAction::default());
+        assert_eq!(syncer.state, BeatSyncState::Seeking);
+    }
+
+    #[test]
+    fn phase_wraps_around_the_loop() {
+        let now = Instant::now();
+        // セッションは 3.9 拍目、クリップは次の周期の 0.1 拍目 → +0.2 拍
+        let mut syncer = syncer(120.0, 3.9, now);
+        syncer.update(now, 0.05);
+        assert!((syncer.phase_error.unwrap() - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn validates_settings() {
+        assert!(settings().validate().is_ok());
+        assert!(BeatSyncSettings { clip_bpm: 10.0, ..settings() }.validate().is_err());
+        assert!(BeatSyncSettings { max_nudge: 0.6, ..settings() }.validate().is_err());
+        assert!(BeatSyncSettings { seek_threshold: 5.0, ..settings() }.validate().is_err());
+    }
+}
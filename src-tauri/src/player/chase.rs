@@ -0,0 +1,418 @@
+/// 外部タイムコードへの追従（チェイス）
+///
+/// 受信したタイムコード + オフセットを目標位置とし、再生位置を合わせ続ける。
+///
+/// ## 追従の方法
+/// - 差が `seek_threshold` を超えたら目標位置へ正確にシークする
+/// - それ以内なら再生速度を少しだけ変えて（最大 ±`max_nudge`）差を縮める
+/// - 差が 1 フレーム以内に収まったら Locked とする
+/// - タイムコードが止まったら一時停止して目標位置に合わせ、
+///   `freewheel` 秒以上届かなくなったら Waiting に戻る
+///
+/// 受信側で補正できない遅れ（オーディオ入力のバッファなど）は offset で調整する。
+/// シークと速度の微調整は drift.rs の `DriftCorrector` が行う。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use super::drift::{Correction, DriftAction, DriftCorrector, TICK};
+use super::events::{EventBus, PlayerEvent};
+use super::PlayerInner;
+use crate::timecode::{self, TimecodeReading, TimecodeSource};
+
+/// チェイスの設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChaseSettings {
+    pub source: TimecodeSource,
+    /// 動画の位置 = タイムコード + offset（秒。負の値も可）
+    #[serde(default)]
+    pub offset: f64,
+    /// これ以上ずれたらシークする（秒）
+    #[serde(default = "default_seek_threshold")]
+    pub seek_threshold: f64,
+    /// 速度補正の上限（0.05 = ±5%）
+    #[serde(default = "default_max_nudge")]
+    pub max_nudge: f64,
+    /// タイムコードが途切れてから追従をやめるまでの秒数
+    #[serde(default = "default_freewheel")]
+    pub freewheel: f64,
+}
+
+fn default_seek_threshold() -> f64 {
+    1.0
+}
+
+fn default_max_nudge() -> f64 {
+    0.05
+}
+
+fn default_freewheel() -> f64 {
+    0.5
+}
+
+impl ChaseSettings {
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        if !self.offset.is_finite() {
+            return Err(anyhow::anyhow!("offset が不正です: {}", self.offset));
+        }
+        if !(0.1..=10.0).contains(&self.seek_threshold) {
+            return Err(anyhow::anyhow!("seek_threshold は 0.1–10 秒で指定してください: {}", self.seek_threshold));
+        }
+        if !(0.0..=0.25).contains(&self.max_nudge) {
+            return Err(anyhow::anyhow!("max_nudge は 0.0–0.25 で指定してください: {}", self.max_nudge));
+        }
+        if !(0.0..=5.0).contains(&self.freewheel) {
+            return Err(anyhow::anyhow!("freewheel は 0–5 秒で指定してください: {}", self.freewheel));
+        }
+        if let TimecodeSource::Mtc { port } = &self.source {
+            if port.is_empty() {
+                return Err(anyhow::anyhow!("MIDI 入力ポートを指定してください"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 追従の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChaseState {
+    /// チェイスしていない
+    #[default]
+    Off,
+    /// タイムコードを待っている（未受信・途切れた）
+    Waiting,
+    /// タイムコードは止まっている（一時停止して位置を合わせている）
+    Stopped,
+    /// 大きくずれたためシークした
+    Seeking,
+    /// 速度を調整して差を縮めている
+    Chasing,
+    /// 1 フレーム以内で追従している
+    Locked,
+}
+
+/// UI に返すチェイスの状況
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChaseStatus {
+    pub state: ChaseState,
+    /// 最後に受信したタイムコード（"01:00:00:00" など）
+    pub timecode: Option<String>,
+    /// 再生位置 - 目標位置（秒）
+    pub drift: Option<f64>,
+    /// チェイスが設定している再生速度
+    pub speed: f64,
+}
+
+/// 目標位置と再生位置から操作を決める
+struct Chaser {
+    settings: ChaseSettings,
+    last: Option<TimecodeReading>,
+    /// タイムコードが進んでいるか
+    running: bool,
+    corrector: DriftCorrector,
+    state: ChaseState,
+}
+
+impl Chaser {
+    fn new(settings: ChaseSettings) -> Self {
+        let corrector = DriftCorrector::new(settings.seek_threshold, settings.max_nudge);
+        Self {
+            settings,
+            last: None,
+            running: false,
+            corrector,
+            state: ChaseState::Waiting,
+        }
+    }
+
+    fn on_reading(&mut self, reading: TimecodeReading) {
+        // 同じ値が繰り返し届く場合（停止中のフルフレーム）は止まっているとみなす
+        self.running = self.last.is_some_and(|last| reading.seconds > last.seconds);
+        self.last = Some(reading);
+    }
+
+    /// now 時点の目標位置
+    fn target(&self, now: Instant) -> Option<f64> {
+        let last = self.last?;
+        let elapsed = if self.running { now.duration_since(last.received).as_secs_f64() } else { 0.0 };
+        Some(last.seconds + elapsed + self.settings.offset)
+    }
+
+    fn update(&mut self, now: Instant, position: f64, paused: bool) -> DriftAction {
+        let mut action = DriftAction::default();
+        let lost = self.last.map_or(true, |last| {
+            now.duration_since(last.received).as_secs_f64() > self.settings.freewheel
+        });
+        let Some(target) = self.target(now).filter(|_| !lost) else {
+            self.state = ChaseState::Waiting;
+            self.running = false;
+            if !paused {
+                action.pause = Some(true);
+            }
+            self.corrector.set_speed(&mut action, 1.0);
+            return action;
+        };
+        let target = target.max(0.0);
+        let drift = position - target;
+        let frame = self.last.map_or(1.0 / 30.0, |last| 1.0 / last.timecode.rate.fps());
+
+        if !self.running {
+            self.state = ChaseState::Stopped;
+            if !paused {
+                action.pause = Some(true);
+            }
+            if drift.abs() > frame && !self.corrector.settling(now) {
+                self.corrector.seek(&mut action, target, now);
+            }
+            self.corrector.set_speed(&mut action, 1.0);
+            return action;
+        }
+
+        if paused {
+            action.pause = Some(false);
+        }
+        match self.corrector.correct(&mut action, now, drift, target, 1.0) {
+            Correction::Settling => {}
+            Correction::Seeked => {
+                log::info!("タイムコードとの差が {:.3} 秒のためシークします", drift);
+                self.state = ChaseState::Seeking;
+            }
+            Correction::Nudged => {
+                self.state = if drift.abs() <= frame { ChaseState::Locked } else { ChaseState::Chasing };
+            }
+        }
+        action
+    }
+
+    fn status(&self, drift: Option<f64>) -> ChaseStatus {
+        ChaseStatus {
+            state: self.state,
+            timecode: self.last.map(|last| last.timecode.to_string()),
+            drift,
+            speed: self.corrector.speed(),
+        }
+    }
+}
+
+// ─── チェイススレッド ────────────────────────────────────────────────────────
+
+/// 動作中のチェイス
+pub struct ChaseHandle {
+    stop: Arc<AtomicBool>,
+    status: Arc<Mutex<ChaseStatus>>,
+}
+
+impl ChaseHandle {
+    /// 受信とチェイスのスレッドを止める（再生速度は 1.0 に戻る）
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn status(&self) -> ChaseStatus {
+        self.status.lock().map(|status| status.clone()).unwrap_or_default()
+    }
+}
+
+/// タイムコードの受信を開始し、チェイススレッドを起動する
+///
+/// 再生セッションをまたいで動き続ける（mpv が無い間は何もしない）。
+pub(super) fn spawn(inner: Arc<Mutex<PlayerInner>>, settings: ChaseSettings, bus: EventBus) -> Result<ChaseHandle> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = std::sync::mpsc::channel();
+    timecode::spawn_source(&settings.source, tx, stop.clone())?;
+
+    let status = Arc::new(Mutex::new(ChaseStatus {
+        state: ChaseState::Waiting,
+        speed: 1.0,
+        ..Default::default()
+    }));
+    let handle = ChaseHandle { stop: stop.clone(), status: status.clone() };
+
+    std::thread::spawn(move || {
+        log::info!("タイムコードのチェイスを開始しました: {:?}", settings.source);
+        let mut chaser = Chaser::new(settings);
+        bus.inject(PlayerEvent::Chase { state: ChaseState::Waiting });
+
+        while !stop.load(Ordering::Relaxed) {
+            match rx.recv_timeout(TICK) {
+                Ok(reading) => {
+                    chaser.on_reading(reading);
+                    // 溜まっている分は最新の値だけ使う
+                    while let Ok(reading) = rx.try_recv() {
+                        chaser.on_reading(reading);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            let Ok(guard) = inner.lock() else { break };
+            let Some(mpv) = &guard.mpv else { continue };
+            let position = mpv.get_time_pos().unwrap_or(0.0);
+            let previous = chaser.state;
+            let action = chaser.update(Instant::now(), position, guard.props.paused);
+
+            if let Err(e) = action.apply(mpv) {
+                log::warn!("チェイスの操作に失敗: {}", e);
+            }
+            drop(guard);
+
+            let drift = chaser.target(Instant::now()).map(|target| position - target);
+            if let Ok(mut status) = status.lock() {
+                *status = chaser.status(drift);
+            }
+            if chaser.state != previous {
+                log::info!("チェイスの状態: {:?} → {:?}", previous, chaser.state);
+                bus.inject(PlayerEvent::Chase { state: chaser.state });
+            }
+        }
+
+        // 速度補正を戻す
+        if let Ok(guard) = inner.lock() {
+            if let Some(mpv) = &guard.mpv {
+                let _ = mpv.nudge_speed(1.0);
+            }
+        }
+        if let Ok(mut status) = status.lock() {
+            *status = ChaseStatus { speed: 1.0, ..Default::default() };
+        }
+        bus.inject(PlayerEvent::Chase { state: ChaseState::Off });
+        log::info!("タイムコードのチェイスを終了しました");
+    });
+
+    Ok(handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::drift::CORRECTION_GAIN;
+    use crate::timecode::{FrameRate, Timecode};
+    use std::time::Duration;
+
+    fn settings() -> ChaseSettings {
+        ChaseSettings {
+            source: TimecodeSource::Mtc { port: "test".to_string() },
+            offset: 0.0,
+            seek_threshold: default_seek_threshold(),
+            max_nudge: default_max_nudge(),
+            freewheel: default_freewheel(),
+        }
+    }
+
+    fn reading(seconds: f64, received: Instant) -> TimecodeReading {
+        let timecode = Timecode { hours: 0, minutes: 0, seconds: 0, frames: 0, rate: FrameRate::Fps25 };
+        TimecodeReading { timecode, seconds, received }
+    }
+
+    /// タイムコードが進んでいる状態（最後の受信が now、位置 10 秒）のチェイサー
+    fn running(now: Instant) -> Chaser {
+        let mut chaser = Chaser::new(settings());
+        chaser.on_reading(reading(9.96, now - Duration::from_millis(40)));
+        chaser.on_reading(reading(10.0, now));
+        chaser
+    }
+
+    #[test]
+    fn waits_and_pauses_without_timecode() {
+        let mut chaser = Chaser::new(settings());
+        let action = chaser.update(Instant::now(), 5.0, false);
+        assert_eq!(chaser.state, ChaseState::Waiting);
+        assert_eq!(action, DriftAction { pause: Some(true), ..Default::default() });
+    }
+
+    #[test]
+    fn seeks_past_the_threshold() {
+        let now = Instant::now();
+        let mut chaser = running(now);
+        let action = chaser.update(now, 11.5, false);
+        assert_eq!(chaser.state, ChaseState::Seeking);
+        assert_eq!(action.seek, Some(10.0));
+
+        // シーク直後は位置が落ち着くまで判定しない
+        let action = chaser.update(now + Duration::from_millis(100), 11.5, false);
+        assert_eq!(action, DriftAction::default());
+        assert_eq!(chaser.state, ChaseState::Seeking);
+    }
+
+    #[test]
+    fn nudges_within_the_threshold() {
+        let now = Instant::now();
+        // 閾値ちょうどまではシークせず速度で詰める（上限 ±5%）
+        let mut chaser = running(now);
+        let action = chaser.update(now, 10.0 + default_seek_threshold(), false);
+        assert_eq!(action.seek, None);
+        assert_eq!(action.speed, Some(1.0 - default_max_nudge()));
+        assert_eq!(chaser.state, ChaseState::Chasing);
+
+        let mut chaser = running(now);
+        let action = chaser.update(now, 10.0 - default_seek_threshold(), false);
+        assert_eq!(action.speed, Some(1.0 + default_max_nudge()));
+
+        // 上限より小さい補正は差に比例する
+        let mut chaser = running(now);
+        let action = chaser.update(now, 10.06, false);
+        let speed = action.speed.unwrap();
+        assert!((speed - (1.0 - 0.06 * CORRECTION_GAIN)).abs() < 1e-9);
+        assert_eq!(chaser.state, ChaseState::Chasing);
+    }
+
+    #[test]
+    fn locks_within_one_frame() {
+        let now = Instant::now();
+        let mut chaser = running(now);
+        chaser.update(now, 10.01, false);
+        assert_eq!(chaser.state, ChaseState::Locked);
+    }
+
+    #[test]
+    fn resumes_playback_when_timecode_runs() {
+        let now = Instant::now();
+        let mut chaser = running(now);
+        let action = chaser.update(now, 10.0, true);
+        assert_eq!(action.pause, Some(false));
+    }
+
+    #[test]
+    fn stopped_timecode_pauses_and_locates() {
+        let now = Instant::now();
+        let mut chaser = Chaser::new(settings());
+        // 停止中は同じ位置のフルフレームが繰り返し届く
+        chaser.on_reading(reading(20.0, now));
+        chaser.on_reading(reading(20.0, now));
+        let action = chaser.update(now, 18.0, false);
+        assert_eq!(chaser.state, ChaseState::Stopped);
+        assert_eq!(action.pause, Some(true));
+        assert_eq!(action.seek, Some(20.0));
+    }
+
+    #[test]
+    fn freewheels_then_waits() {
+        let now = Instant::now();
+        let mut chaser = running(now);
+        // freewheel の間は最後の値から進めた位置に合わせ続ける
+        let action = chaser.update(now + Duration::from_millis(400), 10.4, false);
+        assert_eq!(action.seek, None);
+        assert_eq!(chaser.state, ChaseState::Locked);
+
+        let action = chaser.update(now + Duration::from_millis(600), 10.6, false);
+        assert_eq!(chaser.state, ChaseState::Waiting);
+        assert_eq!(action.pause, Some(true));
+        assert_eq!(action.speed, None);
+    }
+
+    #[test]
+    fn offset_shifts_the_target() {
+        let now = Instant::now();
+        let mut chaser = Chaser::new(ChaseSettings { offset: -5.0, ..settings() });
+        chaser.on_reading(reading(9.96, now - Duration::from_millis(40)));
+        chaser.on_reading(reading(10.0, now));
+        let action = chaser.update(now, 0.0, false);
+        assert_eq!(action.seek, Some(5.0));
+    }
+}
@@ -0,0 +1,207 @@
+/// キューポイント（再生位置で発火するアクション）
+///
+/// クリップ（URL）ごとに「時刻 + アクション」を登録しておき、再生位置（`time-pos`）が
+/// その時刻を通過したときにアクションを実行する。判定はイベントディスパッチャの
+/// コールバック（`PlayerInner::apply_event`）で行う。
+/// クリップの切り替え（loadfile）はディスパッチャを止めないよう、予約して別スレッドで実行する。
+///
+/// ## 通過の判定
+/// 直前の再生位置から現在の再生位置までの間にある時刻を「通過」とみなす。
+/// シークや巻き戻しで位置が飛んだ場合（`MAX_CROSS_GAP` 以上の前進・後退）は発火しない。
+///
+/// ## 保存
+/// キューは URL ごとにアプリのデータディレクトリの `cues.json` に保存し、
+/// 同じ URL を再生したときに読み込む。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::json_store::JsonStore;
+use super::osc::{self, OscArg};
+use super::volume::{self, FadeCurve};
+
+/// 1 回の位置更新で通過とみなす最大の前進量（秒。これより大きい前進はシークとみなす）
+const MAX_CROSS_GAP: f64 = 2.0;
+
+/// キューで実行するアクション
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum CueAction {
+    /// 一時停止する
+    Pause,
+    /// 指定の位置（秒）へ移動する
+    Jump { time: f64 },
+    /// 別のクリップ（URL）に切り替える
+    SwitchClip { url: String },
+    /// OSC メッセージを送る
+    SendOsc {
+        host: String,
+        port: u16,
+        address: String,
+        #[serde(default)]
+        args: Vec<OscArg>,
+    },
+    /// 音量（%）を指定の秒数で変える（上限は音量の設定の max）
+    FadeVolume {
+        volume: f64,
+        duration: f64,
+        #[serde(default)]
+        curve: FadeCurve,
+    },
+}
+
+/// キューポイント
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cue {
+    /// UI が付ける識別子（発火の通知に使う）
+    pub id: String,
+    /// 発火する再生位置（秒）
+    pub time: f64,
+    #[serde(flatten)]
+    pub action: CueAction,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Cue {
+    /// 設定値を確認する
+    pub fn validate(&self) -> Result<()> {
+        if self.id.is_empty() {
+            return Err(anyhow::anyhow!("キューの id を指定してください"));
+        }
+        if !self.time.is_finite() || self.time < 0.0 {
+            return Err(anyhow::anyhow!("キューの時刻が不正です: {}", self.time));
+        }
+        match &self.action {
+            CueAction::Pause => Ok(()),
+            CueAction::Jump { time } if !time.is_finite() || *time < 0.0 => {
+                Err(anyhow::anyhow!("移動先の位置が不正です: {}", time))
+            }
+            CueAction::Jump { .. } => Ok(()),
+            CueAction::SwitchClip { url } => super::validate_url(url),
+            CueAction::SendOsc { host, address, .. } => {
+                if host.is_empty() {
+                    return Err(anyhow::anyhow!("OSC の送信先を指定してください"));
+                }
+                osc::validate_address(address)
+            }
+            CueAction::FadeVolume { volume, duration, .. } => {
+                if !volume.is_finite() || !(0.0..=volume::MAX_VOLUME_LIMIT).contains(volume) {
+                    return Err(anyhow::anyhow!("volume は 0–{} で指定してください: {}", volume::MAX_VOLUME_LIMIT, volume));
+                }
+                volume::validate_duration("duration", *duration)
+            }
+        }
+    }
+}
+
+/// previous から current への位置の変化で通過したキュー
+pub fn crossed(cues: &[Cue], previous: f64, current: f64) -> impl Iterator<Item = &Cue> {
+    let advancing = current > previous && current - previous <= MAX_CROSS_GAP;
+    cues.iter()
+        .filter(move |cue| advancing && cue.enabled && previous < cue.time && cue.time <= current)
+}
+
+// ─── 保存 ────────────────────────────────────────────────────────────────────
+
+/// URL ごとのキューの保存先（`cues.json`）
+#[derive(Debug, Default)]
+pub struct CueStore {
+    entries: JsonStore<HashMap<String, Vec<Cue>>>,
+}
+
+impl CueStore {
+    /// ファイルから読み込む（ファイルが無い・壊れている場合は空で始める）
+    pub fn load(path: PathBuf) -> Self {
+        Self { entries: JsonStore::load(path, "キュー") }
+    }
+
+    pub fn get(&self, url: &str) -> Vec<Cue> {
+        self.entries.get().get(url).cloned().unwrap_or_default()
+    }
+
+    /// URL のキューを置き換えて保存する（空の場合はエントリを消す）
+    pub fn set(&mut self, url: &str, mut cues: Vec<Cue>) -> Result<()> {
+        cues.sort_by(|a, b| a.time.total_cmp(&b.time));
+        self.entries.update(|entries| {
+            if cues.is_empty() {
+                entries.remove(url);
+            } else {
+                entries.insert(url.to_string(), cues);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(id: &str, time: f64) -> Cue {
+        Cue { id: id.to_string(), time, action: CueAction::Pause, enabled: true }
+    }
+
+    fn ids<'a>(cues: impl Iterator<Item = &'a Cue>) -> Vec<&'a str> {
+        cues.map(|cue| cue.id.as_str()).collect()
+    }
+
+    #[test]
+    fn fires_cues_passed_while_playing_forward() {
+        let cues = vec![cue("a", 1.0), cue("b", 1.5), cue("c", 3.0)];
+        assert_eq!(ids(crossed(&cues, 0.9, 1.5)), ["a", "b"]);
+        // 直前の位置ちょうどのキューは前回の更新で発火済み
+        assert_eq!(ids(crossed(&cues, 1.5, 2.0)), Vec::<&str>::new());
+        assert_eq!(ids(crossed(&cues, 2.9, 3.0)), ["c"]);
+    }
+
+    #[test]
+    fn fires_each_cue_once_across_position_updates() {
+        let cues = vec![cue("a", 1.0), cue("b", 2.0)];
+        let mut fired = Vec::new();
+        let mut previous = 0.0;
+        for step in 1..=60 {
+            let pos = step as f64 * 0.05;
+            fired.extend(ids(crossed(&cues, previous, pos)));
+            previous = pos;
+        }
+        assert_eq!(fired, ["a", "b"]);
+    }
+
+    #[test]
+    fn seeks_do_not_fire() {
+        let cues = vec![cue("a", 5.0), cue("b", 30.0)];
+        // 前方へのシーク（MAX_CROSS_GAP を超える前進）
+        assert_eq!(ids(crossed(&cues, 1.0, 40.0)), Vec::<&str>::new());
+        // 後方へのシーク・巻き戻し
+        assert_eq!(ids(crossed(&cues, 40.0, 4.0)), Vec::<&str>::new());
+        assert_eq!(ids(crossed(&cues, 5.5, 4.9)), Vec::<&str>::new());
+        // 巻き戻した後に再び通過すれば発火する
+        assert_eq!(ids(crossed(&cues, 4.9, 5.1)), ["a"]);
+    }
+
+    #[test]
+    fn disabled_cues_do_not_fire() {
+        let mut cues = vec![cue("a", 1.0)];
+        cues[0].enabled = false;
+        assert_eq!(ids(crossed(&cues, 0.5, 1.5)), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn validates_cues() {
+        assert!(cue("a", 1.0).validate().is_ok());
+        assert!(cue("", 1.0).validate().is_err());
+        assert!(cue("a", -1.0).validate().is_err());
+        let jump = Cue { action: CueAction::Jump { time: f64::NAN }, ..cue("a", 1.0) };
+        assert!(jump.validate().is_err());
+        let osc = Cue {
+            action: CueAction::SendOsc { host: "127.0.0.1".to_string(), port: 9000, address: "go".to_string(), args: Vec::new() },
+            ..cue("a", 1.0)
+        };
+        assert!(osc.validate().is_err());
+    }
+}
@@ -0,0 +1,171 @@
+/// 目標位置への追従（チェイス・複数台の同期で共通の位置合わせ）
+///
+/// 再生位置と目標位置の差（ドリフト）を次のように詰める。
+/// - 差が `seek_threshold` を超えたら目標位置へ正確にシークし、位置が落ち着くまで判定を止める
+/// - それ以内なら基準の速度から最大 ±`max_nudge` の範囲で速度を変えて差を縮める
+///
+/// 目標位置の求め方・一時停止の扱い・状態の名前は呼び出し側（chase.rs / net_sync.rs）が決める。
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+use super::seek::SeekRequest;
+use super::MpvContext;
+
+/// 追従を判定する間隔
+pub const TICK: Duration = Duration::from_millis(40);
+/// シーク後、位置が落ち着くまで差を判定しない時間
+const SEEK_SETTLE: Duration = Duration::from_millis(500);
+/// 差（秒）に対する速度補正の強さ（差を約 2 秒で詰める）
+pub const CORRECTION_GAIN: f64 = 0.5;
+
+/// 1 回の判定で行う操作
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DriftAction {
+    pub seek: Option<f64>,
+    pub pause: Option<bool>,
+    pub speed: Option<f64>,
+}
+
+impl DriftAction {
+    /// 操作を mpv に反映する
+    pub fn apply(&self, mpv: &MpvContext) -> Result<()> {
+        if let Some(target) = self.seek {
+            mpv.seek_with(&SeekRequest::exact(target))?;
+        }
+        if let Some(paused) = self.pause {
+            mpv.set_pause(paused)?;
+        }
+        if let Some(speed) = self.speed {
+            mpv.nudge_speed(speed)?;
+        }
+        Ok(())
+    }
+}
+
+/// 再生中の位置合わせの結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Correction {
+    /// シーク・切り替えの直後で位置が落ち着いていない（何もしない）
+    Settling,
+    /// 大きくずれたためシークした
+    Seeked,
+    /// 速度を調整した（差が小さければ速度は基準のまま）
+    Nudged,
+}
+
+/// ドリフトをシークと速度の微調整で詰める
+#[derive(Debug)]
+pub struct DriftCorrector {
+    /// これ以上ずれたらシークする（秒）
+    seek_threshold: f64,
+    /// 速度補正の上限（0.05 = ±5%）
+    max_nudge: f64,
+    /// シーク・切り替え後に判定を再開する時刻
+    settle_until: Option<Instant>,
+    /// 設定している再生速度
+    speed: f64,
+}
+
+impl DriftCorrector {
+    pub fn new(seek_threshold: f64, max_nudge: f64) -> Self {
+        Self { seek_threshold, max_nudge, settle_until: None, speed: 1.0 }
+    }
+
+    /// 設定している再生速度
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// シーク・切り替えの直後で位置が落ち着いていないか
+    pub fn settling(&self, now: Instant) -> bool {
+        self.settle_until.is_some_and(|until| now < until)
+    }
+
+    /// now から duration の間は判定を止める（クリップの読み込みを待つ場合など）
+    pub fn settle(&mut self, now: Instant, duration: Duration) {
+        self.settle_until = Some(now + duration);
+    }
+
+    /// target へシークし、位置が落ち着くまで判定を止める
+    pub fn seek(&mut self, action: &mut DriftAction, target: f64, now: Instant) {
+        action.seek = Some(target);
+        self.settle(now, SEEK_SETTLE);
+    }
+
+    /// 速度が変わる場合だけ操作に入れる（mpv への設定を減らす）
+    pub fn set_speed(&mut self, action: &mut DriftAction, speed: f64) {
+        if (speed - self.speed).abs() >= 0.001 {
+            self.speed = speed;
+            action.speed = Some(speed);
+        }
+    }
+
+    /// 再生中のドリフト（再生位置 - target）を base_speed を基準に詰める
+    pub fn correct(&mut self, action: &mut DriftAction, now: Instant, drift: f64, target: f64, base_speed: f64) -> Correction {
+        if self.settling(now) {
+            return Correction::Settling;
+        }
+        if drift.abs() > self.seek_threshold {
+            self.seek(action, target, now);
+            self.set_speed(action, base_speed);
+            return Correction::Seeked;
+        }
+        let max = self.max_nudge;
+        let speed = base_speed * (1.0 - (drift * CORRECTION_GAIN).clamp(-max, max));
+        self.set_speed(action, speed);
+        Correction::Nudged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeks_past_the_threshold_then_settles() {
+        let now = Instant::now();
+        let mut corrector = DriftCorrector::new(1.0, 0.05);
+        let mut action = DriftAction::default();
+        assert_eq!(corrector.correct(&mut action, now, 1.5, 10.0, 1.0), Correction::Seeked);
+        assert_eq!(action, DriftAction { seek: Some(10.0), ..Default::default() });
+
+        let mut action = DriftAction::default();
+        let later = now + SEEK_SETTLE - Duration::from_millis(1);
+        assert_eq!(corrector.correct(&mut action, later, 1.5, 10.0, 1.0), Correction::Settling);
+        assert_eq!(action, DriftAction::default());
+        assert_eq!(corrector.correct(&mut action, now + SEEK_SETTLE, 0.0, 10.0, 1.0), Correction::Nudged);
+    }
+
+    #[test]
+    fn nudges_in_proportion_up_to_the_limit() {
+        let now = Instant::now();
+        let mut corrector = DriftCorrector::new(1.0, 0.05);
+        let mut action = DriftAction::default();
+        corrector.correct(&mut action, now, 0.06, 10.0, 1.0);
+        assert!((action.speed.unwrap() - (1.0 - 0.06 * CORRECTION_GAIN)).abs() < 1e-9);
+
+        // 閾値ちょうどまではシークしない（上限で頭打ち）
+        let mut action = DriftAction::default();
+        assert_eq!(corrector.correct(&mut action, now, -1.0, 10.0, 1.0), Correction::Nudged);
+        assert_eq!(action.speed, Some(1.05));
+
+        // 基準の速度に対する割合で補正する
+        let mut action = DriftAction::default();
+        corrector.correct(&mut action, now, 1.0, 10.0, 2.0);
+        assert_eq!(action.speed, Some(1.9));
+    }
+
+    #[test]
+    fn unchanged_speed_is_not_sent_again() {
+        let now = Instant::now();
+        let mut corrector = DriftCorrector::new(1.0, 0.05);
+        let mut action = DriftAction::default();
+        corrector.correct(&mut action, now, 0.0, 10.0, 1.0);
+        assert_eq!(action.speed, None);
+        corrector.correct(&mut action, now, 0.5, 10.0, 1.0);
+        assert_eq!(corrector.speed(), 0.95);
+        let mut action = DriftAction::default();
+        corrector.correct(&mut action, now, 0.5, 10.0, 1.0);
+        assert_eq!(action.speed, None);
+    }
+}
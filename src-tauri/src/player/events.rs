@@ -0,0 +1,514 @@
+/// mpv イベントディスパッチャ
+///
+/// ## 仕組み
+/// 1. `spawn()` が `mpv_create_client` で専用のクライアントハンドルを作成する
+///    （メインハンドルとはイベントキューが独立するため、Syphon スレッド等と競合しない）
+/// 2. 監視対象のプロパティと warn 以上のログを購読する（loadfile 前に同期的に行う）
+/// 3. 専用スレッドで `mpv_wait_event` を回し、生イベントを `PlayerEvent` に変換する
+///    （`EventBus::inject` されたイベントは `mpv_wakeup` で待ちを解いてすぐに配る）
+/// 4. 変換したイベントを PlayerState（コールバック）・UI（Tauri Event）・
+///    `EventBus` の購読者（Syphon スレッド、コントロールサーバー等）に配る
+/// 5. mpv 本体が破棄されると SHUTDOWN が届き、スレッドは自動で終了する
+///
+/// 出力スレッドなど mpv 以外で起きた失敗は `EventBus::inject` でディスパッチャに渡し、
+/// mpv のイベントと同じ経路で配る。ディスパッチャが動いていない間（再生していない間）は
+/// 購読者と UI に直接配る（チェイス・ビート同期・複数台の同期・メーターは再生セッションを
+/// またいで動くため、その間の状態の変化も UI に届ける）。
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::ffi::CStr;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use tauri::Emitter;
+
+use super::beat_sync::BeatSyncState;
+use super::chase::ChaseState;
+use super::meter::MeterLevels;
+use super::net_sync::NetSyncState;
+
+// ─── 型付きイベント ──────────────────────────────────────────────────────────
+
+/// END_FILE の終了理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EndFileReason {
+    /// 最後まで再生した
+    Eof,
+    /// stop / loadfile replace などで中断された
+    Stop,
+    /// mpv が終了した
+    Quit,
+    /// 読み込み・デコードに失敗した
+    Error,
+    /// プレイリスト等へリダイレクトされた
+    Redirect,
+    Unknown,
+}
+
+/// 監視しているプロパティの変更通知
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "name", content = "value", rename_all = "kebab-case")]
+pub enum PropertyChange {
+    Pause(bool),
+    TimePos(f64),
+    Duration(f64),
+    Speed(f64),
+    Volume(f64),
+    /// キャッシュ不足で mpv が自動一時停止しているか
+    PausedForCache(bool),
+    /// キャッシュ充填率（0–100）
+    CacheBufferingState(i64),
+    /// 先読み済みのキャッシュ秒数
+    DemuxerCacheDuration(f64),
+    IdleActive(bool),
+    Width(i64),
+    Height(i64),
+    /// メディアのタイトル（YouTube では動画タイトル）
+    MediaTitle(String),
+    /// 表示中の字幕テキスト（字幕が無い間は空文字列）
+    SubText(String),
+}
+
+/// mpv から届くイベントを Rust の列挙型に変換したもの
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum PlayerEvent {
+    StartFile,
+    FileLoaded,
+    EndFile {
+        reason: EndFileReason,
+        /// reason=error の場合の mpv エラーメッセージ
+        error: Option<String>,
+    },
+    Idle,
+    Seek,
+    PlaybackRestart,
+    VideoReconfig,
+    AudioReconfig,
+    PropertyChange(PropertyChange),
+    /// warn 以上の mpv ログ（エラー分類に使う）
+    LogMessage {
+        prefix: String,
+        level: String,
+        text: String,
+    },
+    /// 出力（Syphon / Spout）スレッドの失敗
+    OutputError {
+        output: String,
+        message: String,
+    },
+    /// ウォッチドッグによる自動復旧の進捗
+    Recovery {
+        phase: RecoveryPhase,
+        /// 何回目の復旧試行か（Recovered / GaveUp では最後の試行回数）
+        attempt: u32,
+        /// Scheduled の場合、再読み込みまでの待ち時間
+        delay_ms: u64,
+        /// 復旧のきっかけになった障害
+        reason: String,
+    },
+    /// タイムコードのチェイスの状態が変わった
+    Chase {
+        state: ChaseState,
+    },
+    /// ビート同期の状態が変わった
+    BeatSync {
+        state: BeatSyncState,
+    },
+    /// 複数台の同期の状態が変わった
+    NetSync {
+        state: NetSyncState,
+    },
+    /// メーターの測定値（設定した頻度で届く）
+    AudioMeter(MeterLevels),
+    /// キューポイントを通過してアクションを実行した
+    CueFired {
+        id: String,
+        /// キューの時刻（秒）
+        time: f64,
+    },
+    Shutdown,
+}
+
+/// 復旧処理の段階
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecoveryPhase {
+    /// 障害を検知し、バックオフ後の再読み込みを予約した
+    Scheduled,
+    /// 再読み込みを実行した
+    Reloading,
+    /// 再生が安定し、復旧が完了した
+    Recovered,
+    /// 最大試行回数に達したため諦めた
+    GaveUp,
+}
+
+// ─── イベントバス ────────────────────────────────────────────────────────────
+
+/// 注入したイベントを溜めておく上限（超えたら古いものから捨てる）
+const MAX_INJECTED: usize = 256;
+
+/// `PlayerEvent` を複数の購読者に配るファンアウト
+///
+/// 購読者ごとに mpsc チャンネルを持つ。受信側が破棄された購読者は次回 publish 時に取り除く。
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<mpsc::Sender<PlayerEvent>>>>,
+    /// ディスパッチャが次のループで配信する、mpv 以外から注入されたイベント
+    injected: Arc<Mutex<Injected>>,
+}
+
+/// 注入されたイベントの待ち行列と、それを配るディスパッチャ
+#[derive(Default)]
+struct Injected {
+    queue: VecDeque<PlayerEvent>,
+    /// 動作中のディスパッチャのクライアントハンドル（注入時に mpv_wait_event から起こす）
+    dispatcher: Option<SendableMpvHandle>,
+    /// ディスパッチャが動いていない間に `mpv-event` を送る先
+    app: Option<tauri::AppHandle>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 新しい購読者を登録し、受信側を返す
+    pub fn subscribe(&self) -> mpsc::Receiver<PlayerEvent> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subs) = self.subscribers.lock() {
+            subs.push(tx);
+        }
+        rx
+    }
+
+    /// すべての購読者にイベントを送る
+    pub fn publish(&self, event: &PlayerEvent) {
+        if let Ok(mut subs) = self.subscribers.lock() {
+            subs.retain(|tx| tx.send(event.clone()).is_ok());
+        }
+    }
+
+    /// ディスパッチャが動いていない間に注入されたイベントを UI にも送る
+    pub fn set_app_handle(&self, app: tauri::AppHandle) {
+        if let Ok(mut injected) = self.injected.lock() {
+            injected.app = Some(app);
+        }
+    }
+
+    /// mpv 以外（出力スレッド等）で起きたイベントをディスパッチャ経由で配信する
+    ///
+    /// PlayerState のコールバックにも届くよう、直接 publish せずにディスパッチャへ渡す。
+    /// ディスパッチャが動いていない間（再生していない間）は、コールバックを通さずに
+    /// 購読者と UI（`mpv-event`）へ直接配る。
+    pub fn inject(&self, event: PlayerEvent) {
+        let Ok(mut injected) = self.injected.lock() else { return };
+        let Some(client) = &injected.dispatcher else {
+            let app = injected.app.clone();
+            drop(injected);
+            self.publish(&event);
+            if let Some(app) = app {
+                let _ = app.emit("mpv-event", &event);
+            }
+            return;
+        };
+        let client = client.0;
+        if injected.queue.len() >= MAX_INJECTED {
+            injected.queue.pop_front();
+        }
+        injected.queue.push_back(event);
+        // ロックを持ったまま起こす（detach 後の破棄済みハンドルに触れないため）
+        unsafe { libmpv2_sys::mpv_wakeup(client) };
+    }
+
+    fn take_injected(&self) -> Vec<PlayerEvent> {
+        self.injected
+            .lock()
+            .map(|mut injected| injected.queue.drain(..).collect())
+            .unwrap_or_default()
+    }
+
+    /// ディスパッチャを登録する（以後の inject でこのクライアントを起こす）
+    fn attach(&self, client: *mut libmpv2_sys::mpv_handle) {
+        if let Ok(mut injected) = self.injected.lock() {
+            injected.queue.clear();
+            injected.dispatcher = Some(SendableMpvHandle(client));
+        }
+    }
+
+    /// ディスパッチャの登録を外し、配り残したイベントを捨てる（クライアントを破棄する前に呼ぶ）
+    fn detach(&self, client: *mut libmpv2_sys::mpv_handle) {
+        if let Ok(mut injected) = self.injected.lock() {
+            if injected.dispatcher.as_ref().is_some_and(|handle| handle.0 == client) {
+                injected.dispatcher = None;
+                injected.queue.clear();
+            }
+        }
+    }
+}
+
+// ─── ディスパッチャ ──────────────────────────────────────────────────────────
+
+use libmpv2_sys::{
+    mpv_end_file_reason_MPV_END_FILE_REASON_EOF as REASON_EOF,
+    mpv_end_file_reason_MPV_END_FILE_REASON_ERROR as REASON_ERROR,
+    mpv_end_file_reason_MPV_END_FILE_REASON_QUIT as REASON_QUIT,
+    mpv_end_file_reason_MPV_END_FILE_REASON_REDIRECT as REASON_REDIRECT,
+    mpv_end_file_reason_MPV_END_FILE_REASON_STOP as REASON_STOP,
+    mpv_event_id_MPV_EVENT_AUDIO_RECONFIG as EV_AUDIO_RECONFIG,
+    mpv_event_id_MPV_EVENT_END_FILE as EV_END_FILE,
+    mpv_event_id_MPV_EVENT_FILE_LOADED as EV_FILE_LOADED,
+    mpv_event_id_MPV_EVENT_IDLE as EV_IDLE,
+    mpv_event_id_MPV_EVENT_LOG_MESSAGE as EV_LOG_MESSAGE,
+    mpv_event_id_MPV_EVENT_NONE as EV_NONE,
+    mpv_event_id_MPV_EVENT_PLAYBACK_RESTART as EV_PLAYBACK_RESTART,
+    mpv_event_id_MPV_EVENT_PROPERTY_CHANGE as EV_PROPERTY_CHANGE,
+    mpv_event_id_MPV_EVENT_SEEK as EV_SEEK,
+    mpv_event_id_MPV_EVENT_SHUTDOWN as EV_SHUTDOWN,
+    mpv_event_id_MPV_EVENT_START_FILE as EV_START_FILE,
+    mpv_event_id_MPV_EVENT_VIDEO_RECONFIG as EV_VIDEO_RECONFIG,
+    mpv_format_MPV_FORMAT_DOUBLE as FMT_DOUBLE,
+    mpv_format_MPV_FORMAT_FLAG as FMT_FLAG,
+    mpv_format_MPV_FORMAT_INT64 as FMT_INT64,
+    mpv_format_MPV_FORMAT_STRING as FMT_STRING,
+};
+
+/// 監視するプロパティ一覧（reply_userdata, 名前, フォーマット）
+const OBSERVED_PROPERTIES: &[(u64, &str, libmpv2_sys::mpv_format)] = &[
+    (1, "width", FMT_INT64),
+    (2, "height", FMT_INT64),
+    (3, "pause", FMT_FLAG),
+    (4, "time-pos", FMT_DOUBLE),
+    (5, "duration", FMT_DOUBLE),
+    (6, "speed", FMT_DOUBLE),
+    (7, "volume", FMT_DOUBLE),
+    (8, "paused-for-cache", FMT_FLAG),
+    (9, "cache-buffering-state", FMT_INT64),
+    (10, "demuxer-cache-duration", FMT_DOUBLE),
+    (11, "idle-active", FMT_FLAG),
+    (12, "media-title", FMT_STRING),
+    (13, "sub-text", FMT_STRING),
+];
+
+/// mpv クライアントハンドルのラッパー（スレッド間移動用）
+struct SendableMpvHandle(*mut libmpv2_sys::mpv_handle);
+unsafe impl Send for SendableMpvHandle {}
+
+/// イベントディスパッチャスレッドを起動する
+///
+/// # 引数
+/// * `mpv_handle` - mpv メインハンドルの生ポインタ（専用クライアントの作成元）
+/// * `bus` - イベントを配る `EventBus`
+/// * `app_handle` - Tauri AppHandle（`mpv-event` として UI に送信。None なら送信しない）
+/// * `on_event` - PlayerState の更新用コールバック（ディスパッチャスレッドで呼ばれる）。
+///   mpv のイベントなしで戻った場合（タイムアウト・注入による起床）は None で呼ばれ、
+///   ストール検知などの時間経過処理に使う
+pub fn spawn<F>(
+    mpv_handle: *mut libmpv2_sys::mpv_handle,
+    bus: EventBus,
+    app_handle: Option<tauri::AppHandle>,
+    mut on_event: F,
+) -> Result<()>
+where
+    F: FnMut(Option<&PlayerEvent>) + Send + 'static,
+{
+    // 専用クライアントを作成し、loadfile より前にプロパティ監視を登録しておく
+    let client = unsafe {
+        let name = std::ffi::CString::new("event-dispatcher").unwrap();
+        let client = libmpv2_sys::mpv_create_client(mpv_handle, name.as_ptr());
+        if client.is_null() {
+            return Err(anyhow::anyhow!("mpv クライアントハンドルの作成に失敗"));
+        }
+        for (id, prop, format) in OBSERVED_PROPERTIES {
+            let prop_cstr = std::ffi::CString::new(*prop).unwrap();
+            let ret = libmpv2_sys::mpv_observe_property(client, *id, prop_cstr.as_ptr(), *format);
+            if ret < 0 {
+                log::warn!("observe_property に失敗: {} (エラーコード: {})", prop, ret);
+            }
+        }
+        let level = std::ffi::CString::new("warn").unwrap();
+        libmpv2_sys::mpv_request_log_messages(client, level.as_ptr());
+        SendableMpvHandle(client)
+    };
+    bus.attach(client.0);
+
+    std::thread::spawn(move || {
+        let client = client;
+        log::info!("mpv イベントディスパッチャを開始しました");
+        // None（タイムアウト）はコールバックにだけ渡す
+        let mut dispatch = |event: Option<&PlayerEvent>| {
+            on_event(event);
+            if let Some(event) = event {
+                bus.publish(event);
+                if let Some(app) = &app_handle {
+                    let _ = app.emit("mpv-event", event);
+                }
+            }
+        };
+        loop {
+            for injected in bus.take_injected() {
+                dispatch(Some(&injected));
+            }
+
+            let event = unsafe { next_event(client.0) };
+            dispatch(event.as_ref());
+            if event == Some(PlayerEvent::Shutdown) {
+                break;
+            }
+        }
+        // SHUTDOWN を受け取ったクライアントは自分で破棄する必要がある
+        bus.detach(client.0);
+        unsafe { libmpv2_sys::mpv_destroy(client.0) };
+        log::info!("mpv イベントディスパッチャを終了しました");
+    });
+
+    Ok(())
+}
+
+/// mpv_wait_event で次のイベントを待ち、`PlayerEvent` に変換する
+///
+/// 変換対象外のイベントやタイムアウトの場合は None を返す。
+unsafe fn next_event(client: *mut libmpv2_sys::mpv_handle) -> Option<PlayerEvent> {
+    let event = libmpv2_sys::mpv_wait_event(client, 1.0);
+    if event.is_null() {
+        return None;
+    }
+    let event_id = (*event).event_id;
+    let data = (*event).data;
+
+    match event_id {
+        EV_NONE => None,
+        EV_SHUTDOWN => Some(PlayerEvent::Shutdown),
+        EV_START_FILE => Some(PlayerEvent::StartFile),
+        EV_FILE_LOADED => Some(PlayerEvent::FileLoaded),
+        EV_IDLE => Some(PlayerEvent::Idle),
+        EV_SEEK => Some(PlayerEvent::Seek),
+        EV_PLAYBACK_RESTART => Some(PlayerEvent::PlaybackRestart),
+        EV_VIDEO_RECONFIG => Some(PlayerEvent::VideoReconfig),
+        EV_AUDIO_RECONFIG => Some(PlayerEvent::AudioReconfig),
+        EV_END_FILE => {
+            let end = data as *const libmpv2_sys::mpv_event_end_file;
+            if end.is_null() {
+                return Some(PlayerEvent::EndFile { reason: EndFileReason::Unknown, error: None });
+            }
+            let reason = match (*end).reason {
+                REASON_EOF => EndFileReason::Eof,
+                REASON_STOP => EndFileReason::Stop,
+                REASON_QUIT => EndFileReason::Quit,
+                REASON_ERROR => EndFileReason::Error,
+                REASON_REDIRECT => EndFileReason::Redirect,
+                _ => EndFileReason::Unknown,
+            };
+            let error = if reason == EndFileReason::Error {
+                Some(error_string((*end).error))
+            } else {
+                None
+            };
+            Some(PlayerEvent::EndFile { reason, error })
+        }
+        EV_LOG_MESSAGE => {
+            let msg = data as *const libmpv2_sys::mpv_event_log_message;
+            if msg.is_null() {
+                return None;
+            }
+            let text = |ptr: *const std::os::raw::c_char| {
+                if ptr.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr(ptr).to_string_lossy().trim_end().to_string()
+                }
+            };
+            Some(PlayerEvent::LogMessage {
+                prefix: text((*msg).prefix),
+                level: text((*msg).level),
+                text: text((*msg).text),
+            })
+        }
+        EV_PROPERTY_CHANGE => {
+            let prop = data as *const libmpv2_sys::mpv_event_property;
+            if prop.is_null() || (*prop).name.is_null() {
+                return None;
+            }
+            let name = CStr::from_ptr((*prop).name).to_string_lossy();
+            if (*prop).data.is_null() {
+                // format=NONE（プロパティが利用不可）の場合は data が null になる
+                // 字幕が消えたときは sub-text が利用不可になるので、空文字列として通知する
+                if name == "sub-text" {
+                    return Some(PlayerEvent::PropertyChange(PropertyChange::SubText(String::new())));
+                }
+                return None;
+            }
+            parse_property(&name, (*prop).format, (*prop).data).map(PlayerEvent::PropertyChange)
+        }
+        _ => None,
+    }
+}
+
+/// PROPERTY_CHANGE のペイロードを `PropertyChange` に変換する
+unsafe fn parse_property(
+    name: &str,
+    format: libmpv2_sys::mpv_format,
+    data: *mut std::ffi::c_void,
+) -> Option<PropertyChange> {
+    let flag = || *(data as *const std::os::raw::c_int) != 0;
+    let double = || *(data as *const f64);
+    let int64 = || *(data as *const i64);
+    let string = || {
+        let ptr = *(data as *const *const std::os::raw::c_char);
+        if ptr.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(ptr).to_string_lossy().into_owned()
+        }
+    };
+
+    let change = match (name, format) {
+        ("width", FMT_INT64) => PropertyChange::Width(int64()),
+        ("height", FMT_INT64) => PropertyChange::Height(int64()),
+        ("pause", FMT_FLAG) => PropertyChange::Pause(flag()),
+        ("time-pos", FMT_DOUBLE) => PropertyChange::TimePos(double()),
+        ("duration", FMT_DOUBLE) => PropertyChange::Duration(double()),
+        ("speed", FMT_DOUBLE) => PropertyChange::Speed(double()),
+        ("volume", FMT_DOUBLE) => PropertyChange::Volume(double()),
+        ("paused-for-cache", FMT_FLAG) => PropertyChange::PausedForCache(flag()),
+        ("cache-buffering-state", FMT_INT64) => PropertyChange::CacheBufferingState(int64()),
+        ("demuxer-cache-duration", FMT_DOUBLE) => PropertyChange::DemuxerCacheDuration(double()),
+        ("idle-active", FMT_FLAG) => PropertyChange::IdleActive(flag()),
+        ("media-title", FMT_STRING) => PropertyChange::MediaTitle(string()),
+        ("sub-text", FMT_STRING) => PropertyChange::SubText(string()),
+        _ => {
+            log::debug!("未対応のプロパティ変更: {} format={}", name, format);
+            return None;
+        }
+    };
+    Some(change)
+}
+
+/// mpv のエラーコードをメッセージに変換する
+fn error_string(code: std::os::raw::c_int) -> String {
+    unsafe {
+        let ptr = libmpv2_sys::mpv_error_string(code);
+        if ptr.is_null() {
+            format!("mpv エラー ({})", code)
+        } else {
+            CStr::from_ptr(ptr).to_string_lossy().into_owned()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn injected_events_reach_subscribers_without_a_dispatcher() {
+        let bus = EventBus::new();
+        let rx = bus.subscribe();
+        bus.inject(PlayerEvent::Chase { state: ChaseState::Waiting });
+        assert_eq!(rx.try_recv(), Ok(PlayerEvent::Chase { state: ChaseState::Waiting }));
+        // ディスパッチャが無い間は溜めない（登録したときに古いイベントを配らない）
+        assert!(bus.take_injected().is_empty());
+    }
+}
@@ -0,0 +1,100 @@
+/// アプリのデータディレクトリに JSON で保存する設定
+///
+/// 起動時に読み込み（ファイルが無い・壊れている場合は既定値で始める）、変更のたびに書き出す。
+/// オーディオ設定（`audio.json`）・トラックの優先言語（`tracks.json`）・キュー（`cues.json`）で使う。
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// JSON ファイルに保存する値
+#[derive(Debug, Default)]
+pub struct JsonStore<T> {
+    /// 保存先のファイル（None の場合は保存しない）
+    path: Option<PathBuf>,
+    /// ログに出す名前（"オーディオ設定" など）
+    name: &'static str,
+    value: T,
+}
+
+impl<T: Serialize + DeserializeOwned + Default> JsonStore<T> {
+    /// ファイルから読み込む（ファイルが無い・壊れている場合は既定値で始める）
+    pub fn load(path: PathBuf, name: &'static str) -> Self {
+        let value = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("{}の読み込みに失敗（既定値で始めます）: {}", name, e);
+                T::default()
+            }),
+            Err(_) => T::default(),
+        };
+        Self { path: Some(path), name, value }
+    }
+
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// 値を変更して保存する
+    pub fn update(&mut self, change: impl FnOnce(&mut T)) -> Result<()> {
+        change(&mut self.value);
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| anyhow::anyhow!("保存先を作成できません ({}): {}", dir.display(), e))?;
+        }
+        let text = serde_json::to_string_pretty(&self.value)?;
+        std::fs::write(path, text)
+            .map_err(|e| anyhow::anyhow!("{}を保存できません ({}): {}", self.name, path.display(), e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("yt-bridge-store-{}-{}", name, std::process::id()))
+            .join("store.json")
+    }
+
+    #[test]
+    fn saves_on_update_and_reloads() {
+        let path = temp_path("round-trip");
+        let _ = std::fs::remove_file(&path);
+
+        let mut store: JsonStore<BTreeMap<String, u32>> = JsonStore::load(path.clone(), "テスト");
+        assert!(store.get().is_empty());
+        store.update(|map| {
+            map.insert("a".to_string(), 1);
+        }).unwrap();
+
+        let reloaded: JsonStore<BTreeMap<String, u32>> = JsonStore::load(path.clone(), "テスト");
+        assert_eq!(reloaded.get().get("a"), Some(&1));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn broken_file_starts_from_default() {
+        let path = temp_path("broken");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{ not json").unwrap();
+
+        let store: JsonStore<BTreeMap<String, u32>> = JsonStore::load(path.clone(), "テスト");
+        assert!(store.get().is_empty());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn default_store_does_not_write() {
+        let mut store: JsonStore<Vec<u32>> = JsonStore::default();
+        store.update(|values| values.push(1)).unwrap();
+        assert_eq!(store.get(), &[1]);
+    }
+}
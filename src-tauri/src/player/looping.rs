@@ -0,0 +1,196 @@
+/// ループ再生（ファイル全体・A-B 区間・チャプター）とチャプター一覧
+///
+/// ## チャプター
+/// YouTube のチャプターは mpv の ytdl_hook が yt-dlp のメタデータから取り込み、
+/// `chapter-list` プロパティに入る（概要欄のタイムスタンプから作られたものを含む）。
+///
+/// ## ループ
+/// - `File`: ファイル全体を繰り返す（`loop-file`）
+/// - `Ab`: A 点から B 点までを繰り返す（`ab-loop-a` / `ab-loop-b`。B を省略すると末尾まで）
+/// - `Chapter`: チャプター 1 つを A-B ループとして繰り返す
+///
+/// 区間のループ（Ab / Chapter）は設定した動画にだけ引き継ぎ、別の動画を再生すると解除する。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// チャプターの情報
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Chapter {
+    /// 0 から始まるチャプター番号（mpv の `chapter` プロパティと同じ）
+    pub index: i64,
+    pub title: Option<String>,
+    /// 開始位置（秒）
+    pub start: f64,
+    /// 終了位置（秒。次のチャプターの開始位置、最後のチャプターは動画の長さ）
+    pub end: f64,
+}
+
+/// mpv の chapter-list（JSON）を解析する
+pub fn parse_chapter_list(chapter_list: &str, duration: f64) -> Result<Vec<Chapter>> {
+    let chapters: Vec<serde_json::Value> = serde_json::from_str(chapter_list)
+        .map_err(|e| anyhow::anyhow!("chapter-list を解析できません: {}", e))?;
+
+    let starts: Vec<(Option<String>, f64)> = chapters
+        .iter()
+        .filter_map(|chapter| {
+            let title = chapter.get("title").and_then(|v| v.as_str()).map(str::to_string);
+            Some((title, chapter.get("time")?.as_f64()?))
+        })
+        .collect();
+    Ok(starts
+        .iter()
+        .enumerate()
+        .map(|(index, (title, start))| Chapter {
+            index: index as i64,
+            title: title.clone(),
+            start: *start,
+            end: starts.get(index + 1).map(|(_, next)| *next).unwrap_or(duration),
+        })
+        .collect())
+}
+
+// ─── ループ ──────────────────────────────────────────────────────────────────
+
+/// ループ再生の設定
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum LoopMode {
+    /// ループしない
+    #[default]
+    Off,
+    /// ファイル全体
+    File,
+    /// A 点から B 点まで（秒。b を省略すると末尾まで）
+    Ab { a: f64, b: Option<f64> },
+    /// チャプター 1 つ
+    Chapter { index: i64 },
+}
+
+/// 現在位置から設定する A-B ループの端点
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoopPoint {
+    A,
+    B,
+}
+
+impl LoopMode {
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            LoopMode::Off | LoopMode::File => Ok(()),
+            LoopMode::Ab { a, b } => {
+                if !a.is_finite() || a < 0.0 {
+                    return Err(anyhow::anyhow!("A 点が不正です: {}", a));
+                }
+                match b {
+                    Some(b) if !b.is_finite() || b <= a => {
+                        Err(anyhow::anyhow!("B 点は A 点より後にしてください: a={}, b={}", a, b))
+                    }
+                    _ => Ok(()),
+                }
+            }
+            LoopMode::Chapter { index } if index < 0 => {
+                Err(anyhow::anyhow!("チャプター番号が不正です: {}", index))
+            }
+            LoopMode::Chapter { .. } => Ok(()),
+        }
+    }
+
+    /// 動画の一部だけを繰り返す設定か（別の動画には引き継がない）
+    pub fn is_section(&self) -> bool {
+        matches!(self, LoopMode::Ab { .. } | LoopMode::Chapter { .. })
+    }
+
+    /// mpv の ab-loop-a / ab-loop-b に渡す区間
+    ///
+    /// チャプターが読み込まれていない（見つからない）場合は None。
+    pub fn ab_range(&self, chapters: &[Chapter]) -> Option<(f64, Option<f64>)> {
+        match *self {
+            LoopMode::Off | LoopMode::File => None,
+            LoopMode::Ab { a, b } => Some((a, b)),
+            LoopMode::Chapter { index } => chapters
+                .iter()
+                .find(|chapter| chapter.index == index)
+                .map(|chapter| (chapter.start, Some(chapter.end))),
+        }
+    }
+
+    /// 現在位置を A 点または B 点にした設定
+    ///
+    /// A-B ループ以外から B 点を設定した場合は先頭を A 点とする。
+    /// 現在の A 点より前に B 点を設定した場合はエラーにする。
+    pub fn with_point(&self, point: LoopPoint, position: f64) -> Result<LoopMode> {
+        let mode = match (point, self) {
+            // B 点が A 点より前になる場合は B 点を外す
+            (LoopPoint::A, LoopMode::Ab { b, .. }) => LoopMode::Ab {
+                a: position,
+                b: b.filter(|b| *b > position),
+            },
+            (LoopPoint::A, _) => LoopMode::Ab { a: position, b: None },
+            (LoopPoint::B, LoopMode::Ab { a, .. }) => LoopMode::Ab { a: *a, b: Some(position) },
+            (LoopPoint::B, _) => LoopMode::Ab { a: 0.0, b: Some(position) },
+        };
+        mode.validate()?;
+        Ok(mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapters() -> Vec<Chapter> {
+        parse_chapter_list(r#"[{"title":"Intro","time":0.0},{"title":"Main","time":30.5},{"time":90.0}]"#, 120.0)
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_chapter_list_with_end_positions() {
+        let chapters = chapters();
+        assert_eq!(chapters.len(), 3);
+        assert_eq!(chapters[1].title.as_deref(), Some("Main"));
+        assert_eq!((chapters[1].start, chapters[1].end), (30.5, 90.0));
+        // 最後のチャプターは動画の長さまで
+        assert_eq!(chapters[2].title, None);
+        assert_eq!(chapters[2].end, 120.0);
+    }
+
+    #[test]
+    fn validates_section_loops() {
+        assert!(LoopMode::Ab { a: 10.0, b: Some(20.0) }.validate().is_ok());
+        assert!(LoopMode::Ab { a: 10.0, b: None }.validate().is_ok());
+        assert!(LoopMode::Ab { a: -1.0, b: None }.validate().is_err());
+        assert!(LoopMode::Ab { a: f64::NAN, b: None }.validate().is_err());
+        // B 点は A 点より後でなければならない
+        assert!(LoopMode::Ab { a: 10.0, b: Some(10.0) }.validate().is_err());
+        assert!(LoopMode::Ab { a: 10.0, b: Some(5.0) }.validate().is_err());
+        assert!(LoopMode::Ab { a: 10.0, b: Some(f64::INFINITY) }.validate().is_err());
+        assert!(LoopMode::Chapter { index: 0 }.validate().is_ok());
+        assert!(LoopMode::Chapter { index: -1 }.validate().is_err());
+    }
+
+    #[test]
+    fn resolves_ab_range_for_chapters() {
+        let chapters = chapters();
+        assert_eq!(LoopMode::Chapter { index: 1 }.ab_range(&chapters), Some((30.5, Some(90.0))));
+        // 見つからないチャプターは区間にならない
+        assert_eq!(LoopMode::Chapter { index: 5 }.ab_range(&chapters), None);
+        assert_eq!(LoopMode::File.ab_range(&chapters), None);
+        assert!(LoopMode::Chapter { index: 1 }.is_section());
+        assert!(!LoopMode::File.is_section());
+    }
+
+    #[test]
+    fn sets_points_from_current_position() {
+        let mode = LoopMode::Off.with_point(LoopPoint::B, 40.0).unwrap();
+        assert_eq!(mode, LoopMode::Ab { a: 0.0, b: Some(40.0) });
+        let mode = mode.with_point(LoopPoint::A, 10.0).unwrap();
+        assert_eq!(mode, LoopMode::Ab { a: 10.0, b: Some(40.0) });
+        // A 点を B 点より後に動かすと B 点を外す
+        let mode = mode.with_point(LoopPoint::A, 50.0).unwrap();
+        assert_eq!(mode, LoopMode::Ab { a: 50.0, b: None });
+        // A 点より前の B 点はエラー
+        assert!(mode.with_point(LoopPoint::B, 30.0).is_err());
+    }
+}
@@ -0,0 +1,510 @@
+/// 再生中の音声レベルの取得
+///
+/// mpv の音声フィルタに `astats` を挿入し、フレームごとのメタデータ
+/// （`af-metadata/<ラベル>`）から RMS レベルを読む。
+/// 読み取りは mpv のクライアント API 経由なので、どのスレッドから呼んでもよい。
+///
+/// ## メーター（VU・ラウドネス・スペクトラム）
+/// メーターを有効にすると、フィルタを次のグラフに置き換える。
+/// - 本線: `astats`（チャンネルごとの RMS / ピーク）→ `ebur128`（LUFS）。値は af-metadata から読む
+/// - 分岐（スペクトラム）: モノラル・`TAP_RATE` にして `BANDS` の数のチャンネルに複製し、
+///   `afftfilt`（FFT）でチャンネルごとに担当の帯域だけを残す。`BLOCK` サンプルごとに `astats` で
+///   帯域ごとの RMS を測り、分岐の出力は mpv に戻らないため、`ametadata` の print で
+///   ローカルの UDP ポートに書き出して受け取る（1 ブロック = 帯域すべてを含む 1 件）
+///
+/// サンプル値そのものをテキストで送ると 1 サンプルごとに 1 件になるため、FFT はフィルタの中で行う。
+/// af-metadata はメーター専用の mpv クライアントで読み、PlayerInner のロックを持ったまま読まない。
+///
+/// 測った値は設定した頻度で `PlayerEvent::AudioMeter` と OSC で送る。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::ffi::{CStr, CString};
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::events::{EventBus, PlayerEvent};
+use super::osc::{self, OscArg};
+use super::PlayerInner;
+
+/// MpvContext::new で設定する音声フィルタ（reset=1 でフレームごとに集計し直す）
+pub const LEVEL_FILTER: &str = "@meter:lavfi=[astats=metadata=1:reset=1]";
+
+/// 全チャンネルの RMS レベル（dBFS）を保持するメタデータのキー
+const RMS_LEVEL_PROPERTY: &str = "af-metadata/meter/by-key/lavfi.astats.Overall.RMS_level";
+
+/// 無音とみなす下限（dBFS）
+const SILENCE_DB: f32 = -60.0;
+
+/// 現在の RMS レベルを dBFS で返す（音声が無い・取得できない場合は None）
+pub fn rms_level_db(handle: *mut libmpv2_sys::mpv_handle) -> Option<f32> {
+    read_db(handle, RMS_LEVEL_PROPERTY)
+}
+
+/// 現在の RMS レベルを 0.0（-60 dBFS 以下）–1.0（0 dBFS）に正規化して返す
+///
+/// シェーダの `u_audio_level` などの表示用途向け。
+pub fn audio_level(handle: *mut libmpv2_sys::mpv_handle) -> f32 {
+    match rms_level_db(handle) {
+        Some(db) => ((db - SILENCE_DB) / -SILENCE_DB).clamp(0.0, 1.0),
+        None => 0.0,
+    }
+}
+
+/// メタデータの dB 値を読む（無音の "-inf" は負の無限大になる）
+fn read_db(handle: *mut libmpv2_sys::mpv_handle, property: &str) -> Option<f32> {
+    let name = CString::new(property).ok()?;
+    unsafe {
+        let value = libmpv2_sys::mpv_get_property_string(handle, name.as_ptr());
+        if value.is_null() {
+            return None;
+        }
+        let db = CStr::from_ptr(value).to_str().ok().and_then(|s| s.parse::<f32>().ok());
+        libmpv2_sys::mpv_free(value as *mut _);
+        // 無音では "-inf" になる
+        db.map(|db| if db.is_finite() { db } else { f32::NEG_INFINITY })
+    }
+}
+
+// ─── メーター ────────────────────────────────────────────────────────────────
+
+/// スペクトラムの帯域の中心周波数（Hz。1 オクターブ幅）
+pub const BANDS: [f32; 10] = [31.5, 63.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0];
+/// スペクトラムの分岐のサンプリング周波数（Hz。最上位の帯域はナイキスト周波数の 16 kHz までを測る）
+const TAP_RATE: u32 = 32000;
+/// スペクトラムの FFT の長さと、帯域のレベルを測るブロックの長さ（TAP_RATE で 32 ms）
+const BLOCK: usize = 1024;
+/// 読み取るチャンネル数の上限（7.1ch まで）
+const MAX_CHANNELS: usize = 8;
+/// イベント・OSC で送る下限（-inf は JSON にできないため）
+const FLOOR_DB: f32 = -120.0;
+
+/// メーターのフィルタ（tap_port が None なら従来の RMS だけを測るフィルタ）
+///
+/// ラベル付きのグラフは mpv の `[...]` の引用と衝突するため、`%長さ%` 形式で渡す。
+pub fn filter(tap_port: Option<u16>) -> String {
+    let Some(port) = tap_port else { return LEVEL_FILTER.to_string() };
+    let bands = BANDS.len();
+    let copies: String = (0..bands).map(|ch| format!("|c{}=c0", ch)).collect();
+    // チャンネル ch は 1 kHz × 2^(ch - 5) を中心とする 1 オクターブ（中心の 1/√2 倍から √2 倍）だけを残す
+    // （bin b の周波数は b × sr / (2 × nb)）
+    let mask = "between(b*sr/(2*nb)\\,1000*pow(2\\,ch-5.5)\\,1000*pow(2\\,ch-4.5))";
+    let graph = format!(
+        "asplit=2[main][tap];\
+         [tap]aresample={rate},aformat=sample_fmts=flt:channel_layouts=mono,ametadata=mode=delete,\
+         pan={bands}c{copies},\
+         afftfilt=win_size={block}:real=\\'re*{mask}\\':imag=\\'im*{mask}\\',\
+         asetnsamples=n={block}:p=0,\
+         astats=metadata=1:reset=1:measure_overall=none:measure_perchannel=RMS_level,\
+         ametadata=mode=print:file=\\'udp://127.0.0.1:{port}\\',anullsink;\
+         [main]astats=metadata=1:reset=1,ebur128=metadata=1",
+        rate = TAP_RATE,
+        block = BLOCK,
+    );
+    format!("@meter:lavfi=graph=%{}%{}", graph.len(), graph)
+}
+
+/// OSC の送信先
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeterOsc {
+    pub host: String,
+    pub port: u16,
+    /// アドレスの接頭辞（"/meter" なら /meter/rms・/meter/peak・/meter/lufs・/meter/bands）
+    #[serde(default = "default_prefix")]
+    pub prefix: String,
+}
+
+fn default_prefix() -> String {
+    "/meter".to_string()
+}
+
+/// メーターの設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeterSettings {
+    pub enabled: bool,
+    /// 送信の頻度（Hz）
+    #[serde(default = "default_rate")]
+    pub rate: f64,
+    /// 値を OSC でも送る
+    #[serde(default)]
+    pub osc: Option<MeterOsc>,
+}
+
+fn default_rate() -> f64 {
+    20.0
+}
+
+impl Default for MeterSettings {
+    fn default() -> Self {
+        Self { enabled: false, rate: default_rate(), osc: None }
+    }
+}
+
+impl MeterSettings {
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        if !(1.0..=60.0).contains(&self.rate) {
+            return Err(anyhow::anyhow!("rate は 1–60 Hz で指定してください: {}", self.rate));
+        }
+        if let Some(target) = &self.osc {
+            if target.host.is_empty() || target.port == 0 {
+                return Err(anyhow::anyhow!("OSC の送信先を指定してください"));
+            }
+            osc::validate_address(&target.prefix)?;
+        }
+        Ok(())
+    }
+}
+
+/// 1 チャンネルのレベル（dBFS）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ChannelLevel {
+    pub rms: f32,
+    pub peak: f32,
+}
+
+/// メーターの測定値
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MeterLevels {
+    /// チャンネルごとのレベル
+    pub channels: Vec<ChannelLevel>,
+    /// モーメンタリーラウドネス（LUFS、400 ms）
+    pub momentary: f32,
+    /// ショートタームラウドネス（LUFS、3 秒）
+    pub short_term: f32,
+    /// インテグレーテッドラウドネス（LUFS、再生開始から）
+    pub integrated: f32,
+    /// `BANDS` の帯域ごとの RMS レベル（dBFS。スペクトラムの分岐の FFT から求める）
+    pub bands: Vec<f32>,
+}
+
+impl MeterLevels {
+    /// 一時停止中など音が出ていない状態（インテグレーテッドはそのまま）
+    fn silence(&mut self) {
+        for channel in &mut self.channels {
+            *channel = ChannelLevel { rms: FLOOR_DB, peak: FLOOR_DB };
+        }
+        self.momentary = FLOOR_DB;
+        self.short_term = FLOOR_DB;
+        self.bands.iter_mut().for_each(|band| *band = FLOOR_DB);
+    }
+}
+
+fn floor(db: f32) -> f32 {
+    if db.is_nan() { FLOOR_DB } else { db.max(FLOOR_DB) }
+}
+
+/// 本線のフィルタのメタデータ（チャンネルのレベルと LUFS）を読む
+fn read_levels(handle: *mut libmpv2_sys::mpv_handle, levels: &mut MeterLevels) {
+    let key = |name: &str| format!("af-metadata/meter/by-key/lavfi.{}", name);
+    levels.channels = (1..=MAX_CHANNELS)
+        .map_while(|ch| {
+            let rms = read_db(handle, &key(&format!("astats.{}.RMS_level", ch)))?;
+            let peak = read_db(handle, &key(&format!("astats.{}.Peak_level", ch))).unwrap_or(rms);
+            Some(ChannelLevel { rms: floor(rms), peak: floor(peak) })
+        })
+        .collect();
+    let lufs = |name: &str| read_db(handle, &key(name)).map_or(FLOOR_DB, floor);
+    levels.momentary = lufs("r128.M");
+    levels.short_term = lufs("r128.S");
+    levels.integrated = lufs("r128.I");
+}
+
+/// `ametadata` の print 出力から帯域ごとのレベル（dBFS）を読み、最後に揃ったブロックの値を保持する
+///
+/// 1 ブロックは見出しの行（frame:... pts:...）と、帯域ごとの `lavfi.astats.<帯域>.RMS_level` の行。
+/// UDP のパケットの境目は行の境目と一致しないため、行の途中は次のパケットに持ち越す。
+#[derive(Debug)]
+struct TapParser {
+    partial: String,
+    /// 読んでいる途中のブロック
+    block: Vec<f32>,
+    /// 最後に揃ったブロック
+    bands: Vec<f32>,
+}
+
+impl TapParser {
+    fn new() -> Self {
+        Self {
+            partial: String::new(),
+            block: vec![FLOOR_DB; BANDS.len()],
+            bands: vec![FLOOR_DB; BANDS.len()],
+        }
+    }
+
+    fn feed(&mut self, data: &[u8]) {
+        self.partial.push_str(&String::from_utf8_lossy(data));
+        let Some(end) = self.partial.rfind('\n') else { return };
+        let complete: String = self.partial.drain(..=end).collect();
+        for line in complete.lines() {
+            // 見出しの行・他のキーは読み飛ばす
+            let Some((band, value)) = line
+                .strip_prefix("lavfi.astats.")
+                .and_then(|rest| rest.split_once(".RMS_level="))
+            else {
+                continue;
+            };
+            let (Ok(band), Ok(db)) = (band.parse::<usize>(), value.trim().parse::<f32>()) else { continue };
+            if !(1..=BANDS.len()).contains(&band) {
+                continue;
+            }
+            self.block[band - 1] = floor(db);
+            // 帯域は番号の順に並ぶため、最後の帯域でブロックが揃う
+            if band == BANDS.len() {
+                self.bands.clone_from(&self.block);
+            }
+        }
+    }
+
+    /// 最後に揃ったブロックの帯域ごとのレベル
+    fn bands(&self) -> Vec<f32> {
+        self.bands.clone()
+    }
+}
+
+/// メーター専用の mpv クライアントハンドル（PlayerInner のロックを持たずに af-metadata を読むため）
+///
+/// mpv 本体の破棄（`mpv_terminate_destroy`）はすべてのクライアントが破棄されるまで待つため、
+/// 測定の間隔はこのハンドルのイベントを待って過ごし、SHUTDOWN が届いたらすぐに破棄する。
+struct MeterClient {
+    handle: *mut libmpv2_sys::mpv_handle,
+    /// 作成した再生セッション
+    session: u64,
+}
+
+impl MeterClient {
+    fn new(mpv_handle: *mut libmpv2_sys::mpv_handle, session: u64) -> Option<Self> {
+        let name = CString::new("meter").ok()?;
+        let handle = unsafe { libmpv2_sys::mpv_create_client(mpv_handle, name.as_ptr()) };
+        (!handle.is_null()).then_some(Self { handle, session })
+    }
+
+    /// timeout の間イベントを待つ（mpv が終了する場合は false）
+    fn wait(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return true;
+            }
+            let event = unsafe { libmpv2_sys::mpv_wait_event(self.handle, remaining.as_secs_f64()) };
+            if !event.is_null()
+                && unsafe { (*event).event_id } == libmpv2_sys::mpv_event_id_MPV_EVENT_SHUTDOWN
+            {
+                return false;
+            }
+        }
+    }
+}
+
+impl Drop for MeterClient {
+    fn drop(&mut self) {
+        unsafe { libmpv2_sys::mpv_destroy(self.handle) };
+    }
+}
+
+/// 動作中のメーター
+pub struct MeterHandle {
+    stop: Arc<AtomicBool>,
+    levels: Arc<Mutex<MeterLevels>>,
+    tap_port: u16,
+}
+
+impl MeterHandle {
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// 最新の測定値
+    pub fn levels(&self) -> MeterLevels {
+        self.levels.lock().map(|levels| levels.clone()).unwrap_or_default()
+    }
+
+    /// スペクトラムの分岐の書き出し先（`filter()` に渡す）
+    pub fn tap_port(&self) -> u16 {
+        self.tap_port
+    }
+}
+
+/// スペクトラムの分岐の受信とメーターのスレッドを起動する
+///
+/// 再生セッションをまたいで動き続ける（mpv が無い間は何もしない）。
+/// フィルタの切り替えは呼び出し側が `tap_port()` を使って行う。
+pub(super) fn spawn(inner: Arc<Mutex<PlayerInner>>, settings: MeterSettings, bus: EventBus) -> Result<MeterHandle> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let socket = UdpSocket::bind(("127.0.0.1", 0))
+        .map_err(|e| anyhow::anyhow!("スペクトラムの受信ポートを開けません: {}", e))?;
+    let tap_port = socket.local_addr()?.port();
+    // stop フラグを確認するために定期的に受信を抜ける
+    socket.set_read_timeout(Some(Duration::from_millis(200)))?;
+
+    let tap = Arc::new(Mutex::new(TapParser::new()));
+    let levels = Arc::new(Mutex::new(MeterLevels::default()));
+    let handle = MeterHandle { stop: stop.clone(), levels: levels.clone(), tap_port };
+
+    let tap_stop = stop.clone();
+    let tap_parser = tap.clone();
+    std::thread::spawn(move || {
+        let mut buffer = [0u8; 4096];
+        while !tap_stop.load(Ordering::Relaxed) {
+            let Ok((len, _)) = socket.recv_from(&mut buffer) else { continue };
+            if let Ok(mut parser) = tap_parser.lock() {
+                parser.feed(&buffer[..len]);
+            }
+        }
+    });
+
+    std::thread::spawn(move || {
+        log::info!("メーターを開始しました ({} Hz, OSC={:?})", settings.rate, settings.osc);
+        let interval = Duration::from_secs_f64(1.0 / settings.rate);
+        let osc_socket = UdpSocket::bind(("0.0.0.0", 0)).ok();
+        let mut current = MeterLevels::default();
+        let mut client: Option<MeterClient> = None;
+        // SHUTDOWN が届いた再生セッション（終了中の mpv にクライアントを作り直さない）
+        let mut closed_session = None;
+
+        while !stop.load(Ordering::Relaxed) {
+            match &client {
+                Some(meter_client) if !meter_client.wait(interval) => {
+                    closed_session = Some(meter_client.session);
+                    client = None;
+                    continue;
+                }
+                Some(_) => {}
+                None => std::thread::sleep(interval),
+            }
+
+            // ロックの中ではクライアントの用意と状態の確認だけを行う
+            let paused = {
+                let Ok(guard) = inner.lock() else { break };
+                let Some(mpv) = &guard.mpv else {
+                    client = None;
+                    continue;
+                };
+                let session = guard.session;
+                if client.as_ref().map_or(true, |c| c.session != session) && closed_session != Some(session) {
+                    client = MeterClient::new(mpv.mpv_handle_ptr(), session);
+                }
+                guard.props.paused
+            };
+            let Some(meter_client) = &client else { continue };
+
+            read_levels(meter_client.handle, &mut current);
+            current.bands = tap.lock().map(|parser| parser.bands()).unwrap_or_default();
+            // 一時停止中はフィルタに最後のフレームの値が残るため、無音として送る
+            if paused {
+                current.silence();
+            }
+
+            if let Ok(mut levels) = levels.lock() {
+                levels.clone_from(&current);
+            }
+            bus.inject(PlayerEvent::AudioMeter(current.clone()));
+            if let (Some(target), Some(socket)) = (&settings.osc, &osc_socket) {
+                if let Err(e) = send_osc(socket, target, &current) {
+                    log::debug!("メーターの OSC 送信に失敗: {}", e);
+                }
+            }
+        }
+        log::info!("メーターを終了しました");
+    });
+
+    Ok(handle)
+}
+
+fn send_osc(socket: &UdpSocket, target: &MeterOsc, levels: &MeterLevels) -> Result<()> {
+    let rms: Vec<f32> = levels.channels.iter().map(|ch| ch.rms).collect();
+    let peak: Vec<f32> = levels.channels.iter().map(|ch| ch.peak).collect();
+    let lufs = [levels.momentary, levels.short_term, levels.integrated];
+    for (name, values) in [("rms", &rms[..]), ("peak", &peak[..]), ("lufs", &lufs[..]), ("bands", &levels.bands[..])] {
+        let args: Vec<OscArg> = values.iter().copied().map(OscArg::Float).collect();
+        osc::send_with(socket, &target.host, target.port, &format!("{}/{}", target.prefix, name), &args)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 帯域ごとのレベルを ametadata の print の形式にする
+    fn record(frame: usize, levels: &[f32]) -> String {
+        let mut text = format!("frame:{:<4} pts:{:<7} pts_time:{}\n", frame, frame * BLOCK, (frame * BLOCK) as f32 / TAP_RATE as f32);
+        for (i, level) in levels.iter().enumerate() {
+            text.push_str(&format!("lavfi.astats.{}.RMS_level={:.6}\n", i + 1, level));
+        }
+        text
+    }
+
+    fn levels(base: f32) -> Vec<f32> {
+        (0..BANDS.len()).map(|i| base - i as f32).collect()
+    }
+
+    #[test]
+    fn tap_parser_keeps_the_last_complete_block() {
+        let mut parser = TapParser::new();
+        assert_eq!(parser.bands(), vec![FLOOR_DB; BANDS.len()]);
+
+        parser.feed(record(0, &levels(-10.0)).as_bytes());
+        assert_eq!(parser.bands(), levels(-10.0));
+
+        // 途中までのブロックでは値を変えない
+        let next = record(1, &levels(-20.0));
+        let (head, tail) = next.split_at(next.len() / 2);
+        parser.feed(head.as_bytes());
+        assert_eq!(parser.bands(), levels(-10.0));
+        parser.feed(tail.as_bytes());
+        assert_eq!(parser.bands(), levels(-20.0));
+    }
+
+    #[test]
+    fn tap_parser_joins_lines_split_across_packets() {
+        let mut parser = TapParser::new();
+        let text = record(0, &levels(-30.0));
+        for chunk in text.as_bytes().chunks(7) {
+            parser.feed(chunk);
+        }
+        assert_eq!(parser.bands(), levels(-30.0));
+    }
+
+    #[test]
+    fn tap_parser_floors_silence_and_ignores_other_keys() {
+        let mut parser = TapParser::new();
+        let mut text = String::from("frame:0    pts:0       pts_time:0\n");
+        text.push_str("lavfi.astats.Overall.RMS_level=-3.0\n");
+        text.push_str("lavfi.astats.11.RMS_level=-3.0\n");
+        for band in 1..=BANDS.len() {
+            text.push_str(&format!("lavfi.astats.{}.RMS_level=-inf\n", band));
+        }
+        parser.feed(text.as_bytes());
+        assert_eq!(parser.bands(), vec![FLOOR_DB; BANDS.len()]);
+    }
+
+    #[test]
+    fn filter_splits_bands_per_block() {
+        assert_eq!(filter(None), LEVEL_FILTER);
+        let filter = filter(Some(40000));
+        assert!(filter.starts_with("@meter:lavfi=graph=%"));
+        assert!(filter.contains(&format!("asetnsamples=n={}", BLOCK)));
+        assert!(filter.contains(&format!("afftfilt=win_size={}", BLOCK)));
+        assert!(filter.contains(&format!("pan={}c|c0=c0|", BANDS.len())));
+        assert!(filter.contains(&format!("|c{}=c0,", BANDS.len() - 1)));
+        assert!(filter.contains(&format!("aresample={}", TAP_RATE)));
+        assert!(filter.contains("udp://127.0.0.1:40000"));
+        assert!(!filter.contains("n=1:"));
+        // %長さ% がグラフのバイト数と一致する
+        let rest = filter.trim_start_matches("@meter:lavfi=graph=%");
+        let (len, graph) = rest.split_once('%').unwrap();
+        assert_eq!(len.parse::<usize>().unwrap(), graph.len());
+    }
+
+    #[test]
+    fn band_centers_follow_the_filter_octaves() {
+        // フィルタは 1 kHz × 2^(ch - 5) を中心にする。表示用の中心周波数との差は 1/12 オクターブ未満
+        for (ch, center) in BANDS.iter().enumerate() {
+            let exact = 1000.0 * 2f32.powi(ch as i32 - 5);
+            assert!((center / exact).log2().abs() < 1.0 / 12.0, "{} Hz", center);
+        }
+    }
+}
@@ -1,14 +1,55 @@
+mod drift;
+mod json_store;
 mod mpv_context;
+mod status;
+mod watchdog;
 pub mod audio;
+pub mod audio_settings;
+pub mod beat_sync;
+pub mod chase;
+pub mod cues;
+pub mod events;
+pub mod looping;
+pub mod meter;
+pub mod net_sync;
+pub mod osc;
+pub mod seek;
+pub mod subtitles;
+pub mod tracks;
+pub mod volume;
 
 use anyhow::Result;
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{Emitter, Manager};
 
+use crate::error::AppError;
+use crate::output::autocrop::{AutoCropSettings, CropMode, CropStatus};
+use crate::output::color::ColorAdjust;
+use crate::output::key::Key;
+use crate::output::overlay::OverlayLayer;
+use crate::output::shader::ShaderEffect;
+use crate::output::standby::StandbySource;
+use crate::output::transform::Transform;
+use crate::output::{OutputRegistry, OutputStats, OUTPUT_NAMES};
 use crate::output::preview::PreviewHandle;
 #[cfg(target_os = "macos")]
 use crate::output::syphon::{self, SyphonHandle};
+use audio::{AudioOutputSettings, ChannelRouting, DeviceAudio};
+use audio_settings::{AudioDeviceStatus, AudioSettingsStore, DeviceAction, DeviceChange, DevicePolicy};
+use beat_sync::{BeatSyncHandle, BeatSyncSettings, BeatSyncStatus};
+use chase::{ChaseHandle, ChaseSettings, ChaseStatus};
+use cues::{Cue, CueAction, CueStore};
+use events::{EventBus, PlayerEvent, PropertyChange};
+use looping::{Chapter, LoopMode, LoopPoint};
+use meter::{MeterHandle, MeterLevels, MeterSettings};
+use net_sync::{NetSyncHandle, NetSyncSettings, NetSyncStatus};
+use seek::SeekRequest;
 pub use mpv_context::MpvContext;
+pub use status::{ErrorCause, PlayError, PlayStatus, StatusKind};
+use status::StatusMachine;
+use subtitles::{SubtitleSettings, SubtitleTrack};
+use tracks::{Track, TrackKind, TrackPreferences, TrackPreferencesStore};
+use volume::{FadeCurve, VolumeFade, VolumeLevel, VolumeSettings};
 
 pub fn resolve_ytdlp_path() -> String {
     MpvContext::resolve_ytdlp_path()
@@ -16,23 +57,16 @@ pub fn resolve_ytdlp_path() -> String {
 
 // ─── プレイヤーの状態 ────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone)]
-pub enum PlayStatus {
-    Idle,
-    Loading,
-    Playing,
-    Paused,
-    Error(String),
-}
-
 /// Tauri の `manage()` に渡す共有状態
 /// Arc<Mutex<>> で複数スレッドから安全にアクセス
 pub struct PlayerState {
     inner: Arc<Mutex<PlayerInner>>,
     /// Tauri AppHandle（プレビューイベント送信用）
     app_handle: Option<tauri::AppHandle>,
-    /// Syphon スレッドから playing 遷移を通知するフラグ
-    syphon_playing: Arc<AtomicBool>,
+    /// mpv イベントの配信先（セッションをまたいで共有）
+    events: EventBus,
+    /// 出力ごとの設定（スタンバイ映像・変形など。セッションをまたいで共有）
+    outputs: OutputRegistry,
 }
 
 struct PlayerInner {
@@ -42,15 +76,75 @@ struct PlayerInner {
     /// Syphon 出力ハンドル (macOS のみ)
     #[cfg(target_os = "macos")]
     syphon: Option<SyphonHandle>,
-    status: PlayStatus,
+    /// mpv イベントで駆動される再生ステータス
+    status: StatusMachine,
     current_url: Option<String>,
-    output_active: bool,
-    /// UI で設定されたボリューム値（再生開始時に適用）
-    pending_volume: u8,
+    /// UI で設定されたボリューム値（%。再生開始時に適用。フェード中はフェードの目標値）
+    pending_volume: f64,
+    /// 音量の上限と、再生・停止時のフェード
+    volume_settings: VolumeSettings,
+    /// 音声が出始めたらフェードインする（再生開始から PLAYBACK_RESTART まで true）
+    fade_in_pending: bool,
     /// UI で設定されたミュート状態（再生開始時に適用）
     pending_mute: bool,
-    /// UI で設定されたループ状態（再生開始時に適用）
-    pending_loop: bool,
+    /// 優先する出力デバイス・抜けたときの動作・出力の設定（再生開始時に適用。audio.json に保存）
+    audio: AudioSettingsStore,
+    /// 優先デバイスが抜けている間 true（その間は既定のデバイスに出力する）
+    device_missing: bool,
+    /// `fallback` で既定のデバイスに切り替えた後 true（優先デバイスが戻っても追わない。保存はしない）
+    device_fallback: bool,
+    /// UI で設定されたループ再生（再生開始時と FILE_LOADED で適用）
+    pending_loop: LoopMode,
+    /// 区間のループを設定した動画の URL（別の動画では区間のループを解除する）
+    loop_url: Option<String>,
+    /// 字幕の設定（再生開始時と FILE_LOADED で適用）
+    subtitles: SubtitleSettings,
+    /// 音声トラックの優先言語（再生開始時と FILE_LOADED で適用）
+    tracks: TrackPreferencesStore,
+    /// 再生セッション番号（古いディスパッチャからのイベントを無視するため）
+    session: u64,
+    /// ディスパッチャから受け取った最新のプロパティ値
+    props: PlaybackProps,
+    /// 出力スレッドの稼働状況（黒帯の検出結果を含む）
+    output_stats: Option<Arc<OutputStats>>,
+    /// URL ごとのキューポイントの保存先
+    cue_store: CueStore,
+    /// 再生中のクリップのキューポイント
+    cues: Vec<Cue>,
+    /// キューの通過判定に使う直前の再生位置（シーク直後は None）
+    cue_pos: Option<f64>,
+    /// キューで予約したクリップの切り替え（ディスパッチャの外で実行する）
+    pending_clip: Option<String>,
+    /// 同じセッション内でクリップを切り替えた回数（ウォッチドッグが切り替えを検知するために使う）
+    clip: u64,
+    /// キューで開始した音量フェード
+    volume_fade: Option<VolumeFade>,
+    /// キューの発火を通知するためのイベントバス
+    events: EventBus,
+    /// 外部タイムコードへの追従（再生セッションをまたいで動く）
+    chase: Option<ChaseHandle>,
+    /// ネットワークのテンポへの同期（再生セッションをまたいで動く）
+    beat_sync: Option<BeatSyncHandle>,
+    /// 複数台の同期再生（再生セッションをまたいで動く）
+    net_sync: Option<NetSyncHandle>,
+    /// 音声メーターの設定
+    meter_settings: MeterSettings,
+    /// 動作中の音声メーター（再生セッションをまたいで動く）
+    meter: Option<MeterHandle>,
+}
+
+/// mpv イベントで更新される再生中のプロパティ値
+#[derive(Debug, Clone, Default)]
+#[allow(dead_code)]
+pub struct PlaybackProps {
+    pub time_pos: f64,
+    pub duration: f64,
+    pub speed: f64,
+    pub volume: f64,
+    pub paused: bool,
+    pub paused_for_cache: bool,
+    /// キャッシュ充填率（0–100）
+    pub cache_percent: i64,
 }
 
 /// 初期 FBO 解像度（動画の実解像度が取得できない場合のフォールバック）
@@ -59,46 +153,105 @@ const PREVIEW_HEIGHT: u32 = 1080;
 
 impl PlayerState {
     pub fn new() -> Self {
+        let events = EventBus::new();
         Self {
             inner: Arc::new(Mutex::new(PlayerInner {
                 mpv: None,
                 preview: None,
                 #[cfg(target_os = "macos")]
                 syphon: None,
-                status: PlayStatus::Idle,
+                status: StatusMachine::new(),
                 current_url: None,
-                output_active: false,
-                pending_volume: 100,
+                pending_volume: 100.0,
+                volume_settings: VolumeSettings::default(),
+                fade_in_pending: false,
                 pending_mute: false,
-                pending_loop: false,
+                audio: AudioSettingsStore::default(),
+                device_missing: false,
+                device_fallback: false,
+                pending_loop: LoopMode::Off,
+                loop_url: None,
+                subtitles: SubtitleSettings::default(),
+                tracks: TrackPreferencesStore::default(),
+                session: 0,
+                props: PlaybackProps::default(),
+                output_stats: None,
+                cue_store: CueStore::default(),
+                cues: Vec::new(),
+                cue_pos: None,
+                pending_clip: None,
+                clip: 0,
+                volume_fade: None,
+                events: events.clone(),
+                chase: None,
+                beat_sync: None,
+                net_sync: None,
+                meter_settings: MeterSettings::default(),
+                meter: None,
             })),
             app_handle: None,
-            syphon_playing: Arc::new(AtomicBool::new(false)),
+            events,
+            outputs: OutputRegistry::new(),
         }
     }
 
     /// Tauri AppHandle を設定する（setup 時に呼ぶ）
     pub fn set_app_handle(&mut self, handle: tauri::AppHandle) {
+        self.events.set_app_handle(handle.clone());
+        // キューポイント・オーディオ設定・トラックの優先言語はアプリのデータディレクトリに保存する
+        match handle.path().app_data_dir() {
+            Ok(dir) => {
+                if let Ok(mut inner) = self.inner.lock() {
+                    inner.cue_store = CueStore::load(dir.join("cues.json"));
+                    inner.audio = AudioSettingsStore::load(dir.join("audio.json"), "オーディオ設定");
+                    inner.tracks = TrackPreferencesStore::load(dir.join("tracks.json"), "トラック設定");
+                    inner.subtitles.language = inner.tracks.get().sub_language.clone();
+                    let preferred = inner.audio.get().device.clone();
+                    inner.device_missing = preferred != "auto"
+                        && !audio::enumerate_devices().iter().any(|(id, _)| *id == preferred);
+                    if inner.device_missing {
+                        log::warn!("優先デバイスが見つかりません（既定のデバイスで始めます）: {}", preferred);
+                    }
+                }
+            }
+            Err(e) => log::warn!("データディレクトリを取得できません（キューと設定は保存されません）: {}", e),
+        }
+        // デバイスの抜き差しを UI に知らせ、優先デバイスの有無に応じて出力先を切り替える
+        let app = handle.clone();
+        let inner = self.inner.clone();
+        audio::spawn_monitor(move |devices| {
+            emit_audio_devices(&app, devices);
+            apply_device_policy(&inner, &app, devices);
+        });
         self.app_handle = Some(handle);
+
+        // 再生していない間もスタンバイ映像を送れるよう、出力は起動時から動かしておく
+        #[cfg(target_os = "macos")]
+        if let Ok(mut inner) = self.inner.lock() {
+            self.start_syphon(&mut inner);
+        }
+    }
+
+    /// mpv イベントの購読を開始する（コントロールサーバー等から使う）
+    #[allow(dead_code)]
+    pub fn subscribe_events(&self) -> std::sync::mpsc::Receiver<PlayerEvent> {
+        self.events.subscribe()
     }
 
     // ─── 再生制御 ─────────────────────────────────────────────────────────────
 
     pub async fn play(&self, url: &str, quality: Option<&str>) -> Result<()> {
         println!("=== play() called with URL: {} ===", url);
-        let mut inner = self.inner.lock()
-            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        validate_url(url)?;
 
         // 既存のセッションをクリア（プレビューウィンドウと Syphon を停止）
-        if let Some(prev) = inner.preview.take() {
-            prev.stop();
-        }
-        #[cfg(target_os = "macos")]
-        if let Some(syphon) = inner.syphon.take() {
-            syphon.stop();
-        }
-        inner.mpv = None;
-        inner.output_active = false;
+        self.teardown()?;
+
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        inner.session += 1;
+        inner.props = PlaybackProps::default();
+        let session = inner.session;
 
         println!("mpv を初期化: URL={}", url);
         log::info!("mpv を初期化: URL={}", url);
@@ -106,99 +259,264 @@ impl PlayerState {
         // mpv を初期化して再生開始
         let ctx = MpvContext::new(url, quality)?;
 
-        // UI で設定されたボリュームとミュート状態を適用
-        if let Err(e) = ctx.set_volume(inner.pending_volume) {
+        // UI で設定されたボリュームとミュート状態を適用（音量より先に上限を設定する）
+        if let Err(e) = ctx.set_volume_max(inner.volume_settings.max) {
+            log::warn!("ボリュームの上限の設定に失敗: {}", e);
+        }
+        // フェードインする場合は無音で始め、音声が出始めてから上げる
+        let fade_in = inner.volume_settings.fade_in > 0.0;
+        if let Err(e) = ctx.set_volume(if fade_in { 0.0 } else { inner.pending_volume }) {
             log::warn!("初期ボリューム設定に失敗: {}", e);
         }
         if let Err(e) = ctx.set_mute(inner.pending_mute) {
             log::warn!("初期ミュート設定に失敗: {}", e);
         }
-        if let Err(e) = ctx.set_loop(inner.pending_loop) {
+        if let Err(e) = inner.apply_device_audio(&ctx) {
+            log::warn!("音声出力の設定に失敗: {}", e);
+        }
+        // 優先デバイスが抜けている間は既定のデバイスに出力する
+        if let Err(e) = ctx.set_audio_device(inner.active_device()) {
+            log::warn!("出力デバイスの設定に失敗: {}", e);
+        }
+        if inner.pending_loop.is_section() && inner.loop_url.as_deref() != Some(url) {
+            log::info!("別の動画のため区間ループを解除します: {:?}", inner.pending_loop);
+            inner.pending_loop = LoopMode::Off;
+        }
+        if let Err(e) = ctx.apply_loop(&inner.pending_loop) {
             log::warn!("初期ループ設定に失敗: {}", e);
         }
-        log::info!("初期設定を適用: volume={}, mute={}, loop={}", inner.pending_volume, inner.pending_mute, inner.pending_loop);
+        // 字幕の取得言語は loadfile より前に設定する必要がある
+        if let Err(e) = ctx.apply_subtitles(&inner.subtitles) {
+            log::warn!("字幕設定の適用に失敗: {}", e);
+        }
+        if let Err(e) = ctx.apply_track_preferences(inner.tracks.get()) {
+            log::warn!("音声の優先言語の適用に失敗: {}", e);
+        }
+        log::info!("初期設定を適用: volume={}, mute={}, loop={:?}", inner.pending_volume, inner.pending_mute, inner.pending_loop);
 
-        // Syphon 出力を別スレッドで起動する (macOS のみ)
-        // Syphon スレッド内で RenderContext を作成してから loadfile を実行する
-        // app_handle を渡すことで、プレビューも Syphon から直接送信される
+        // イベントディスパッチャを起動（loadfile より前に購読を済ませる）
+        let inner_arc = self.inner.clone();
+        let app_for_events = self.app_handle.clone();
+        events::spawn(ctx.mpv_handle_ptr(), self.events.clone(), self.app_handle.clone(), move |event| {
+            let Ok(mut inner) = inner_arc.lock() else { return };
+            if inner.session != session {
+                return;
+            }
+            // プロパティの変更が絶えず届く間もストールを判定できるよう、時間経過は毎回評価する
+            let now = std::time::Instant::now();
+            let mut changed = event.is_some_and(|event| inner.apply_event(event));
+            changed |= inner.status.on_tick(now);
+            inner.step_volume_fade(now);
+            // loadfile はディスパッチャを止めないよう別スレッドで実行する
+            if let Some(url) = inner.pending_clip.take() {
+                let inner_arc = inner_arc.clone();
+                std::thread::spawn(move || {
+                    let Ok(mut inner) = inner_arc.lock() else { return };
+                    if inner.session != session {
+                        return;
+                    }
+                    if let Err(e) = inner.switch_clip(&url) {
+                        log::warn!("クリップの切り替えに失敗 ({}): {}", url, e);
+                    }
+                });
+            }
+            if changed {
+                emit_status(app_for_events.as_ref(), inner.status.status());
+            }
+        })?;
+
+        // 出力スレッドの描画状況（ウォッチドッグが出力の停止を検知するために使う）
+        let stats = Arc::new(OutputStats::default());
+
+        // Syphon 出力を mpv に接続する (macOS のみ)
+        // 出力スレッドで RenderContext を作成してから loadfile を実行する
+        // プレビューも Syphon 出力から直接送信される
         #[cfg(target_os = "macos")]
         {
-            let handle_ptr = ctx.mpv_handle_ptr();
-            let app_clone = self.app_handle.clone();
-            let server_name = "yt-spout-syphon-bridge";
-            // playing フラグをリセットして Syphon スレッドに渡す
-            self.syphon_playing.store(false, Ordering::SeqCst);
-            let is_playing = self.syphon_playing.clone();
-
-            match syphon::spawn(handle_ptr, server_name, url, PREVIEW_WIDTH, PREVIEW_HEIGHT, app_clone, is_playing) {
-                Ok(handle) => {
-                    inner.syphon = Some(handle);
-                    log::info!("Syphon 出力を起動しました (サーバー名: {})", server_name);
-                }
-                Err(e) => {
-                    log::warn!("Syphon 出力の起動に失敗（再生は続行）: {}", e);
+            self.start_syphon(&mut inner);
+            if let Some(syphon) = &inner.syphon {
+                match syphon.attach(ctx.mpv_handle_ptr(), stats.clone()) {
+                    Ok(()) => log::info!("Syphon 出力に接続しました"),
+                    Err(e) => log::warn!("Syphon 出力への接続に失敗（再生は続行）: {}", e),
                 }
             }
         }
 
-        log::info!("プレビューは Syphon 出力から直接送信されます");
+        if let Err(e) = ctx.reload_at(url, 0.0) {
+            #[cfg(target_os = "macos")]
+            if let Some(syphon) = &inner.syphon {
+                syphon.detach();
+            }
+            // mpv の破棄はディスパッチャスレッドの終了を待つため、ロックを外してから行う
+            drop(inner);
+            drop(ctx);
+            return Err(e);
+        }
+        log::info!("loadfile コマンドを実行: {}", url);
 
         inner.mpv = Some(ctx);
-        inner.status = PlayStatus::Loading;
+        inner.status.set(PlayStatus::Loading);
         inner.current_url = Some(url.to_string());
-        inner.output_active = true;
+        inner.cues = inner.cue_store.get(url);
+        inner.cue_pos = None;
+        inner.pending_clip = None;
+        inner.volume_fade = None;
+        inner.fade_in_pending = fade_in;
+        inner.output_stats = Some(stats.clone());
+
+        // ストリーム障害を監視して自動復旧する
+        watchdog::spawn(self.inner.clone(), session, self.events.clone(), stats);
 
         Ok(())
     }
 
     pub async fn stop(&self) -> Result<()> {
+        self.fade_out().await?;
+        self.teardown()?;
         let mut inner = self.inner.lock()
             .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
-        // プレビューウィンドウを停止
-        if let Some(prev) = inner.preview.take() {
-            prev.stop();
+        inner.status.set(PlayStatus::Idle);
+        inner.current_url = None;
+        Ok(())
+    }
+
+    /// 現在のセッション（プレビュー・mpv）を破棄する
+    ///
+    /// Syphon 出力は mpv から切り離すだけで、サーバーはスタンバイ映像を送り続ける。
+    /// mpv の破棄はディスパッチャスレッドの終了を待つため、
+    /// ディスパッチャのコールバックと競合しないようロックを外してから行う。
+    fn teardown(&self) -> Result<()> {
+        let mpv = {
+            let mut inner = self.inner.lock()
+                .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+            // プレビューウィンドウを停止
+            if let Some(prev) = inner.preview.take() {
+                prev.stop();
+            }
+            // Syphon 出力を mpv から切り離す (macOS のみ。mpv より先に RenderContext を破棄する)
+            #[cfg(target_os = "macos")]
+            if let Some(syphon) = &inner.syphon {
+                syphon.detach();
+            }
+            // 古いディスパッチャからのイベントを無視させる
+            inner.session += 1;
+            inner.output_stats = None;
+            inner.mpv.take()
+        };
+        drop(mpv);
+        Ok(())
+    }
+
+    /// 再生を終了し、出力のサーバーも停止する（アプリ終了時に呼ぶ）
+    pub fn shutdown(&self) {
+        if let Err(e) = self.teardown() {
+            log::warn!("再生の終了に失敗: {}", e);
         }
-        // Syphon 出力を停止 (macOS のみ)
         #[cfg(target_os = "macos")]
-        if let Some(syphon) = inner.syphon.take() {
-            syphon.stop();
+        {
+            let syphon = self.inner.lock().ok().and_then(|mut inner| inner.syphon.take());
+            if let Some(syphon) = syphon {
+                syphon.stop();
+            }
+        }
+    }
+
+    /// Syphon 出力を起動する（無効にされている・起動済みの場合は何もしない）
+    #[cfg(target_os = "macos")]
+    fn start_syphon(&self, inner: &mut PlayerInner) {
+        if !self.outputs.get("syphon").enabled || inner.output_active() {
+            return;
+        }
+        // 起動時のエラーなどで終了したスレッドは作り直す
+        if let Some(old) = inner.syphon.take() {
+            old.stop();
+        }
+        let server_name = "yt-spout-syphon-bridge";
+        match syphon::spawn(server_name, PREVIEW_WIDTH, PREVIEW_HEIGHT, self.app_handle.clone(), self.events.clone(), self.outputs.clone()) {
+            Ok(handle) => {
+                inner.syphon = Some(handle);
+                log::info!("Syphon 出力を起動しました (サーバー名: {})", server_name);
+            }
+            Err(e) => log::warn!("Syphon 出力の起動に失敗: {}", e),
         }
-        inner.mpv = None;
-        inner.status = PlayStatus::Idle;
-        inner.current_url = None;
-        inner.output_active = false;
-        Ok(())
     }
 
     pub async fn toggle_pause(&self) -> Result<bool> {
+        let pausing = {
+            let inner = self.inner.lock()
+                .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+            if inner.mpv.is_none() {
+                return Err(AppError::NotPlaying.into());
+            }
+            !inner.props.paused
+        };
+        // 一時停止は音量を下げきってから行う
+        if pausing {
+            self.fade_out().await?;
+        }
+
         let mut inner = self.inner.lock()
             .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let Some(mpv) = &inner.mpv else { return Err(AppError::NotPlaying.into()) };
+        let paused: bool = mpv.toggle_pause()?;
+        // 一時停止中に元の音量へ戻しておき、再開時はフェードインする
+        let settings = inner.volume_settings.clone();
+        let target = inner.pending_volume;
+        let fade_in = !paused && settings.fade_in > 0.0;
+        mpv.set_volume(if fade_in { 0.0 } else { target })?;
+        inner.props.paused = paused;
+        inner.volume_fade = fade_in.then(|| VolumeFade::new(0.0, target, settings.fade_in, settings.curve));
+        // ステータスは pause プロパティの変更イベントで更新される
+        Ok(paused)
+    }
+
+    /// 再生中なら設定の時間で音量を 0 まで下げ、下がりきるまで待つ（停止・一時停止の前に呼ぶ）
+    ///
+    /// pending_volume は変えないため、次の再生・再開は元の音量に戻る。
+    async fn fade_out(&self) -> Result<()> {
+        let seconds = {
+            let mut inner = self.inner.lock()
+                .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+            let settings = inner.volume_settings.clone();
+            if settings.fade_out <= 0.0 || inner.mpv.is_none() || inner.props.paused {
+                return Ok(());
+            }
+            let fade = VolumeFade::new(inner.props.volume, 0.0, settings.fade_out, settings.curve);
+            inner.volume_fade = Some(fade);
+            inner.fade_in_pending = false;
+            settings.fade_out
+        };
+        tokio::time::sleep(std::time::Duration::from_secs_f64(seconds)).await;
+
+        // 最後の 1 歩がディスパッチャで反映される前でも無音で止まるようにする
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        inner.volume_fade = None;
         if let Some(mpv) = &inner.mpv {
-            let paused: bool = mpv.toggle_pause()?;
-            inner.status = if paused {
-                PlayStatus::Paused
-            } else {
-                PlayStatus::Playing
-            };
-            return Ok(paused);
+            if let Err(e) = mpv.set_volume(0.0) {
+                log::warn!("フェードアウト後の音量設定に失敗: {}", e);
+            }
         }
-        Ok(false)
+        Ok(())
     }
 
     // ─── 状態の読み取り ───────────────────────────────────────────────────────
 
     pub fn status(&self) -> PlayStatus {
-        // Syphon スレッドが最初のフレームを描画した場合は Playing に昇格
-        if self.syphon_playing.load(Ordering::SeqCst) {
-            if let Ok(mut inner) = self.inner.lock() {
-                if matches!(inner.status, PlayStatus::Loading) {
-                    inner.status = PlayStatus::Playing;
-                }
-            }
-        }
         self.inner.lock()
-            .map(|inner| inner.status.clone())
-            .unwrap_or(PlayStatus::Error("Mutex ロック失敗".to_string()))
+            .map(|inner| inner.status.status().clone())
+            .unwrap_or_else(|_| PlayStatus::Error(PlayError {
+                cause: ErrorCause::Output,
+                message: "Mutex ロック失敗".to_string(),
+            }))
+    }
+
+    /// ディスパッチャが最後に受け取ったプロパティ値
+    #[allow(dead_code)]
+    pub fn playback_props(&self) -> PlaybackProps {
+        self.inner.lock()
+            .map(|inner| inner.props.clone())
+            .unwrap_or_default()
     }
 
     pub fn current_url(&self) -> Option<String> {
@@ -209,7 +527,7 @@ impl PlayerState {
 
     pub fn is_output_active(&self) -> bool {
         self.inner.lock()
-            .map(|inner| inner.output_active)
+            .map(|inner| inner.output_active())
             .unwrap_or(false)
     }
 
@@ -226,8 +544,10 @@ impl PlayerState {
         if let Some(mpv) = &inner.mpv {
             log::info!("mpv からデバイス一覧を取得します");
             match mpv.list_audio_devices() {
-                Ok(devices) => {
+                Ok(mut devices) => {
                     log::info!("mpv から {} 個のデバイスを取得しました", devices.len());
+                    // JACK の接続先は mpv の一覧に含まれない
+                    devices.extend(audio::extra_devices());
                     devices
                 }
                 Err(e) => {
@@ -242,20 +562,136 @@ impl PlayerState {
         }
     }
 
+    /// mpv が起動中なら mpv が認識している出力デバイスの一覧（JACK の接続先を含む）
+    fn mpv_audio_devices(&self) -> Option<Vec<(String, String)>> {
+        let inner = self.inner.lock().ok()?;
+        let mut devices = inner.mpv.as_ref()?.list_audio_devices().ok()?;
+        devices.extend(audio::extra_devices());
+        Some(devices)
+    }
+
     pub async fn set_audio_device(&self, device_id: &str) -> Result<()> {
+        // "" と "auto" はデフォルトデバイスへのリセット
+        // 存在の確認は mpv の一覧でだけ行う（停止中の一覧は OS から列挙できた一部のデバイスしか含まないため、
+        // 載っていない ID も受け付け、誤っていれば再生時に mpv のエラーになる）
+        if !device_id.is_empty() && device_id != "auto" {
+            if let Some(devices) = self.mpv_audio_devices() {
+                if !devices.iter().any(|(id, _)| id == device_id) {
+                    return Err(AppError::DeviceNotFound(device_id.to_string()).into());
+                }
+            }
+        }
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        // 優先デバイスとして保存し、次回の再生・起動でも同じデバイスに出力する
+        let device = if device_id.is_empty() { "auto".to_string() } else { device_id.to_string() };
+        inner.device_missing = false;
+        inner.device_fallback = false;
+        inner.audio.update(|settings| settings.device = device.clone())?;
+        if let Some(mpv) = &inner.mpv {
+            // 切り替え先のデバイスの遅延とチャンネルの割り当てに切り替える
+            inner.apply_device_audio(mpv)?;
+            mpv.set_audio_device(&device).map_err(|e| anyhow::anyhow!("{}", e))?;
+        }
+        Ok(())
+    }
+
+    /// 優先デバイスが抜けたときの動作を設定する
+    pub fn set_audio_device_policy(&self, policy: DevicePolicy) -> Result<()> {
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        inner.audio.update(|settings| settings.policy = policy)?;
+        // fallback をやめたら、接続されている優先デバイスに戻す
+        if policy != DevicePolicy::Fallback && inner.device_fallback {
+            inner.device_fallback = false;
+            if let (false, Some(mpv)) = (inner.device_missing, &inner.mpv) {
+                let device = inner.audio.get().device.clone();
+                inner.apply_device_audio(mpv)?;
+                mpv.set_audio_device(&device)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 優先デバイスとその接続状況を返す
+    pub fn audio_device_status(&self) -> Result<AudioDeviceStatus> {
         let inner = self.inner.lock()
             .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(device_status(&inner))
+    }
+
+    /// JACK のクライアント名・PipeWire のノード名とチャンネル数を設定する
+    ///
+    /// 再生中の場合は出力を作り直すため、音声が一瞬途切れる。
+    pub fn set_audio_output_settings(&self, settings: AudioOutputSettings) -> Result<()> {
+        settings.validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        inner.audio.update(|saved| saved.output = settings)?;
         if let Some(mpv) = &inner.mpv {
-            mpv.set_audio_device(device_id).map_err(|e| anyhow::anyhow!("{}", e))?;
+            inner.apply_device_audio(mpv)?;
+            mpv.reload_audio_output()?;
         }
         Ok(())
     }
 
-    pub async fn set_volume(&self, volume: u8) -> Result<()> {
+    pub fn audio_output_settings(&self) -> Result<AudioOutputSettings> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(inner.audio.get().output.clone())
+    }
+
+    /// 出力中のデバイスの音声の遅延を設定して保存する（秒。プロジェクターの遅延に合わせる）
+    pub fn set_audio_delay(&self, seconds: f64) -> Result<()> {
+        self.update_device_audio(|audio| audio.delay = seconds)
+    }
+
+    /// 出力中のデバイスのチャンネルの割り当てを設定して保存する
+    pub fn set_channel_routing(&self, routing: ChannelRouting) -> Result<()> {
+        self.update_device_audio(|audio| audio.routing = routing)
+    }
+
+    /// 出力中のデバイスの遅延とチャンネルの割り当て
+    pub fn device_audio(&self) -> Result<DeviceAudio> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(inner.audio.get().device_audio(inner.active_device()))
+    }
+
+    fn update_device_audio(&self, change: impl FnOnce(&mut DeviceAudio)) -> Result<()> {
         let mut inner = self.inner.lock()
             .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let device = inner.active_device().to_string();
+        let before = inner.audio.get().device_audio(&device);
+        let mut audio = before;
+        change(&mut audio);
+        audio.validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        inner.audio.update(|settings| {
+            settings.devices.insert(device, audio);
+        })?;
+        if let Some(mpv) = &inner.mpv {
+            inner.apply_device_audio(mpv)?;
+            // 必要なチャンネル数が変わった場合は出力を作り直す
+            let settings = inner.audio.get();
+            if settings.output_for(before.routing) != settings.output_for(audio.routing) {
+                mpv.reload_audio_output()?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn set_volume(&self, volume: f64) -> Result<()> {
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        inner.volume_settings.check_volume(volume)
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
         // pending_volume を常に更新（次回再生時に適用される）
         inner.pending_volume = volume;
+        // 手動の操作をフェードより優先する
+        inner.volume_fade = None;
+        inner.fade_in_pending = false;
         // mpv が起動中であれば即座に適用
         if let Some(mpv) = &inner.mpv {
             mpv.set_volume(volume).map_err(|e| anyhow::anyhow!("{}", e))?;
@@ -263,6 +699,66 @@ impl PlayerState {
         Ok(())
     }
 
+    /// 音量を dB で設定する（0 dB が原音のまま）
+    pub async fn set_volume_db(&self, db: f64) -> Result<()> {
+        self.set_volume(volume::db_to_volume(db)).await
+    }
+
+    /// 音量を duration_ms かけて volume（%）まで変える
+    ///
+    /// curve を省略すると音量の設定のカーブを使う。再生していない場合は次の再生の音量になる。
+    pub fn fade_volume(&self, volume: f64, duration_ms: u64, curve: Option<FadeCurve>) -> Result<()> {
+        let seconds = duration_ms as f64 / 1000.0;
+        volume::validate_duration("duration", seconds)
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        inner.volume_settings.check_volume(volume)
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        let curve = curve.unwrap_or(inner.volume_settings.curve);
+        inner.pending_volume = volume;
+        inner.fade_in_pending = false;
+        if inner.mpv.is_some() {
+            let fade = VolumeFade::new(inner.props.volume, volume, seconds, curve);
+            inner.volume_fade = Some(fade);
+        }
+        Ok(())
+    }
+
+    /// 音量を duration_ms かけて db まで変える
+    pub fn fade_volume_db(&self, db: f64, duration_ms: u64, curve: Option<FadeCurve>) -> Result<()> {
+        self.fade_volume(volume::db_to_volume(db), duration_ms, curve)
+    }
+
+    /// 現在の音量（フェード中は途中の値）
+    pub fn volume_level(&self) -> Result<VolumeLevel> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let volume = if inner.mpv.is_some() { inner.props.volume } else { inner.pending_volume };
+        Ok(VolumeLevel::new(volume, inner.volume_settings.max))
+    }
+
+    /// 音量の上限とフェードの設定を反映する
+    pub fn set_volume_settings(&self, settings: VolumeSettings) -> Result<()> {
+        settings.validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        // 上限を下げた場合は音量も上限までに収める（再生中の音量は mpv が収める）
+        inner.pending_volume = inner.pending_volume.min(settings.max);
+        if let Some(mpv) = &inner.mpv {
+            mpv.set_volume_max(settings.max)?;
+        }
+        inner.volume_settings = settings;
+        Ok(())
+    }
+
+    pub fn volume_settings(&self) -> Result<VolumeSettings> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(inner.volume_settings.clone())
+    }
+
     pub async fn set_mute(&self, mute: bool) -> Result<()> {
         let mut inner = self.inner.lock()
             .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
@@ -285,37 +781,139 @@ impl PlayerState {
         Ok(inner.pending_mute)
     }
 
-    // ─── プレイヤー制御の拡張機能 ─────────────────────────────────────────────
+    // ─── 音声メーター ─────────────────────────────────────────────────────────
 
-    pub async fn set_loop(&self, enabled: bool) -> Result<()> {
+    /// メーターの設定を反映する（有効ならメーターを起動し直し、音声フィルタを切り替える）
+    pub fn set_meter_settings(&self, settings: MeterSettings) -> Result<()> {
+        settings.validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
         let mut inner = self.inner.lock()
             .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
-        // pending_loop を常に更新（次回再生時に引き継がれる）
-        inner.pending_loop = enabled;
+        if let Some(meter) = inner.meter.take() {
+            meter.stop();
+        }
+        if settings.enabled {
+            inner.meter = Some(meter::spawn(self.inner.clone(), settings.clone(), self.events.clone())?);
+        }
+        // 再生中に切り替えると音声の出力が一瞬途切れる
         if let Some(mpv) = &inner.mpv {
-            mpv.set_loop(enabled).map_err(|e| anyhow::anyhow!("{}", e))?;
+            inner.apply_audio_filters(mpv)?;
         }
+        inner.meter_settings = settings;
         Ok(())
     }
 
-    pub fn get_loop(&self) -> Result<bool> {
+    pub fn meter_settings(&self) -> Result<MeterSettings> {
         let inner = self.inner.lock()
             .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(inner.meter_settings.clone())
+    }
+
+    /// メーターの最新の測定値（無効な場合は空）
+    pub fn meter_levels(&self) -> Result<MeterLevels> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(inner.meter.as_ref().map(|meter| meter.levels()).unwrap_or_default())
+    }
+
+    // ─── プレイヤー制御の拡張機能 ─────────────────────────────────────────────
+
+    /// ループ再生のオン・オフ（オンはファイル全体のループ）
+    pub async fn set_loop(&self, enabled: bool) -> Result<()> {
+        let mode = if enabled { LoopMode::File } else { LoopMode::Off };
+        self.set_loop_mode(mode)
+    }
+
+    /// いずれかのループが有効か
+    pub fn get_loop(&self) -> Result<bool> {
+        Ok(self.loop_mode()? != LoopMode::Off)
+    }
+
+    /// ループ再生を設定する（次回の再生にも引き継がれる。区間のループは同じ動画のみ）
+    pub fn set_loop_mode(&self, mode: LoopMode) -> Result<()> {
+        mode.validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
         if let Some(mpv) = &inner.mpv {
-            return mpv.get_loop().map_err(|e| anyhow::anyhow!("{}", e));
+            if let LoopMode::Chapter { index } = mode {
+                let chapters = mpv.chapters()?;
+                if !chapters.iter().any(|chapter| chapter.index == index) {
+                    return Err(AppError::InvalidArgument(format!("チャプターが見つかりません: {}", index)).into());
+                }
+            }
+            mpv.apply_loop(&mode).map_err(|e| anyhow::anyhow!("{}", e))?;
         }
-        Ok(false)
+        log::info!("ループを設定: {:?}", mode);
+        // pending_loop を常に更新（次回再生時に引き継がれる）
+        inner.loop_url = inner.current_url.clone();
+        inner.pending_loop = mode;
+        Ok(())
     }
 
-    pub async fn seek(&self, seconds: f64) -> Result<()> {
+    pub fn loop_mode(&self) -> Result<LoopMode> {
         let inner = self.inner.lock()
             .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
-        if let Some(mpv) = &inner.mpv {
-            mpv.seek(seconds).map_err(|e| anyhow::anyhow!("{}", e))?;
+        Ok(inner.pending_loop.clone())
+    }
+
+    /// 現在の再生位置を A-B ループの A 点または B 点にする
+    pub fn set_loop_point(&self, point: LoopPoint) -> Result<LoopMode> {
+        let position = {
+            let inner = self.inner.lock()
+                .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+            let mpv = inner.mpv.as_ref().ok_or(AppError::NotPlaying)?;
+            mpv.get_time_pos()?
+        };
+        let mode = self
+            .loop_mode()?
+            .with_point(point, position)
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        self.set_loop_mode(mode.clone())?;
+        Ok(mode)
+    }
+
+    pub async fn seek(&self, seconds: f64) -> Result<()> {
+        if !seconds.is_finite() || seconds < 0.0 {
+            return Err(AppError::InvalidArgument(format!("seconds={}", seconds)).into());
         }
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let mpv = inner.mpv.as_ref().ok_or(AppError::NotPlaying)?;
+        mpv.seek(seconds).map_err(|e| anyhow::anyhow!("{}", e))?;
         Ok(())
     }
 
+    /// 基準（絶対位置・相対・パーセント）と精度を指定してシークする
+    pub fn seek_with(&self, request: SeekRequest) -> Result<()> {
+        request.validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let mpv = inner.mpv.as_ref().ok_or(AppError::NotPlaying)?;
+        mpv.seek_with(&request)
+    }
+
+    /// 1 フレーム進める / 戻す（移動後は一時停止する）
+    pub fn frame_step(&self, forward: bool) -> Result<()> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let mpv = inner.mpv.as_ref().ok_or(AppError::NotPlaying)?;
+        mpv.frame_step(forward)
+    }
+
+    /// フレーム番号の位置へ正確に移動する。移動先の秒数を返す
+    pub fn seek_to_frame(&self, frame: u64) -> Result<f64> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let mpv = inner.mpv.as_ref().ok_or(AppError::NotPlaying)?;
+        let fps = mpv.video_fps()?;
+        let seconds = seek::frame_time(frame, fps)?;
+        log::info!("フレーム {} へ移動 ({:.3} 秒, {} fps)", frame, seconds, fps);
+        mpv.seek_with(&SeekRequest::exact(seconds))?;
+        Ok(seconds)
+    }
+
     pub fn get_time_pos(&self) -> Result<f64> {
         let inner = self.inner.lock()
             .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
@@ -360,4 +958,774 @@ impl PlayerState {
         }
         Ok(String::new())
     }
+
+    // ─── チャプター ───────────────────────────────────────────────────────────
+
+    /// チャプターの一覧（チャプターの無い動画では空）
+    pub fn chapters(&self) -> Result<Vec<Chapter>> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let mpv = inner.mpv.as_ref().ok_or(AppError::NotPlaying)?;
+        mpv.chapters()
+    }
+
+    /// チャプターの先頭へ移動する
+    pub fn jump_to_chapter(&self, index: i64) -> Result<()> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let mpv = inner.mpv.as_ref().ok_or(AppError::NotPlaying)?;
+        let chapters = mpv.chapters()?;
+        if !chapters.iter().any(|chapter| chapter.index == index) {
+            return Err(AppError::InvalidArgument(format!("チャプターが見つかりません: {}", index)).into());
+        }
+        log::info!("チャプター {} へ移動", index);
+        mpv.set_chapter(index)
+    }
+
+    // ─── トラック ─────────────────────────────────────────────────────────────
+
+    /// 音声・映像・字幕トラックの一覧
+    pub fn tracks(&self) -> Result<Vec<Track>> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let mpv = inner.mpv.as_ref().ok_or(AppError::NotPlaying)?;
+        mpv.tracks()
+    }
+
+    /// トラックを ID で選択する（None で選択を外す）
+    ///
+    /// 音声・字幕はトラックの言語を優先言語として覚え、次回の再生でも同じ言語を選ぶ。
+    pub fn select_track(&self, kind: TrackKind, id: Option<i64>) -> Result<()> {
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let mpv = inner.mpv.as_ref().ok_or(AppError::NotPlaying)?;
+        let Some(id) = id else {
+            return mpv.select_track(kind, None);
+        };
+
+        let tracks = mpv.tracks()?;
+        let track = tracks
+            .iter()
+            .find(|track| track.kind == kind && track.id == id)
+            .ok_or_else(|| AppError::InvalidArgument(format!("トラックが見つかりません: {:?} {}", kind, id)))?;
+        mpv.select_track(kind, Some(id))?;
+        log::info!("トラックを選択: {:?} {} ({:?})", kind, id, track.lang);
+
+        let Some(lang) = track.lang.clone() else { return Ok(()) };
+        // 選択はできているので、保存に失敗しても選択自体は成功として返す
+        let saved = match kind {
+            TrackKind::Audio => inner.tracks.update(|tracks| tracks.audio_language = Some(lang)),
+            // 自動生成字幕の "-orig" は外して覚える（find_track は "en" で "en-orig" にも合う）
+            TrackKind::Sub => {
+                let lang = lang.trim_end_matches("-orig").to_string();
+                inner.subtitles.language = Some(lang.clone());
+                inner.tracks.update(|tracks| tracks.sub_language = Some(lang))
+            }
+            TrackKind::Video => Ok(()),
+        };
+        if let Err(e) = saved {
+            log::warn!("トラック設定の保存に失敗: {}", e);
+        }
+        Ok(())
+    }
+
+    /// 音声・字幕の優先言語を設定する（次回の再生にも引き継がれる）
+    pub fn set_track_preferences(&self, preferences: TrackPreferences) -> Result<()> {
+        preferences
+            .validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        if let Some(mpv) = &inner.mpv {
+            mpv.apply_track_preferences(&preferences)?;
+        }
+        // 字幕の言語は字幕の設定にも反映する
+        if preferences.sub_language != inner.subtitles.language {
+            inner.subtitles.language = preferences.sub_language.clone();
+            if let Some(mpv) = &inner.mpv {
+                mpv.apply_subtitles(&inner.subtitles)?;
+            }
+        }
+        log::info!("トラックの優先言語を設定: {:?}", preferences);
+        inner.tracks.update(|tracks| *tracks = preferences)
+    }
+
+    pub fn track_preferences(&self) -> Result<TrackPreferences> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(inner.tracks.get().clone())
+    }
+
+    // ─── タイムコード ─────────────────────────────────────────────────────────
+
+    /// 外部タイムコード（MTC / LTC）への追従を開始する（動作中のチェイスは置き換える）
+    pub fn start_chase(&self, settings: ChaseSettings) -> Result<()> {
+        settings.validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        // どれも再生速度を操作するため、ビート同期・同期のフォロワーとは同時に動かさない
+        self.stop_beat_sync()?;
+        self.stop_following()?;
+        self.stop_chase()?;
+        // 入力を開くまで待つため、ロックを持たずに起動する
+        let handle = chase::spawn(self.inner.clone(), settings, self.events.clone())?;
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        inner.chase = Some(handle);
+        Ok(())
+    }
+
+    pub fn stop_chase(&self) -> Result<()> {
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        if let Some(chase) = inner.chase.take() {
+            chase.stop();
+        }
+        Ok(())
+    }
+
+    /// チェイスの状態（ロック状態・受信中のタイムコード・差）
+    pub fn chase_status(&self) -> Result<ChaseStatus> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(inner.chase.as_ref().map(|chase| chase.status()).unwrap_or_default())
+    }
+
+    // ─── ビート同期 ───────────────────────────────────────────────────────────
+
+    /// ネットワークのテンポへの同期を開始する（動作中の同期・チェイスは止める）
+    pub fn start_beat_sync(&self, settings: BeatSyncSettings) -> Result<()> {
+        settings.validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        self.stop_chase()?;
+        self.stop_following()?;
+        self.stop_beat_sync()?;
+        let handle = beat_sync::spawn(self.inner.clone(), settings, self.events.clone())?;
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        inner.beat_sync = Some(handle);
+        Ok(())
+    }
+
+    pub fn stop_beat_sync(&self) -> Result<()> {
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        if let Some(beat_sync) = inner.beat_sync.take() {
+            beat_sync.stop();
+        }
+        Ok(())
+    }
+
+    /// ビート同期の状態（セッションのテンポ・位相差・速度）
+    pub fn beat_sync_status(&self) -> Result<BeatSyncStatus> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(inner.beat_sync.as_ref().map(|sync| sync.status()).unwrap_or_default())
+    }
+
+    // ─── 複数台の同期 ─────────────────────────────────────────────────────────
+
+    /// マスターまたはフォロワーとして同期を開始する（動作中の同期は置き換える）
+    ///
+    /// フォロワーは再生速度を操作するため、チェイス・ビート同期を止める。
+    pub fn start_net_sync(&self, settings: NetSyncSettings) -> Result<()> {
+        settings.validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        if settings.is_follower() {
+            self.stop_chase()?;
+            self.stop_beat_sync()?;
+        }
+        self.stop_net_sync()?;
+        let handle = net_sync::spawn(self.inner.clone(), settings, self.events.clone())?;
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        inner.net_sync = Some(handle);
+        Ok(())
+    }
+
+    pub fn stop_net_sync(&self) -> Result<()> {
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        if let Some(net_sync) = inner.net_sync.take() {
+            net_sync.stop();
+        }
+        Ok(())
+    }
+
+    /// 同期の状態（フォロワーはマスターとの差を含む）
+    pub fn net_sync_status(&self) -> Result<NetSyncStatus> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(inner.net_sync.as_ref().map(|sync| sync.status()).unwrap_or_default())
+    }
+
+    /// フォロワーとして動いている同期だけを止める（マスターは続ける）
+    fn stop_following(&self) -> Result<()> {
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        if inner.net_sync.as_ref().is_some_and(|sync| sync.is_follower()) {
+            if let Some(net_sync) = inner.net_sync.take() {
+                net_sync.stop();
+            }
+        }
+        Ok(())
+    }
+
+    // ─── キューポイント ───────────────────────────────────────────────────────
+
+    /// クリップ（URL）のキューポイントを置き換えて保存する
+    /// 再生中のクリップであればすぐに反映する
+    pub fn set_cues(&self, url: &str, cues: Vec<Cue>) -> Result<()> {
+        for cue in &cues {
+            cue.validate()
+                .map_err(|e| AppError::InvalidArgument(format!("キュー {}: {}", cue.id, e)))?;
+        }
+        let mut ids: Vec<&str> = cues.iter().map(|cue| cue.id.as_str()).collect();
+        ids.sort_unstable();
+        if let Some(pair) = ids.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(AppError::InvalidArgument(format!("キューの id が重複しています: {}", pair[0])).into());
+        }
+
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        log::info!("キューを設定: {} ({} 件)", url, cues.len());
+        inner.cue_store.set(url, cues)?;
+        if inner.current_url.as_deref() == Some(url) {
+            inner.cues = inner.cue_store.get(url);
+        }
+        Ok(())
+    }
+
+    pub fn cues(&self, url: &str) -> Result<Vec<Cue>> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(inner.cue_store.get(url))
+    }
+
+    // ─── 字幕 ─────────────────────────────────────────────────────────────────
+
+    /// 字幕トラックの一覧（yt-dlp が取得した字幕・自動生成字幕を含む）
+    pub fn subtitle_tracks(&self) -> Result<Vec<SubtitleTrack>> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let mpv = inner.mpv.as_ref().ok_or(AppError::NotPlaying)?;
+        mpv.subtitle_tracks()
+    }
+
+    /// 字幕トラックを ID で選択する（None で字幕なし）
+    pub fn select_subtitle(&self, id: Option<i64>) -> Result<()> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        let mpv = inner.mpv.as_ref().ok_or(AppError::NotPlaying)?;
+        if let Some(id) = id {
+            let tracks = mpv.subtitle_tracks()?;
+            if !tracks.iter().any(|track| track.id == id) {
+                return Err(AppError::InvalidArgument(format!("字幕トラックが見つかりません: {}", id)).into());
+            }
+        }
+        mpv.select_subtitle(id)
+    }
+
+    /// 字幕の表示先・言語・見た目を設定する（次回の再生にも引き継がれる）
+    pub fn set_subtitle_settings(&self, settings: SubtitleSettings) -> Result<()> {
+        settings
+            .validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        let mut inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        if let Some(mpv) = &inner.mpv {
+            mpv.apply_subtitles(&settings)?;
+        }
+        log::info!("字幕を設定: {:?}", settings);
+        self.outputs.set_preview_overlays(settings.preview_layer().into_iter().collect())?;
+        // 言語は tracks.json に保存し、次回の起動でも使う
+        if inner.tracks.get().sub_language != settings.language {
+            let language = settings.language.clone();
+            inner.tracks.update(|tracks| tracks.sub_language = language)?;
+        }
+        inner.subtitles = settings;
+        Ok(())
+    }
+
+    pub fn subtitle_settings(&self) -> Result<SubtitleSettings> {
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(inner.subtitles.clone())
+    }
+
+    // ─── 出力設定 ─────────────────────────────────────────────────────────────
+
+    /// 出力のスタンバイ映像を設定する（再生中の出力にもすぐ反映される）
+    pub fn set_standby_source(&self, output: &str, source: StandbySource) -> Result<()> {
+        check_output(output)?;
+        source
+            .validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        log::info!("スタンバイ映像を設定: {} = {:?}", output, source);
+        self.outputs.update(output, |settings| settings.standby = source)
+    }
+
+    pub fn standby_source(&self, output: &str) -> Result<StandbySource> {
+        check_output(output)?;
+        Ok(self.outputs.get(output).standby)
+    }
+
+    /// 出力を有効・無効にする（無効にするとサーバーを停止し、有効にすると起動し直す）
+    pub fn set_output_enabled(&self, output: &str, enabled: bool) -> Result<()> {
+        check_output(output)?;
+        log::info!("出力を{}します: {}", if enabled { "有効に" } else { "無効に" }, output);
+        self.outputs.update(output, |settings| settings.enabled = enabled)?;
+
+        #[cfg(target_os = "macos")]
+        {
+            let mut inner = self.inner.lock()
+                .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+            if !enabled {
+                if let Some(syphon) = inner.syphon.take() {
+                    syphon.stop();
+                }
+                if let Some(stats) = &inner.output_stats {
+                    stats.reset_frames();
+                }
+            } else if !inner.output_active() {
+                self.start_syphon(&mut inner);
+                // 再生中なら接続し、描画先が無かった間の映像を今の位置から読み込み直す
+                if let (Some(mpv), Some(syphon), Some(stats), Some(url)) =
+                    (&inner.mpv, &inner.syphon, &inner.output_stats, &inner.current_url)
+                {
+                    syphon.attach(mpv.mpv_handle_ptr(), stats.clone())?;
+                    mpv.reload_at(url, inner.props.time_pos)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn output_enabled(&self, output: &str) -> Result<bool> {
+        check_output(output)?;
+        Ok(self.outputs.get(output).enabled)
+    }
+
+    /// 出力の映像変形を設定する（再生中の出力にもすぐ反映される）
+    pub fn set_output_transform(&self, output: &str, transform: Transform) -> Result<()> {
+        check_output(output)?;
+        transform
+            .validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        log::info!("映像変形を設定: {} = {:?}", output, transform);
+        self.outputs.update(output, |settings| settings.transform = transform)
+    }
+
+    pub fn output_transform(&self, output: &str) -> Result<Transform> {
+        check_output(output)?;
+        Ok(self.outputs.get(output).transform)
+    }
+
+    /// 出力の色調整と LUT を設定する（再生中の出力にもすぐ反映される）
+    pub fn set_output_color(&self, output: &str, color: ColorAdjust) -> Result<()> {
+        check_output(output)?;
+        color
+            .validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        log::info!("色調整を設定: {} = {:?}", output, color);
+        self.outputs.update(output, |settings| settings.color = color)
+    }
+
+    pub fn output_color(&self, output: &str) -> Result<ColorAdjust> {
+        check_output(output)?;
+        Ok(self.outputs.get(output).color)
+    }
+
+    /// 出力のユーザーシェーダを設定する（設定順に適用。ファイルの変更は自動で読み直す）
+    pub fn set_output_shaders(&self, output: &str, shaders: Vec<ShaderEffect>) -> Result<()> {
+        check_output(output)?;
+        for shader in &shaders {
+            shader
+                .validate()
+                .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        }
+        log::info!("シェーダを設定: {} = {:?}", output, shaders);
+        self.outputs.update(output, |settings| settings.shaders = shaders)
+    }
+
+    pub fn output_shaders(&self, output: &str) -> Result<Vec<ShaderEffect>> {
+        check_output(output)?;
+        Ok(self.outputs.get(output).shaders)
+    }
+
+    /// 出力のキーイングを設定する（再生中の出力にもすぐ反映される）
+    pub fn set_output_key(&self, output: &str, key: Key) -> Result<()> {
+        check_output(output)?;
+        key.validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        log::info!("キーイングを設定: {} = {:?}", output, key);
+        self.outputs.update(output, |settings| settings.key = key)
+    }
+
+    pub fn output_key(&self, output: &str) -> Result<Key> {
+        check_output(output)?;
+        Ok(self.outputs.get(output).key)
+    }
+
+    /// 出力にオーバーレイのレイヤーを追加する（同じ id があれば置き換える）
+    pub fn set_overlay(&self, output: &str, layer: OverlayLayer) -> Result<()> {
+        check_output(output)?;
+        layer
+            .validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        log::info!("オーバーレイを設定: {} = {:?}", output, layer);
+        self.outputs.update(output, |settings| {
+            match settings.overlays.iter_mut().find(|l| l.id == layer.id) {
+                Some(existing) => *existing = layer,
+                None => settings.overlays.push(layer),
+            }
+        })
+    }
+
+    /// 出力からオーバーレイのレイヤーを取り除く（フェードせずに消える）
+    pub fn remove_overlay(&self, output: &str, id: &str) -> Result<()> {
+        check_output(output)?;
+        if !self.outputs.get(output).overlays.iter().any(|l| l.id == id) {
+            return Err(AppError::InvalidArgument(format!("オーバーレイが見つかりません: {}", id)).into());
+        }
+        self.outputs.update(output, |settings| settings.overlays.retain(|l| l.id != id))
+    }
+
+    /// レイヤーの表示を切り替える（fade_in / fade_out に従ってフェードする）
+    pub fn set_overlay_visible(&self, output: &str, id: &str, visible: bool) -> Result<()> {
+        check_output(output)?;
+        if !self.outputs.get(output).overlays.iter().any(|l| l.id == id) {
+            return Err(AppError::InvalidArgument(format!("オーバーレイが見つかりません: {}", id)).into());
+        }
+        self.outputs.update(output, |settings| {
+            if let Some(layer) = settings.overlays.iter_mut().find(|l| l.id == id) {
+                layer.visible = visible;
+            }
+        })
+    }
+
+    pub fn overlays(&self, output: &str) -> Result<Vec<OverlayLayer>> {
+        check_output(output)?;
+        Ok(self.outputs.get(output).overlays)
+    }
+
+    /// 出力の黒帯自動切り出しを設定する
+    ///
+    /// Locked で rect を省略した場合は、その時点で検出している矩形で固定する。
+    pub fn set_auto_crop(&self, output: &str, mut autocrop: AutoCropSettings) -> Result<()> {
+        check_output(output)?;
+        if autocrop.mode == CropMode::Locked && autocrop.rect.is_none() {
+            let status = self.crop_status(output)?;
+            autocrop.rect = status.applied.or(status.detected);
+            if autocrop.rect.is_none() {
+                return Err(AppError::InvalidArgument("固定できる検出結果がまだありません".to_string()).into());
+            }
+        }
+        autocrop
+            .validate()
+            .map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        log::info!("黒帯の自動切り出しを設定: {} = {:?}", output, autocrop);
+        self.outputs.update(output, |settings| settings.autocrop = autocrop)
+    }
+
+    pub fn auto_crop(&self, output: &str) -> Result<AutoCropSettings> {
+        check_output(output)?;
+        Ok(self.outputs.get(output).autocrop)
+    }
+
+    /// 出力の黒帯検出状況（検出は Syphon の出力スレッドで行う）
+    pub fn crop_status(&self, output: &str) -> Result<CropStatus> {
+        check_output(output)?;
+        let mode = self.outputs.get(output).autocrop.mode;
+        let inner = self.inner.lock()
+            .map_err(|e| anyhow::anyhow!("Mutex ロック失敗: {}", e))?;
+        Ok(match &inner.output_stats {
+            Some(stats) if output == "syphon" => stats.crop_status(mode),
+            _ => CropStatus { mode, detected: None, applied: None },
+        })
+    }
+}
+
+impl PlayerInner {
+    /// 映像出力が動いているか（再生していない間もスタンバイ映像を送っていれば true）
+    fn output_active(&self) -> bool {
+        #[cfg(target_os = "macos")]
+        {
+            self.syphon.as_ref().is_some_and(|syphon| !syphon.is_finished())
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            false
+        }
+    }
+
+    /// 出力中のデバイス（優先デバイスが抜けている間は既定のデバイス）
+    fn active_device(&self) -> &str {
+        if self.device_missing || self.device_fallback { "auto" } else { self.audio.get().device.as_str() }
+    }
+
+    /// 出力中のデバイスの遅延・チャンネルの割り当てと、出力の設定を反映する
+    fn apply_device_audio(&self, mpv: &MpvContext) -> Result<()> {
+        let settings = self.audio.get();
+        let audio = settings.device_audio(self.active_device());
+        mpv.apply_audio_output(&settings.output_for(audio.routing))?;
+        mpv.set_audio_delay(audio.delay)?;
+        self.apply_audio_filters(mpv)
+    }
+
+    /// メーターとチャンネルの割り当ての音声フィルタを反映する
+    fn apply_audio_filters(&self, mpv: &MpvContext) -> Result<()> {
+        let routing = self.audio.get().device_audio(self.active_device()).routing;
+        mpv.set_audio_filters(self.meter.as_ref().map(|meter| meter.tap_port()), routing)
+    }
+
+    /// ディスパッチャから届いたイベントを状態に反映する。ステータスが変化した場合は true を返す
+    fn apply_event(&mut self, event: &PlayerEvent) -> bool {
+        match event {
+            PlayerEvent::PropertyChange(change) => match *change {
+                PropertyChange::Pause(paused) => self.props.paused = paused,
+                PropertyChange::TimePos(pos) => {
+                    self.props.time_pos = pos;
+                    self.fire_cues(pos);
+                }
+                PropertyChange::Duration(dur) => self.props.duration = dur,
+                PropertyChange::Speed(speed) => self.props.speed = speed,
+                PropertyChange::Volume(volume) => self.props.volume = volume,
+                PropertyChange::PausedForCache(waiting) => self.props.paused_for_cache = waiting,
+                PropertyChange::CacheBufferingState(percent) => self.props.cache_percent = percent,
+                _ => {}
+            },
+            // 復旧時に設定した開始位置は読み込み後に解除する
+            // 字幕・音声トラックとチャプターは読み込み後に揃うので、ここで設定を反映し直す
+            PlayerEvent::FileLoaded => {
+                if let Some(mpv) = &self.mpv {
+                    if let Err(e) = mpv.clear_start() {
+                        log::warn!("start の解除に失敗: {}", e);
+                    }
+                    if let Err(e) = mpv.select_preferred_subtitle(&self.subtitles) {
+                        log::warn!("字幕の選択に失敗: {}", e);
+                    }
+                    if let Err(e) = mpv.select_preferred_audio(self.tracks.get()) {
+                        log::warn!("音声トラックの選択に失敗: {}", e);
+                    }
+                    if matches!(self.pending_loop, LoopMode::Chapter { .. }) {
+                        if let Err(e) = mpv.apply_loop(&self.pending_loop) {
+                            log::warn!("チャプターループの設定に失敗: {}", e);
+                        }
+                    }
+                }
+            }
+            // 再生開始時のフェードインは音声が出始めてから始める
+            PlayerEvent::PlaybackRestart if self.fade_in_pending => {
+                self.fade_in_pending = false;
+                let settings = &self.volume_settings;
+                self.volume_fade = Some(VolumeFade::new(0.0, self.pending_volume, settings.fade_in, settings.curve));
+            }
+            // シーク・読み込み直後の位置の飛びではキューを発火しない
+            PlayerEvent::Seek | PlayerEvent::StartFile => self.cue_pos = None,
+            PlayerEvent::Recovery { phase, attempt, reason, .. } => {
+                log::info!("自動復旧: {:?} (試行 {} 回, 原因 {})", phase, attempt, reason);
+            }
+            PlayerEvent::EndFile { error: Some(message), .. } => {
+                log::error!("mpv の再生が失敗しました: {}", message);
+            }
+            PlayerEvent::OutputError { output, message } => {
+                log::error!("出力 {} でエラー: {}", output, message);
+            }
+            _ => {}
+        }
+        self.status.on_event(event)
+    }
+
+    /// 直前の位置から pos までに通過したキューを実行する
+    fn fire_cues(&mut self, pos: f64) {
+        let Some(previous) = self.cue_pos.replace(pos) else { return };
+        let fired: Vec<Cue> = cues::crossed(&self.cues, previous, pos).cloned().collect();
+        for cue in fired {
+            self.run_cue(&cue);
+        }
+    }
+
+    fn run_cue(&mut self, cue: &Cue) {
+        log::info!("キュー {} を発火 ({:.2} 秒): {:?}", cue.id, cue.time, cue.action);
+        self.events.inject(PlayerEvent::CueFired { id: cue.id.clone(), time: cue.time });
+
+        let result = match &cue.action {
+            CueAction::Pause => self.mpv.as_ref().map_or(Ok(()), |mpv| mpv.set_pause(true)),
+            CueAction::Jump { time } => self
+                .mpv
+                .as_ref()
+                .map_or(Ok(()), |mpv| mpv.seek_with(&SeekRequest::exact(*time))),
+            CueAction::SwitchClip { url } => {
+                self.pending_clip = Some(url.clone());
+                Ok(())
+            }
+            // 名前解決で待たされることがあるため、ディスパッチャを止めないよう別スレッドで送る
+            CueAction::SendOsc { host, port, address, args } => {
+                let (host, port, address, args) = (host.clone(), *port, address.clone(), args.clone());
+                std::thread::spawn(move || {
+                    if let Err(e) = osc::send(&host, port, &address, &args) {
+                        log::warn!("{}", e);
+                    }
+                });
+                Ok(())
+            }
+            CueAction::FadeVolume { volume, duration, curve } => {
+                let volume = volume.min(self.volume_settings.max);
+                self.pending_volume = volume;
+                self.volume_fade = Some(VolumeFade::new(self.props.volume, volume, *duration, *curve));
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            log::warn!("キュー {} の実行に失敗: {}", cue.id, e);
+        }
+    }
+
+    /// 同じ mpv で別のクリップを読み込む（出力スレッドはそのまま使い続ける）
+    ///
+    /// `clip` を進め、ウォッチドッグが前のクリップの再生位置や復旧の状態を引き継がないようにする。
+    fn switch_clip(&mut self, url: &str) -> Result<()> {
+        let Some(mpv) = &self.mpv else { return Ok(()) };
+        mpv.reload_at(url, 0.0)?;
+        if self.pending_loop.is_section() {
+            self.pending_loop = LoopMode::Off;
+            mpv.apply_loop(&self.pending_loop)?;
+        }
+        self.current_url = Some(url.to_string());
+        self.cues = self.cue_store.get(url);
+        self.cue_pos = None;
+        self.clip += 1;
+        Ok(())
+    }
+
+    /// 進行中の音量フェードを now の時点まで進める
+    fn step_volume_fade(&mut self, now: std::time::Instant) {
+        let Some(fade) = &self.volume_fade else { return };
+        let (volume, done) = fade.step(now, self.props.volume);
+        if let (Some(volume), Some(mpv)) = (volume, &self.mpv) {
+            if let Err(e) = mpv.set_volume(volume) {
+                log::warn!("フェード中の音量設定に失敗: {}", e);
+            }
+        }
+        if done {
+            self.volume_fade = None;
+        }
+    }
+}
+
+/// ステータス変化を `player-status` イベントで UI に通知する
+fn emit_status(app_handle: Option<&tauri::AppHandle>, status: &PlayStatus) {
+    #[derive(Clone, serde::Serialize)]
+    struct StatusEvent {
+        status: StatusKind,
+        error: Option<AppError>,
+        buffering: Option<u8>,
+    }
+
+    let Some(app) = app_handle else { return };
+    let error = match status {
+        PlayStatus::Error(e) => Some(AppError::from(e)),
+        _ => None,
+    };
+    let buffering = match status {
+        PlayStatus::Buffering(percent) => Some(*percent),
+        _ => None,
+    };
+    let _ = app.emit("player-status", StatusEvent {
+        status: status.kind(),
+        error,
+        buffering,
+    });
+}
+
+/// オーディオデバイスの一覧の変化を `audio-devices-changed` イベントで UI に通知する
+fn emit_audio_devices(app: &tauri::AppHandle, devices: &[(String, String)]) {
+    #[derive(Clone, serde::Serialize)]
+    struct DeviceEvent<'a> {
+        id: &'a str,
+        name: &'a str,
+    }
+
+    let devices: Vec<DeviceEvent> = devices
+        .iter()
+        .map(|(id, name)| DeviceEvent { id, name })
+        .collect();
+    let _ = app.emit("audio-devices-changed", devices);
+}
+
+/// 優先デバイスの接続状況
+fn device_status(inner: &PlayerInner) -> AudioDeviceStatus {
+    let settings = inner.audio.get();
+    AudioDeviceStatus {
+        preferred: settings.device.clone(),
+        policy: settings.policy,
+        available: !inner.device_missing,
+    }
+}
+
+/// デバイスの一覧が変わったときに、優先デバイスの有無に応じて出力先を切り替える
+///
+/// 抜けたとき・戻ったときは `audio-device-status` イベントで UI に知らせる。
+fn apply_device_policy(inner: &Arc<Mutex<PlayerInner>>, app: &tauri::AppHandle, devices: &[(String, String)]) {
+    let mut inner = match inner.lock() {
+        Ok(guard) => guard,
+        Err(e) => {
+            log::error!("Mutex ロック失敗: {}", e);
+            return;
+        }
+    };
+    let preferred = inner.audio.get().device.clone();
+    let policy = inner.audio.get().policy;
+    let Some(change) = audio_settings::detect_change(&preferred, inner.device_missing, devices) else { return };
+
+    let action = audio_settings::device_action(&change, policy, inner.device_fallback);
+
+    match change {
+        DeviceChange::Lost => {
+            log::warn!("優先デバイスが抜けました ({:?}): {}", policy, preferred);
+            inner.device_missing = true;
+            // fallback は戻ってきても追わない（保存した優先デバイスは次回の起動・選び直しで使う）
+            inner.device_fallback = policy == DevicePolicy::Fallback;
+        }
+        DeviceChange::Returned => {
+            log::info!("優先デバイスが戻りました ({:?}): {}", policy, preferred);
+            inner.device_missing = false;
+        }
+    }
+    if let Some(mpv) = &inner.mpv {
+        let result = match action {
+            DeviceAction::UseDefault => inner.apply_device_audio(mpv).and_then(|_| mpv.set_audio_device("auto")),
+            // pause は一時停止のまま出力先だけ戻す（再開は手動）
+            DeviceAction::UsePreferred => inner.apply_device_audio(mpv).and_then(|_| mpv.set_audio_device(&preferred)),
+            DeviceAction::Pause => mpv.set_pause(true),
+            DeviceAction::Keep => Ok(()),
+        };
+        if let Err(e) = result {
+            log::warn!("出力先の切り替えに失敗 ({:?}): {}", action, e);
+        }
+    }
+    let _ = app.emit("audio-device-status", device_status(&inner));
+}
+
+/// 設定対象の出力名を確認する
+fn check_output(output: &str) -> Result<()> {
+    if !OUTPUT_NAMES.contains(&output) {
+        return Err(AppError::InvalidArgument(format!("未知の出力: {}", output)).into());
+    }
+    Ok(())
+}
+
+/// 再生できる URL かどうかを確認する
+///
+/// http(s) / ytdl:// の URL か、存在するローカルファイルのみ受け付ける。
+fn validate_url(url: &str) -> Result<()> {
+    let trimmed = url.trim();
+    let is_remote = ["http://", "https://", "ytdl://"]
+        .iter()
+        .any(|scheme| trimmed.starts_with(scheme) && trimmed.len() > scheme.len());
+    if is_remote || std::path::Path::new(trimmed).is_file() {
+        Ok(())
+    } else {
+        Err(AppError::InvalidUrl(url.to_string()).into())
+    }
 }
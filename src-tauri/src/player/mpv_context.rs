@@ -14,6 +14,12 @@
 use anyhow::Result;
 use libmpv2::Mpv;
 
+use super::audio::ChannelRouting;
+use super::looping::{self, Chapter, LoopMode};
+use super::seek::SeekRequest;
+use super::subtitles::{self, SubtitleMode, SubtitleSettings, SubtitleTrack};
+use super::tracks::{self, Track, TrackKind, TrackPreferences};
+
 /// libmpv2::Error は Rc を内包するため Send+Sync でない。
 /// map_err で文字列に変換して anyhow::Error に乗せるヘルパー。
 fn mpv_err(e: libmpv2::Error) -> anyhow::Error {
@@ -65,6 +71,18 @@ impl MpvContext {
         // 音声ピッチ補正を有効化（速度変更時に音程を保持）
         mpv.set_property("audio-pitch-correction", true).map_err(mpv_err)?;
 
+        // 音声レベルの計測用フィルタ（シェーダの u_audio_level などで使う）
+        mpv.set_property("af", super::meter::LEVEL_FILTER).map_err(mpv_err)?;
+
+        // イベントは events.rs の専用クライアントで受け取るため、メインハンドルでは購読しない
+        // （誰も読まないイベントキューが溢れるのを防ぐ。SHUTDOWN は無効化できない）
+        unsafe {
+            let handle = mpv.ctx.as_ptr();
+            for id in libmpv2_sys::mpv_event_id_MPV_EVENT_LOG_MESSAGE..=libmpv2_sys::mpv_event_id_MPV_EVENT_HOOK {
+                libmpv2_sys::mpv_request_event(handle, id, 0);
+            }
+        }
+
         // 注意: loadfile は RenderContext 作成後に Syphon スレッドで実行する
 
         Ok(Self { mpv })
@@ -101,6 +119,12 @@ impl MpvContext {
         Ok(!current)
     }
 
+    /// 一時停止の設定
+    pub fn set_pause(&self, paused: bool) -> Result<()> {
+        self.mpv.set_property("pause", paused).map_err(mpv_err)?;
+        Ok(())
+    }
+
     /// オーディオデバイス一覧を取得する
     /// 戻り値: (device_id, display_name) のリスト
     pub fn list_audio_devices(&self) -> Result<Vec<(String, String)>> {
@@ -174,15 +198,59 @@ impl MpvContext {
     }
 
     /// 出力オーディオデバイスを切り替える
+    ///
+    /// `jack` / `jack/<クライアント名>` は JACK への出力に切り替える（audio.rs を参照）。
     pub fn set_audio_device(&self, device_id: &str) -> Result<()> {
         let id = if device_id.is_empty() { "auto" } else { device_id };
+        let (ao, id) = match super::audio::parse_jack_device(id) {
+            Some(connect) => {
+                // ポート名は "クライアント名:ポート名"。クライアント名は正規表現として扱われるためエスケープする
+                let port = connect.map(|client| format!("^{}:", regex_escape(client)));
+                self.mpv.set_property("jack-connect", port.is_some()).map_err(mpv_err)?;
+                self.mpv.set_property("jack-port", port.unwrap_or_default()).map_err(mpv_err)?;
+                ("jack", "auto")
+            }
+            None => ("", id),
+        };
+
+        // 出力ドライバが変わる場合は音声出力を作り直す（読み込み前は次の読み込みで反映される）
+        let current: String = self.mpv.get_property("ao").unwrap_or_default();
+        self.mpv.set_property("ao", ao).map_err(mpv_err)?;
         self.mpv.set_property("audio-device", id).map_err(mpv_err)?;
+        if current != ao {
+            self.reload_audio_output()?;
+        }
+        Ok(())
+    }
+
+    /// 音声出力を作り直す（名前・チャンネル数・出力ドライバの変更を反映する）
+    pub fn reload_audio_output(&self) -> Result<()> {
+        // 音声の読み込み前は失敗するが、その場合は読み込み時に反映される
+        if let Err(e) = self.mpv.command("ao-reload", &[]) {
+            log::debug!("ao-reload に失敗（音声の読み込み前）: {:?}", e);
+        }
+        Ok(())
+    }
+
+    /// JACK / PipeWire に見せる名前とチャンネル数を設定する
+    pub fn apply_audio_output(&self, settings: &super::audio::AudioOutputSettings) -> Result<()> {
+        self.mpv.set_property("audio-client-name", settings.client_name.as_str()).map_err(mpv_err)?;
+        self.mpv.set_property("jack-name", settings.client_name.as_str()).map_err(mpv_err)?;
+        // 標準の配置に無いチャンネル数（16ch など）でもポートを作る
+        self.mpv.set_property("jack-std-channel-layout", "any").map_err(mpv_err)?;
+        self.mpv.set_property("audio-channels", settings.mpv_channels()).map_err(mpv_err)?;
+        Ok(())
+    }
+
+    /// ボリューム設定（%。100 を超えると volume-max まで増幅する）
+    pub fn set_volume(&self, volume: f64) -> Result<()> {
+        self.mpv.set_property("volume", volume).map_err(mpv_err)?;
         Ok(())
     }
 
-    /// ボリューム設定（0–100）
-    pub fn set_volume(&self, volume: u8) -> Result<()> {
-        self.mpv.set_property("volume", volume as i64).map_err(mpv_err)?;
+    /// ボリュームの上限を設定する（%）
+    pub fn set_volume_max(&self, max: f64) -> Result<()> {
+        self.mpv.set_property("volume-max", max).map_err(mpv_err)?;
         Ok(())
     }
 
@@ -203,22 +271,129 @@ impl MpvContext {
         }
     }
 
-    /// ループ再生の設定
-    pub fn set_loop(&self, enabled: bool) -> Result<()> {
-        let value = if enabled { "inf" } else { "no" };
-        self.mpv.set_property("loop-file", value).map_err(mpv_err)?;
+    /// ループ再生の設定を反映する
+    ///
+    /// チャプターのループはチャプター一覧が揃う FILE_LOADED の後に呼び直す。
+    pub fn apply_loop(&self, mode: &LoopMode) -> Result<()> {
+        let loop_file = if *mode == LoopMode::File { "inf" } else { "no" };
+        self.mpv.set_property("loop-file", loop_file).map_err(mpv_err)?;
+
+        let chapters = match mode {
+            LoopMode::Chapter { .. } => self.chapters().unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        let (a, b) = match mode.ab_range(&chapters) {
+            Some((a, b)) => (a.to_string(), b.map_or("no".to_string(), |b| b.to_string())),
+            None => ("no".to_string(), "no".to_string()),
+        };
+        self.mpv.set_property("ab-loop-a", a).map_err(mpv_err)?;
+        self.mpv.set_property("ab-loop-b", b).map_err(mpv_err)?;
+        Ok(())
+    }
+
+    /// チャプターの一覧を取得
+    pub fn chapters(&self) -> Result<Vec<Chapter>> {
+        let chapter_list: String = self.mpv.get_property("chapter-list").map_err(mpv_err)?;
+        looping::parse_chapter_list(&chapter_list, self.get_duration()?)
+    }
+
+    /// チャプターの先頭へ移動する
+    pub fn set_chapter(&self, index: i64) -> Result<()> {
+        self.mpv.set_property("chapter", index).map_err(mpv_err)?;
         Ok(())
     }
 
-    /// ループ再生の状態を取得
-    pub fn get_loop(&self) -> Result<bool> {
-        match self.mpv.get_property::<String>("loop-file") {
-            Ok(value) => Ok(value == "inf"),
-            Err(e) => {
-                log::warn!("loop-file 取得失敗: {:?}", e);
-                Ok(false)
+    /// 字幕の取得・表示設定を反映する
+    ///
+    /// 取得する言語（ytdl-raw-options）は次の読み込みから有効になる。
+    pub fn apply_subtitles(&self, settings: &SubtitleSettings) -> Result<()> {
+        self.mpv.set_property("ytdl-raw-options", settings.ytdl_raw_options()).map_err(mpv_err)?;
+        if let Some(language) = &settings.language {
+            self.mpv.set_property("slang", format!("{},{}-orig", language, language)).map_err(mpv_err)?;
+        }
+
+        // 焼き込み以外では mpv には描かせない（PreviewOnly はプレビュー側で重ねる）
+        let burn_in = settings.mode == SubtitleMode::BurnIn;
+        self.mpv.set_property("sub-visibility", burn_in).map_err(mpv_err)?;
+
+        let style = &settings.style;
+        self.mpv.set_property("sub-font-size", style.font_size as f64).map_err(mpv_err)?;
+        self.mpv.set_property("sub-pos", style.position as i64).map_err(mpv_err)?;
+        self.mpv.set_property("sub-color", subtitles::mpv_color(style.color)).map_err(mpv_err)?;
+        match style.background {
+            Some(background) => {
+                self.mpv.set_property("sub-border-style", "opaque-box").map_err(mpv_err)?;
+                self.mpv.set_property("sub-back-color", subtitles::mpv_color(background)).map_err(mpv_err)?;
+            }
+            None => {
+                self.mpv.set_property("sub-border-style", "outline-and-shadow").map_err(mpv_err)?;
+            }
+        }
+
+        // 取得済みのトラックがあればすぐ切り替える
+        self.select_preferred_subtitle(settings)
+    }
+
+    /// 設定の言語に合う字幕トラックを選択する（FILE_LOADED でトラックが揃った後にも呼ぶ）
+    pub fn select_preferred_subtitle(&self, settings: &SubtitleSettings) -> Result<()> {
+        if settings.mode == SubtitleMode::Off {
+            return self.select_subtitle(None);
+        }
+        if let Ok(tracks) = self.subtitle_tracks() {
+            if let Some(track) = subtitles::find_track(&tracks, settings.language.as_deref()) {
+                self.select_subtitle(Some(track.id))?;
             }
         }
+        Ok(())
+    }
+
+    /// 字幕トラックの一覧を取得
+    pub fn subtitle_tracks(&self) -> Result<Vec<SubtitleTrack>> {
+        let track_list: String = self.mpv.get_property("track-list").map_err(mpv_err)?;
+        subtitles::parse_tracks(&track_list)
+    }
+
+    /// 字幕トラックを選択する（None で字幕なし）
+    pub fn select_subtitle(&self, id: Option<i64>) -> Result<()> {
+        self.select_track(TrackKind::Sub, id)
+    }
+
+    /// 音声・映像・字幕トラックの一覧を取得
+    pub fn tracks(&self) -> Result<Vec<Track>> {
+        let track_list: String = self.mpv.get_property("track-list").map_err(mpv_err)?;
+        tracks::parse_track_list(&track_list)
+    }
+
+    /// トラックを選択する（None で選択を外す）
+    pub fn select_track(&self, kind: TrackKind, id: Option<i64>) -> Result<()> {
+        let property = kind.mpv_property();
+        match id {
+            Some(id) => self.mpv.set_property(property, id).map_err(mpv_err)?,
+            None => self.mpv.set_property(property, "no").map_err(mpv_err)?,
+        }
+        Ok(())
+    }
+
+    /// 音声の優先言語を反映する
+    ///
+    /// alang は読み込み時のトラック選択に使われるため、再生中は合うトラックを直接選ぶ。
+    pub fn apply_track_preferences(&self, preferences: &TrackPreferences) -> Result<()> {
+        if let Some(language) = &preferences.audio_language {
+            self.mpv.set_property("alang", language.as_str()).map_err(mpv_err)?;
+        }
+        self.select_preferred_audio(preferences)
+    }
+
+    /// 優先言語に合う音声トラックを選択する（FILE_LOADED でトラックが揃った後にも呼ぶ）
+    pub fn select_preferred_audio(&self, preferences: &TrackPreferences) -> Result<()> {
+        if let Ok(tracks) = self.tracks() {
+            if let Some(track) = preferences.preferred_audio(&tracks) {
+                if !track.selected {
+                    self.select_track(TrackKind::Audio, Some(track.id))?;
+                }
+            }
+        }
+        Ok(())
     }
 
     /// シーク（秒単位）
@@ -227,6 +402,70 @@ impl MpvContext {
         Ok(())
     }
 
+    /// 基準・精度を指定してシークする
+    pub fn seek_with(&self, request: &SeekRequest) -> Result<()> {
+        self.mpv
+            .command("seek", &[&request.value.to_string(), &request.mpv_flags()])
+            .map_err(mpv_err)?;
+        Ok(())
+    }
+
+    /// 1 フレーム進める（forward = false で 1 フレーム戻す）。移動後は一時停止する
+    pub fn frame_step(&self, forward: bool) -> Result<()> {
+        let command = if forward { "frame-step" } else { "frame-back-step" };
+        self.mpv.command(command, &[]).map_err(mpv_err)?;
+        Ok(())
+    }
+
+    /// 選択中の映像トラックのフレームレート（トラック情報に無ければ container-fps）
+    pub fn video_fps(&self) -> Result<f64> {
+        let from_track = self
+            .tracks()?
+            .into_iter()
+            .find(|track| track.kind == TrackKind::Video && track.selected)
+            .and_then(|track| track.fps);
+        match from_track {
+            Some(fps) => Ok(fps),
+            None => self.mpv.get_property("container-fps").map_err(mpv_err),
+        }
+    }
+
+    /// URL を読み込み直して指定位置から再開する（自動復旧用）
+    /// ytdl_hook が yt-dlp で URL を再解決するため、期限切れのストリーム URL も更新される
+    pub fn reload_at(&self, url: &str, start: f64) -> Result<()> {
+        if start > 0.0 {
+            self.mpv.set_property("start", format!("{:.3}", start)).map_err(mpv_err)?;
+        }
+        self.mpv.command("loadfile", &[url, "replace"]).map_err(mpv_err)?;
+        Ok(())
+    }
+
+    /// 音声フィルタを設定する
+    ///
+    /// メーターのフィルタ（tap_port が None なら RMS だけを測る既定のフィルタ）の後に
+    /// チャンネルの割り当てを置き、メーターは割り当て前の音声を測る。
+    pub fn set_audio_filters(&self, tap_port: Option<u16>, routing: ChannelRouting) -> Result<()> {
+        let mut filters = super::meter::filter(tap_port);
+        if let Some(route) = routing.filter() {
+            filters.push(',');
+            filters.push_str(route);
+        }
+        self.mpv.set_property("af", filters).map_err(mpv_err)?;
+        Ok(())
+    }
+
+    /// 音声の遅延を設定する（秒。正の値で映像より遅らせる）
+    pub fn set_audio_delay(&self, seconds: f64) -> Result<()> {
+        self.mpv.set_property("audio-delay", seconds).map_err(mpv_err)?;
+        Ok(())
+    }
+
+    /// reload_at で設定した開始位置を解除する（次のループ・再読み込みに影響させない）
+    pub fn clear_start(&self) -> Result<()> {
+        self.mpv.set_property("start", "none").map_err(mpv_err)?;
+        Ok(())
+    }
+
     /// 再生位置を取得（秒）
     pub fn get_time_pos(&self) -> Result<f64> {
         match self.mpv.get_property("time-pos") {
@@ -264,6 +503,12 @@ impl MpvContext {
         Ok(())
     }
 
+    /// 同期のための速度の微調整（頻繁に呼ぶためログを出さない）
+    pub fn nudge_speed(&self, speed: f64) -> Result<()> {
+        self.mpv.set_property("speed", speed.clamp(0.25, 4.0)).map_err(mpv_err)?;
+        Ok(())
+    }
+
     /// 再生速度を取得
     pub fn get_speed(&self) -> Result<f64> {
         match self.mpv.get_property("speed") {
@@ -294,3 +539,15 @@ impl MpvContext {
         }
     }
 }
+
+/// 正規表現の特殊文字をエスケープする
+fn regex_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,715 @@
+/// 複数台の同期再生（マスター / フォロワー）
+///
+/// 同じクリップを複数のマシンで再生し、別々のプロジェクターに出すための同期。
+///
+/// ## プロトコル
+/// マスターは `SEND_INTERVAL` ごとに次の JSON を UDP で送る（ブロードキャスト・ユニキャストのどちらでもよい）。
+///
+/// ```json
+/// {"v": 1, "group": "default", "clip": "https://...", "pos": 12.345, "paused": false, "speed": 1.0, "sent": 1760000000.123}
+/// ```
+///
+/// - `pos`: `sent` の時点の再生位置（秒）
+/// - `sent`: 送信時刻（UNIX 時刻の秒。壁時計の基準）
+///
+/// フォロワーは `pos + (現在時刻 - sent) × speed` を目標位置とし、チェイスと同じ
+/// `DriftCorrector`（drift.rs）で、大きくずれたらシーク、それ以内なら再生速度の微調整で追従する。
+/// 壁時計の差がそのまま同期の誤差になるため、各マシンの時計は NTP などで合わせておく。
+///
+/// ## 1 台での確認
+/// 2 つのプロセスを起動し、マスターの送信先を `127.0.0.1:20810`、フォロワーのポートを 20810 にする。
+/// フォロワーを複数起動する場合はポートを分け、マスターの送信先に並べる。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::drift::{Correction, DriftAction, DriftCorrector, TICK};
+use super::events::{EventBus, PlayerEvent};
+use super::PlayerInner;
+
+/// 既定のポート（テンポ受信の 20809 の隣）
+pub const DEFAULT_PORT: u16 = 20810;
+/// パケットの形式のバージョン
+const PROTOCOL_VERSION: u32 = 1;
+/// マスターの送信間隔
+const SEND_INTERVAL: Duration = Duration::from_millis(50);
+/// この時間マスターから届かなければ Waiting とする
+const MASTER_TIMEOUT: Duration = Duration::from_secs(1);
+/// クリップを切り替えた後、読み込みを待つ時間
+const SWITCH_SETTLE: Duration = Duration::from_secs(2);
+/// 一時停止中に位置を合わせ直す差（秒）
+const PAUSED_TOLERANCE: f64 = 0.04;
+/// Locked とみなす差（秒）
+const LOCK_TOLERANCE: f64 = 1.0 / 60.0;
+
+/// 同期の設定（役割ごと）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "role", rename_all = "kebab-case")]
+pub enum NetSyncSettings {
+    /// 再生位置を送る
+    Master {
+        /// 送信先（"192.168.1.255:20810" など。既定はブロードキャスト）
+        #[serde(default = "default_targets")]
+        targets: Vec<String>,
+        #[serde(default = "default_group")]
+        group: String,
+    },
+    /// マスターに追従する
+    Follower {
+        #[serde(default = "default_port")]
+        port: u16,
+        #[serde(default = "default_group")]
+        group: String,
+        /// 動画の位置 = マスターの位置 + offset（秒。負の値も可）
+        #[serde(default)]
+        offset: f64,
+        /// これ以上ずれたらシークする（秒）
+        #[serde(default = "default_seek_threshold")]
+        seek_threshold: f64,
+        /// 速度補正の上限（0.05 = ±5%）
+        #[serde(default = "default_max_nudge")]
+        max_nudge: f64,
+        /// マスターのクリップが変わったら同じクリップに切り替える
+        #[serde(default = "default_follow_clip")]
+        follow_clip: bool,
+    },
+}
+
+fn default_targets() -> Vec<String> {
+    vec![format!("255.255.255.255:{}", DEFAULT_PORT)]
+}
+
+fn default_group() -> String {
+    "default".to_string()
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+fn default_seek_threshold() -> f64 {
+    0.5
+}
+
+fn default_max_nudge() -> f64 {
+    0.05
+}
+
+fn default_follow_clip() -> bool {
+    true
+}
+
+impl NetSyncSettings {
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Master { targets, group } => {
+                validate_group(group)?;
+                if targets.is_empty() {
+                    return Err(anyhow::anyhow!("送信先を指定してください"));
+                }
+                for target in targets {
+                    target
+                        .parse::<SocketAddr>()
+                        .map_err(|_| anyhow::anyhow!("送信先は IP アドレス:ポートで指定してください: {}", target))?;
+                }
+            }
+            Self::Follower { group, offset, seek_threshold, max_nudge, .. } => {
+                validate_group(group)?;
+                if !offset.is_finite() {
+                    return Err(anyhow::anyhow!("offset が不正です: {}", offset));
+                }
+                if !(0.1..=10.0).contains(seek_threshold) {
+                    return Err(anyhow::anyhow!("seek_threshold は 0.1–10 秒で指定してください: {}", seek_threshold));
+                }
+                if !(0.0..=0.25).contains(max_nudge) {
+                    return Err(anyhow::anyhow!("max_nudge は 0.0–0.25 で指定してください: {}", max_nudge));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 再生速度・一時停止を操作する役割か
+    pub fn is_follower(&self) -> bool {
+        matches!(self, Self::Follower { .. })
+    }
+}
+
+fn validate_group(group: &str) -> Result<()> {
+    if group.is_empty() || group.len() > 64 {
+        return Err(anyhow::anyhow!("group は 1–64 文字で指定してください"));
+    }
+    Ok(())
+}
+
+/// 同期の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NetSyncState {
+    #[default]
+    Off,
+    /// マスターとして送信している
+    Master,
+    /// マスターを待っている（未受信・途切れた）
+    Waiting,
+    /// マスターのクリップに切り替えている
+    Switching,
+    /// 大きくずれたためシークした
+    Seeking,
+    /// 速度を調整して差を縮めている
+    Syncing,
+    /// 一時停止したマスターに合わせて止まっている
+    Paused,
+    /// マスターとの差が 1 フレーム以内
+    Locked,
+}
+
+/// UI に返す同期の状況
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NetSyncStatus {
+    pub state: NetSyncState,
+    /// マスターのクリップ
+    pub clip: Option<String>,
+    /// 再生位置 - マスターの位置（秒。フォロワーのみ）
+    pub sync_error: Option<f64>,
+    /// 送信から受信までの時間（秒。時計のずれを含む）
+    pub latency: Option<f64>,
+    /// 同期が設定している再生速度
+    pub speed: f64,
+}
+
+/// 送受信するパケット
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SyncPacket {
+    v: u32,
+    group: String,
+    clip: String,
+    pos: f64,
+    paused: bool,
+    speed: f64,
+    sent: f64,
+}
+
+/// 受信したマスターの状態
+#[derive(Debug, Clone)]
+struct MasterReport {
+    packet: SyncPacket,
+    received: Instant,
+    latency: f64,
+}
+
+/// 現在の UNIX 時刻（秒）
+fn wall_clock() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// 1 パケットを解析する（壊れたパケット・別のグループ・別のバージョンは None）
+fn parse_packet(data: &[u8], group: &str) -> Option<SyncPacket> {
+    let packet: SyncPacket = serde_json::from_slice(data).ok()?;
+    let valid = packet.v == PROTOCOL_VERSION
+        && packet.group == group
+        && packet.pos.is_finite()
+        && packet.speed.is_finite()
+        && packet.sent.is_finite();
+    valid.then_some(packet)
+}
+
+// ─── フォロワーの判定 ────────────────────────────────────────────────────────
+
+/// 1 回の判定で行う操作
+#[derive(Debug, Clone, Default, PartialEq)]
+struct FollowAction {
+    /// 切り替えるクリップ
+    clip: Option<String>,
+    /// シーク・一時停止・速度の操作
+    drift: DriftAction,
+}
+
+/// マスターの状態と再生位置から操作を決める
+struct Follower {
+    offset: f64,
+    follow_clip: bool,
+    master: Option<MasterReport>,
+    /// 切り替えを指示したクリップ（同じクリップを読み込み直さないため）
+    requested_clip: Option<String>,
+    corrector: DriftCorrector,
+    sync_error: Option<f64>,
+    state: NetSyncState,
+}
+
+impl Follower {
+    fn new(offset: f64, seek_threshold: f64, max_nudge: f64, follow_clip: bool) -> Self {
+        Self {
+            offset,
+            follow_clip,
+            master: None,
+            requested_clip: None,
+            corrector: DriftCorrector::new(seek_threshold, max_nudge),
+            sync_error: None,
+            state: NetSyncState::Waiting,
+        }
+    }
+
+    fn on_packet(&mut self, packet: SyncPacket, received: Instant, received_wall: f64) {
+        let latency = received_wall - packet.sent;
+        self.master = Some(MasterReport { packet, received, latency });
+    }
+
+    /// wall 時点のマスターの位置
+    fn target(&self, wall: f64) -> Option<f64> {
+        let packet = &self.master.as_ref()?.packet;
+        let elapsed = if packet.paused { 0.0 } else { (wall - packet.sent).max(0.0) * packet.speed };
+        Some((packet.pos + elapsed + self.offset).max(0.0))
+    }
+
+    fn update(&mut self, now: Instant, wall: f64, current_clip: Option<&str>, position: f64, paused: bool) -> FollowAction {
+        let mut action = FollowAction::default();
+        let master = self.master.as_ref().filter(|m| now.duration_since(m.received) < MASTER_TIMEOUT);
+        let (Some(master), Some(target)) = (master, self.target(wall)) else {
+            // 途切れてもそのまま再生を続ける
+            self.state = NetSyncState::Waiting;
+            self.sync_error = None;
+            self.corrector.set_speed(&mut action.drift, 1.0);
+            return action;
+        };
+        let packet = &master.packet;
+
+        if self.follow_clip && current_clip != Some(packet.clip.as_str()) {
+            if self.requested_clip.as_deref() != Some(packet.clip.as_str()) {
+                log::info!("マスターのクリップに切り替えます: {}", packet.clip);
+                self.requested_clip = Some(packet.clip.clone());
+                self.corrector.settle(now, SWITCH_SETTLE);
+                action.clip = Some(packet.clip.clone());
+            }
+            self.state = NetSyncState::Switching;
+            self.sync_error = None;
+            return action;
+        }
+        self.requested_clip = None;
+
+        let error = position - target;
+        self.sync_error = Some(error);
+        let master_speed = packet.speed;
+        let drift = &mut action.drift;
+
+        if packet.paused {
+            self.state = NetSyncState::Paused;
+            if !paused {
+                drift.pause = Some(true);
+            }
+            if error.abs() > PAUSED_TOLERANCE && !self.corrector.settling(now) {
+                self.corrector.seek(drift, target, now);
+            }
+            self.corrector.set_speed(drift, master_speed);
+            return action;
+        }
+
+        if paused {
+            drift.pause = Some(false);
+        }
+        match self.corrector.correct(drift, now, error, target, master_speed) {
+            Correction::Settling => {}
+            Correction::Seeked => {
+                log::info!("マスターとの差が {:.3} 秒のためシークします", error);
+                self.state = NetSyncState::Seeking;
+            }
+            Correction::Nudged => {
+                self.state = if error.abs() <= LOCK_TOLERANCE { NetSyncState::Locked } else { NetSyncState::Syncing };
+            }
+        }
+        action
+    }
+
+    fn status(&self) -> NetSyncStatus {
+        NetSyncStatus {
+            state: self.state,
+            clip: self.master.as_ref().map(|m| m.packet.clip.clone()),
+            sync_error: self.sync_error,
+            latency: self.master.as_ref().map(|m| m.latency),
+            speed: self.corrector.speed(),
+        }
+    }
+}
+
+// ─── 同期スレッド ────────────────────────────────────────────────────────────
+
+/// 動作中の同期
+pub struct NetSyncHandle {
+    stop: Arc<AtomicBool>,
+    status: Arc<Mutex<NetSyncStatus>>,
+    follower: bool,
+}
+
+impl NetSyncHandle {
+    /// 送受信のスレッドを止める（フォロワーは再生速度が 1.0 に戻る）
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn status(&self) -> NetSyncStatus {
+        self.status.lock().map(|status| status.clone()).unwrap_or_default()
+    }
+
+    /// 再生速度・一時停止を操作しているか（チェイス・ビート同期と同時に動かさない）
+    pub fn is_follower(&self) -> bool {
+        self.follower
+    }
+}
+
+/// 役割に応じて送信またはフォロワーのスレッドを起動する
+///
+/// 再生セッションをまたいで動き続ける（mpv が無い間は何もしない）。
+pub(super) fn spawn(inner: Arc<Mutex<PlayerInner>>, settings: NetSyncSettings, bus: EventBus) -> Result<NetSyncHandle> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let status = Arc::new(Mutex::new(NetSyncStatus { speed: 1.0, ..Default::default() }));
+    let handle = NetSyncHandle { stop: stop.clone(), status: status.clone(), follower: settings.is_follower() };
+
+    match settings {
+        NetSyncSettings::Master { targets, group } => {
+            let targets: Vec<SocketAddr> = targets.iter().filter_map(|t| t.parse().ok()).collect();
+            let socket = UdpSocket::bind(("0.0.0.0", 0))
+                .map_err(|e| anyhow::anyhow!("同期の送信ソケットを開けません: {}", e))?;
+            socket.set_broadcast(true)?;
+            spawn_master(inner, socket, targets, group, stop, status, bus);
+        }
+        NetSyncSettings::Follower { port, group, offset, seek_threshold, max_nudge, follow_clip } => {
+            let (tx, rx) = std::sync::mpsc::channel();
+            spawn_receiver(port, group, tx, stop.clone())?;
+            let follower = Follower::new(offset, seek_threshold, max_nudge, follow_clip);
+            spawn_follower(inner, follower, rx, stop, status, bus);
+        }
+    }
+    Ok(handle)
+}
+
+fn spawn_master(
+    inner: Arc<Mutex<PlayerInner>>,
+    socket: UdpSocket,
+    targets: Vec<SocketAddr>,
+    group: String,
+    stop: Arc<AtomicBool>,
+    status: Arc<Mutex<NetSyncStatus>>,
+    bus: EventBus,
+) {
+    std::thread::spawn(move || {
+        log::info!("同期のマスターを開始しました: {:?} (group={})", targets, group);
+        bus.inject(PlayerEvent::NetSync { state: NetSyncState::Master });
+
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(SEND_INTERVAL);
+            let packet = {
+                let Ok(guard) = inner.lock() else { break };
+                let (Some(mpv), Some(clip)) = (&guard.mpv, &guard.current_url) else { continue };
+                SyncPacket {
+                    v: PROTOCOL_VERSION,
+                    group: group.clone(),
+                    clip: clip.clone(),
+                    pos: mpv.get_time_pos().unwrap_or(0.0),
+                    paused: guard.props.paused,
+                    speed: mpv.get_speed().unwrap_or(1.0),
+                    sent: wall_clock(),
+                }
+            };
+            send_packet(&socket, &targets, &packet);
+            if let Ok(mut status) = status.lock() {
+                *status = NetSyncStatus {
+                    state: NetSyncState::Master,
+                    clip: Some(packet.clip),
+                    speed: packet.speed,
+                    ..Default::default()
+                };
+            }
+        }
+
+        if let Ok(mut status) = status.lock() {
+            *status = NetSyncStatus { speed: 1.0, ..Default::default() };
+        }
+        bus.inject(PlayerEvent::NetSync { state: NetSyncState::Off });
+        log::info!("同期のマスターを終了しました");
+    });
+}
+
+fn spawn_follower(
+    inner: Arc<Mutex<PlayerInner>>,
+    mut follower: Follower,
+    rx: std::sync::mpsc::Receiver<(SyncPacket, Instant, f64)>,
+    stop: Arc<AtomicBool>,
+    status: Arc<Mutex<NetSyncStatus>>,
+    bus: EventBus,
+) {
+    std::thread::spawn(move || {
+        log::info!("同期のフォロワーを開始しました");
+        bus.inject(PlayerEvent::NetSync { state: NetSyncState::Waiting });
+
+        while !stop.load(Ordering::Relaxed) {
+            match rx.recv_timeout(TICK) {
+                Ok(received) => {
+                    // 溜まっている分は最新の値だけ使う
+                    let (packet, at, wall) = rx.try_iter().last().unwrap_or(received);
+                    follower.on_packet(packet, at, wall);
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            let Ok(mut guard) = inner.lock() else { break };
+            let Some(mpv) = &guard.mpv else { continue };
+            let position = mpv.get_time_pos().unwrap_or(0.0);
+            let previous = follower.state;
+            let action = follower.update(
+                Instant::now(),
+                wall_clock(),
+                guard.current_url.as_deref(),
+                position,
+                guard.props.paused,
+            );
+
+            if let Some(clip) = &action.clip {
+                if let Err(e) = guard.switch_clip(clip) {
+                    log::warn!("マスターのクリップに切り替えられません: {}", e);
+                }
+            }
+            if let Some(mpv) = &guard.mpv {
+                if let Err(e) = action.drift.apply(mpv) {
+                    log::warn!("同期の操作に失敗: {}", e);
+                }
+            }
+            drop(guard);
+
+            if let Ok(mut status) = status.lock() {
+                *status = follower.status();
+            }
+            if follower.state != previous {
+                log::info!("同期の状態: {:?} → {:?}", previous, follower.state);
+                bus.inject(PlayerEvent::NetSync { state: follower.state });
+            }
+        }
+
+        // 速度補正を戻す
+        if let Ok(guard) = inner.lock() {
+            if let Some(mpv) = &guard.mpv {
+                let _ = mpv.nudge_speed(1.0);
+            }
+        }
+        if let Ok(mut status) = status.lock() {
+            *status = NetSyncStatus { speed: 1.0, ..Default::default() };
+        }
+        bus.inject(PlayerEvent::NetSync { state: NetSyncState::Off });
+        log::info!("同期のフォロワーを終了しました");
+    });
+}
+
+/// パケットをすべての送信先に送る（届かない送信先があっても続ける）
+fn send_packet(socket: &UdpSocket, targets: &[SocketAddr], packet: &SyncPacket) {
+    let Ok(data) = serde_json::to_vec(packet) else { return };
+    for target in targets {
+        if let Err(e) = socket.send_to(&data, target) {
+            log::debug!("同期パケットの送信に失敗 ({}): {}", target, e);
+        }
+    }
+}
+
+/// port で受信し、同じグループのパケットを tx に送るスレッドを起動する
+fn spawn_receiver(
+    port: u16,
+    group: String,
+    tx: Sender<(SyncPacket, Instant, f64)>,
+    stop: Arc<AtomicBool>,
+) -> Result<()> {
+    let socket = bind(port)?;
+    // stop フラグを確認するために定期的に受信を抜ける
+    socket.set_read_timeout(Some(Duration::from_millis(200)))?;
+
+    std::thread::spawn(move || {
+        log::info!("同期パケットの受信を開始しました (port={})", port);
+        let mut buffer = [0u8; 4096];
+        while !stop.load(Ordering::Relaxed) {
+            let Ok((len, _)) = socket.recv_from(&mut buffer) else { continue };
+            // 受信した時刻は解析の前に取る
+            let (at, wall) = (Instant::now(), wall_clock());
+            let Some(packet) = parse_packet(&buffer[..len], &group) else { continue };
+            if tx.send((packet, at, wall)).is_err() {
+                break;
+            }
+        }
+        log::info!("同期パケットの受信を終了しました");
+    });
+    Ok(())
+}
+
+/// 止めた直前の受信スレッドがポートを閉じるまで（読み取りのタイムアウト分）待って開き直す
+fn bind(port: u16) -> Result<UdpSocket> {
+    let mut attempts = 0;
+    loop {
+        match UdpSocket::bind(("0.0.0.0", port)) {
+            Ok(socket) => return Ok(socket),
+            Err(e) if attempts < 5 => {
+                log::debug!("同期の受信ポート {} を開き直します: {}", port, e);
+                attempts += 1;
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            Err(e) => return Err(anyhow::anyhow!("同期の受信ポート {} を開けません: {}", port, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(pos: f64, paused: bool, sent: f64) -> SyncPacket {
+        SyncPacket {
+            v: PROTOCOL_VERSION,
+            group: "default".to_string(),
+            clip: "https://example.com/clip".to_string(),
+            pos,
+            paused,
+            speed: 1.0,
+            sent,
+        }
+    }
+
+    fn follower() -> Follower {
+        Follower::new(0.0, default_seek_threshold(), default_max_nudge(), true)
+    }
+
+    /// now の時点で pos にいるマスターを受信したフォロワー
+    fn following(pos: f64, paused: bool, now: Instant, wall: f64) -> Follower {
+        let mut follower = follower();
+        follower.on_packet(packet(pos, paused, wall), now, wall);
+        follower
+    }
+
+    const CLIP: Option<&str> = Some("https://example.com/clip");
+
+    #[test]
+    fn parses_packets_of_the_same_group_and_version() {
+        let data = serde_json::to_vec(&packet(12.5, false, 100.0)).unwrap();
+        assert_eq!(parse_packet(&data, "default"), Some(packet(12.5, false, 100.0)));
+        assert_eq!(parse_packet(&data, "stage-left"), None);
+
+        let old = serde_json::to_vec(&SyncPacket { v: PROTOCOL_VERSION + 1, ..packet(12.5, false, 100.0) }).unwrap();
+        assert_eq!(parse_packet(&old, "default"), None);
+        assert_eq!(parse_packet(br#"{"v":1,"group":"default"}"#, "default"), None);
+        assert_eq!(parse_packet(b"not json", "default"), None);
+    }
+
+    #[test]
+    fn target_advances_with_wall_clock_unless_paused() {
+        let now = Instant::now();
+        let follower = following(10.0, false, now, 100.0);
+        assert!((follower.target(100.5).unwrap() - 10.5).abs() < 1e-9);
+        let follower = following(10.0, true, now, 100.0);
+        assert_eq!(follower.target(100.5), Some(10.0));
+    }
+
+    #[test]
+    fn seeks_past_the_threshold_and_waits_for_settle() {
+        let now = Instant::now();
+        let mut follower = following(10.0, false, now, 100.0);
+        let action = follower.update(now, 100.0, CLIP, 10.0 + default_seek_threshold() + 0.1, false);
+        assert_eq!(follower.state, NetSyncState::Seeking);
+        assert_eq!(action.drift.seek, Some(10.0));
+
+        // 位置が落ち着くまではずれていても何もしない
+        let later = now + Duration::from_millis(100);
+        let action = follower.update(later, 100.1, CLIP, 12.0, false);
+        assert_eq!(action, FollowAction::default());
+        assert_eq!(follower.state, NetSyncState::Seeking);
+
+        // 落ち着いた後は再び判定する
+        let settled = now + Duration::from_millis(500);
+        follower.on_packet(packet(10.5, false, 100.5), settled, 100.5);
+        follower.update(settled, 100.5, CLIP, 10.5, false);
+        assert_eq!(follower.state, NetSyncState::Locked);
+    }
+
+    #[test]
+    fn nudges_speed_within_max_nudge() {
+        let now = Instant::now();
+        // 閾値ちょうどまではシークせず、上限の速度で詰める
+        let mut follower = following(10.0, false, now, 100.0);
+        let action = follower.update(now, 100.0, CLIP, 10.0 + default_seek_threshold(), false);
+        assert_eq!(action.drift.seek, None);
+        assert_eq!(action.drift.speed, Some(1.0 - default_max_nudge()));
+        assert_eq!(follower.state, NetSyncState::Syncing);
+
+        let mut follower = following(10.0, false, now, 100.0);
+        let action = follower.update(now, 100.0, CLIP, 10.0 - default_seek_threshold(), false);
+        assert_eq!(action.drift.speed, Some(1.0 + default_max_nudge()));
+
+        // マスターの速度を基準に補正する
+        let mut follower = self::follower();
+        follower.on_packet(SyncPacket { speed: 2.0, ..packet(10.0, false, 100.0) }, now, 100.0);
+        let action = follower.update(now, 100.0, CLIP, 10.0, false);
+        assert_eq!(action.drift.speed, Some(2.0));
+        assert_eq!(follower.state, NetSyncState::Locked);
+    }
+
+    #[test]
+    fn follows_pause_and_clip_of_the_master() {
+        let now = Instant::now();
+        let mut follower = following(10.0, true, now, 100.0);
+        let action = follower.update(now, 100.0, CLIP, 10.5, false);
+        assert_eq!(follower.state, NetSyncState::Paused);
+        assert_eq!(action.drift.pause, Some(true));
+        assert_eq!(action.drift.seek, Some(10.0));
+
+        let mut follower = following(10.0, false, now, 100.0);
+        let action = follower.update(now, 100.0, Some("https://example.com/other"), 3.0, false);
+        assert_eq!(follower.state, NetSyncState::Switching);
+        assert_eq!(action.clip.as_deref(), CLIP);
+        // 切り替え中は同じクリップを指示し直さない
+        let action = follower.update(now, 100.0, Some("https://example.com/other"), 3.0, false);
+        assert_eq!(action.clip, None);
+    }
+
+    #[test]
+    fn waits_when_the_master_goes_silent() {
+        let now = Instant::now();
+        let mut follower = following(10.0, false, now, 100.0);
+        follower.update(now, 100.0, CLIP, 10.3, false);
+        assert_ne!(follower.corrector.speed(), 1.0);
+
+        let action = follower.update(now + MASTER_TIMEOUT, 101.0, CLIP, 11.0, false);
+        assert_eq!(follower.state, NetSyncState::Waiting);
+        assert_eq!(action.drift.speed, Some(1.0));
+        assert_eq!(follower.sync_error, None);
+    }
+
+    #[test]
+    fn loopback_round_trip_from_master_to_follower() {
+        // 空いているポートを探してフォロワーの受信を開く
+        let port = UdpSocket::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        spawn_receiver(port, "default".to_string(), tx, stop.clone()).unwrap();
+
+        let socket = UdpSocket::bind(("127.0.0.1", 0)).unwrap();
+        let targets = [SocketAddr::from(([127, 0, 0, 1], port))];
+        // 別のグループのパケットは届かない
+        send_packet(&socket, &targets, &SyncPacket { group: "other".to_string(), ..packet(1.0, false, wall_clock()) });
+        let sent = packet(42.0, false, wall_clock());
+        send_packet(&socket, &targets, &sent);
+
+        let (received, at, wall) = rx.recv_timeout(Duration::from_secs(2)).expect("パケットが届きません");
+        stop.store(true, Ordering::Relaxed);
+        // 送信時刻は JSON を経由すると最下位の桁が丸められることがある
+        assert!((received.sent - sent.sent).abs() < 1e-6);
+        assert_eq!(SyncPacket { sent: sent.sent, ..received.clone() }, sent);
+
+        let mut follower = follower();
+        follower.on_packet(received, at, wall);
+        follower.update(at, wall, CLIP, 42.0 + (wall - sent.sent), false);
+        assert_eq!(follower.state, NetSyncState::Locked);
+        assert!(follower.status().latency.unwrap() >= 0.0);
+    }
+}
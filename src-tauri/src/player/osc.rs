@@ -0,0 +1,71 @@
+/// OSC（Open Sound Control）メッセージの送信
+///
+/// 照明卓・VJ ソフトなどへの合図に使う。OSC 1.0 のメッセージ（バンドルなし）を UDP で送る。
+/// 引数は int32（i）・float32（f）・文字列（s）に対応する。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::net::UdpSocket;
+
+/// OSC メッセージの引数（JSON の整数は int32、小数は float32、文字列は string になる）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    String(String),
+}
+
+/// OSC アドレスの形式を確認する
+pub fn validate_address(address: &str) -> Result<()> {
+    if !address.starts_with('/') || address.contains(|c: char| c.is_whitespace() || c == '#') {
+        return Err(anyhow::anyhow!("OSC アドレスが不正です: {}", address));
+    }
+    Ok(())
+}
+
+/// OSC メッセージをバイト列にする
+pub fn encode(address: &str, args: &[OscArg]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    write_string(&mut packet, address);
+
+    let mut tags = String::from(",");
+    for arg in args {
+        tags.push(match arg {
+            OscArg::Int(_) => 'i',
+            OscArg::Float(_) => 'f',
+            OscArg::String(_) => 's',
+        });
+    }
+    write_string(&mut packet, &tags);
+
+    for arg in args {
+        match arg {
+            OscArg::Int(value) => packet.extend_from_slice(&value.to_be_bytes()),
+            OscArg::Float(value) => packet.extend_from_slice(&value.to_be_bytes()),
+            OscArg::String(value) => write_string(&mut packet, value),
+        }
+    }
+    packet
+}
+
+/// NUL 終端し、4 バイト境界まで NUL で埋める
+fn write_string(packet: &mut Vec<u8>, value: &str) {
+    packet.extend_from_slice(value.as_bytes());
+    let padding = 4 - value.len() % 4;
+    packet.resize(packet.len() + padding, 0);
+}
+
+/// OSC メッセージを host:port に送る
+pub fn send(host: &str, port: u16, address: &str, args: &[OscArg]) -> Result<()> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0))
+        .map_err(|e| anyhow::anyhow!("OSC 送信用のソケットを作成できません: {}", e))?;
+    send_with(&socket, host, port, address, args)
+}
+
+/// 作成済みのソケットで送る（メーターなど高い頻度で送る場合に使う）
+pub fn send_with(socket: &UdpSocket, host: &str, port: u16, address: &str, args: &[OscArg]) -> Result<()> {
+    socket
+        .send_to(&encode(address, args), (host, port))
+        .map_err(|e| anyhow::anyhow!("OSC メッセージを送信できません ({}:{}): {}", host, port, e))?;
+    Ok(())
+}
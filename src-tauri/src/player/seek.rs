@@ -0,0 +1,119 @@
+/// シークの種類（絶対位置・相対・パーセント）と精度、フレーム単位の移動
+///
+/// mpv の `seek` は既定ではキーフレーム単位で移動するため速いが位置がずれる。
+/// `exact` を指定すると `+exact`（hr-seek）でデコードし直し、指定の位置に正確に止まる。
+/// フレーム番号での移動は映像トラックの fps（`demux-fps`）から時刻に換算する。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// シークの基準
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeekMode {
+    /// 先頭からの秒数
+    #[default]
+    Absolute,
+    /// 現在位置からの秒数（負の値で戻る）
+    Relative,
+    /// 動画の長さに対する割合（0–100）
+    Percent,
+}
+
+/// シークの指定
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SeekRequest {
+    #[serde(default)]
+    pub mode: SeekMode,
+    pub value: f64,
+    /// true の場合はキーフレームではなく指定の位置に正確に移動する
+    #[serde(default)]
+    pub exact: bool,
+}
+
+impl SeekRequest {
+    /// 先頭からの秒数で正確に移動する指定
+    pub fn exact(seconds: f64) -> Self {
+        Self { mode: SeekMode::Absolute, value: seconds, exact: true }
+    }
+
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        if !self.value.is_finite() {
+            return Err(anyhow::anyhow!("シーク位置が不正です: {}", self.value));
+        }
+        match self.mode {
+            SeekMode::Absolute if self.value < 0.0 => {
+                Err(anyhow::anyhow!("シーク位置は 0 以上で指定してください: {}", self.value))
+            }
+            SeekMode::Percent if !(0.0..=100.0).contains(&self.value) => {
+                Err(anyhow::anyhow!("割合は 0–100 で指定してください: {}", self.value))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// mpv の seek コマンドに渡すフラグ（"absolute+exact" など）
+    pub fn mpv_flags(&self) -> String {
+        let base = match self.mode {
+            SeekMode::Absolute => "absolute",
+            SeekMode::Relative => "relative",
+            SeekMode::Percent => "absolute-percent",
+        };
+        let precision = if self.exact { "exact" } else { "keyframes" };
+        format!("{}+{}", base, precision)
+    }
+}
+
+/// フレーム番号を先頭からの秒数に換算する
+pub fn frame_time(frame: u64, fps: f64) -> Result<f64> {
+    if !fps.is_finite() || fps <= 0.0 {
+        return Err(anyhow::anyhow!("fps が不正です: {}", fps));
+    }
+    Ok(frame as f64 / fps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> SeekRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn parses_relative_seeks() {
+        let request = parse(r#"{"mode":"relative","value":-5.5}"#);
+        assert_eq!(request, SeekRequest { mode: SeekMode::Relative, value: -5.5, exact: false });
+        // 相対シークは負の値（戻る）も受け付ける
+        assert!(request.validate().is_ok());
+        assert_eq!(request.mpv_flags(), "relative+keyframes");
+        assert!(parse(r#"{"mode":"relative","value":10,"exact":true}"#).validate().is_ok());
+    }
+
+    #[test]
+    fn parses_percent_seeks() {
+        let request = parse(r#"{"mode":"percent","value":50,"exact":true}"#);
+        assert_eq!(request, SeekRequest { mode: SeekMode::Percent, value: 50.0, exact: true });
+        assert_eq!(request.mpv_flags(), "absolute-percent+exact");
+        assert!(parse(r#"{"mode":"percent","value":0}"#).validate().is_ok());
+        assert!(parse(r#"{"mode":"percent","value":100}"#).validate().is_ok());
+        assert!(parse(r#"{"mode":"percent","value":100.5}"#).validate().is_err());
+        assert!(parse(r#"{"mode":"percent","value":-1}"#).validate().is_err());
+    }
+
+    #[test]
+    fn mode_defaults_to_absolute() {
+        let request = parse(r#"{"value":12}"#);
+        assert_eq!(request.mode, SeekMode::Absolute);
+        assert_eq!(request.mpv_flags(), "absolute+keyframes");
+        assert!(parse(r#"{"value":-1}"#).validate().is_err());
+        assert!(serde_json::from_str::<SeekRequest>(r#"{"mode":"chapter","value":1}"#).is_err());
+    }
+
+    #[test]
+    fn converts_frames_to_seconds() {
+        assert_eq!(frame_time(48, 24.0).unwrap(), 2.0);
+        assert!(frame_time(1, 0.0).is_err());
+        assert!(frame_time(1, f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,477 @@
+/// 再生ステータスの状態遷移
+///
+/// ディスパッチャから届く `PlayerEvent` だけを入力にして `PlayStatus` を決める。
+/// ストール検知のみ「進捗のイベントが来ないこと」が条件になるため、
+/// ディスパッチャのループごとに `on_tick()` で時間経過を評価する。
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use super::events::{EndFileReason, PlayerEvent, PropertyChange};
+
+/// バッファリングが進まない / 再生位置が進まない状態がこの時間続いたら Stalled とみなす
+const STALL_TIMEOUT: Duration = Duration::from_secs(8);
+
+// ─── ステータス ──────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlayStatus {
+    Idle,
+    Loading,
+    Playing,
+    Paused,
+    /// キャッシュ不足で待機中（充填率 0–100）
+    Buffering(u8),
+    Seeking,
+    /// 最後まで再生した（ループ無効時）
+    Ended,
+    /// バッファリング・再生位置が一定時間進んでいない
+    Stalled,
+    Error(PlayError),
+}
+
+/// エラーの分類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorCause {
+    /// 接続失敗・HTTP エラー・タイムアウトなど
+    Network,
+    /// yt-dlp による URL 解決の失敗
+    Extractor,
+    /// 未対応フォーマット・デコード失敗
+    Decoder,
+    /// 音声/映像出力・Syphon/Spout の失敗
+    Output,
+}
+
+/// 分類付きのエラー
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlayError {
+    pub cause: ErrorCause,
+    pub message: String,
+}
+
+/// IPC / UI 向けのステータス種別（付随データを持たない）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusKind {
+    Idle,
+    Loading,
+    Playing,
+    Paused,
+    Buffering,
+    Seeking,
+    Ended,
+    Stalled,
+    Error,
+}
+
+impl PlayStatus {
+    pub fn kind(&self) -> StatusKind {
+        match self {
+            PlayStatus::Idle => StatusKind::Idle,
+            PlayStatus::Loading => StatusKind::Loading,
+            PlayStatus::Playing => StatusKind::Playing,
+            PlayStatus::Paused => StatusKind::Paused,
+            PlayStatus::Buffering(_) => StatusKind::Buffering,
+            PlayStatus::Seeking => StatusKind::Seeking,
+            PlayStatus::Ended => StatusKind::Ended,
+            PlayStatus::Stalled => StatusKind::Stalled,
+            PlayStatus::Error(_) => StatusKind::Error,
+        }
+    }
+}
+
+// ─── 状態機械 ────────────────────────────────────────────────────────────────
+
+/// `PlayStatus` の状態機械
+///
+/// 遷移の判定に必要な直近のプロパティ値（pause・キャッシュ充填率など）も保持する。
+#[derive(Debug)]
+pub struct StatusMachine {
+    status: PlayStatus,
+    paused: bool,
+    cache_percent: u8,
+    /// 最後に進捗（再生位置の更新・キャッシュ充填率の増加）があった時刻
+    last_progress: Instant,
+    /// 直近の警告/エラーログ（エラー分類に使う）
+    last_log: Option<(String, String)>,
+}
+
+impl StatusMachine {
+    pub fn new() -> Self {
+        Self {
+            status: PlayStatus::Idle,
+            paused: false,
+            cache_percent: 0,
+            last_progress: Instant::now(),
+            last_log: None,
+        }
+    }
+
+    pub fn status(&self) -> &PlayStatus {
+        &self.status
+    }
+
+    /// 外部（play / stop / 出力エラー）から状態を設定する
+    pub fn set(&mut self, status: PlayStatus) {
+        if matches!(status, PlayStatus::Loading | PlayStatus::Idle) {
+            self.paused = false;
+            self.cache_percent = 0;
+            self.last_log = None;
+        }
+        self.last_progress = Instant::now();
+        self.status = status;
+    }
+
+    /// イベントを適用する。ステータスが変化した場合は true を返す
+    pub fn on_event(&mut self, event: &PlayerEvent) -> bool {
+        let next = self.next_status(event);
+        self.transition(next)
+    }
+
+    /// 時間経過を評価する（ディスパッチャのループごとに呼ぶ）。ステータスが変化した場合は true を返す
+    pub fn on_tick(&mut self, now: Instant) -> bool {
+        let waiting = matches!(self.status, PlayStatus::Buffering(_))
+            || (self.status == PlayStatus::Playing && !self.paused);
+        if waiting && now.duration_since(self.last_progress) >= STALL_TIMEOUT {
+            log::warn!("{} 秒以上進捗がないため Stalled とみなします", STALL_TIMEOUT.as_secs());
+            return self.transition(Some(PlayStatus::Stalled));
+        }
+        false
+    }
+
+    fn transition(&mut self, next: Option<PlayStatus>) -> bool {
+        match next {
+            Some(next) if next != self.status => {
+                log::info!("再生ステータス: {:?} → {:?}", self.status, next);
+                self.status = next;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 再生中（一時停止含む）のときの通常状態
+    fn running_status(&self) -> PlayStatus {
+        if self.paused { PlayStatus::Paused } else { PlayStatus::Playing }
+    }
+
+    /// イベントから次のステータスを決める（変化しない場合は None）
+    fn next_status(&mut self, event: &PlayerEvent) -> Option<PlayStatus> {
+        // Idle（停止済み）では mpv の残りイベントで状態を変えない
+        if self.status == PlayStatus::Idle {
+            return None;
+        }
+
+        match event {
+            PlayerEvent::StartFile => Some(PlayStatus::Loading),
+            PlayerEvent::Seek => match self.status {
+                PlayStatus::Loading | PlayStatus::Ended | PlayStatus::Error(_) => None,
+                _ => Some(PlayStatus::Seeking),
+            },
+            // 最初のフレームの準備完了 / シーク完了
+            PlayerEvent::PlaybackRestart => {
+                self.last_progress = Instant::now();
+                Some(self.running_status())
+            }
+            PlayerEvent::EndFile { reason, error } => match reason {
+                EndFileReason::Eof => Some(PlayStatus::Ended),
+                EndFileReason::Error => {
+                    let message = error.clone().unwrap_or_else(|| "不明なエラー".to_string());
+                    let cause = categorize_error(&message, self.last_log.as_ref());
+                    Some(PlayStatus::Error(PlayError { cause, message: self.describe(&message) }))
+                }
+                // stop / replace / redirect は次の START_FILE か stop() で決まる
+                _ => None,
+            },
+            PlayerEvent::LogMessage { prefix, text, .. } => {
+                self.last_log = Some((prefix.clone(), text.clone()));
+                None
+            }
+            PlayerEvent::OutputError { message, .. } => Some(PlayStatus::Error(PlayError {
+                cause: ErrorCause::Output,
+                message: message.clone(),
+            })),
+            PlayerEvent::PropertyChange(change) => self.on_property(change),
+            _ => None,
+        }
+    }
+
+    fn on_property(&mut self, change: &PropertyChange) -> Option<PlayStatus> {
+        match *change {
+            PropertyChange::Pause(paused) => {
+                self.paused = paused;
+                match self.status {
+                    PlayStatus::Playing | PlayStatus::Paused | PlayStatus::Stalled => {
+                        Some(self.running_status())
+                    }
+                    _ => None,
+                }
+            }
+            PropertyChange::PausedForCache(true) => match self.status {
+                PlayStatus::Playing | PlayStatus::Stalled => {
+                    self.last_progress = Instant::now();
+                    Some(PlayStatus::Buffering(self.cache_percent))
+                }
+                _ => None,
+            },
+            PropertyChange::PausedForCache(false) => match self.status {
+                PlayStatus::Buffering(_) | PlayStatus::Stalled => Some(self.running_status()),
+                _ => None,
+            },
+            PropertyChange::CacheBufferingState(percent) => {
+                let percent = percent.clamp(0, 100) as u8;
+                if percent > self.cache_percent {
+                    self.last_progress = Instant::now();
+                }
+                self.cache_percent = percent;
+                match self.status {
+                    PlayStatus::Buffering(_) => Some(PlayStatus::Buffering(percent)),
+                    _ => None,
+                }
+            }
+            PropertyChange::TimePos(_) => {
+                self.last_progress = Instant::now();
+                match self.status {
+                    // 再生位置が再び進み始めたらストールから復帰
+                    PlayStatus::Stalled if !self.paused => Some(PlayStatus::Playing),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// エラーメッセージに直近のログを補足する
+    fn describe(&self, message: &str) -> String {
+        match &self.last_log {
+            Some((prefix, text)) => format!("{} ({}: {})", message, prefix, text),
+            None => message.to_string(),
+        }
+    }
+}
+
+/// mpv のエラーメッセージと直近のログからエラーを分類する
+pub fn categorize_error(message: &str, last_log: Option<&(String, String)>) -> ErrorCause {
+    const NETWORK_HINTS: &[&str] = &[
+        "http error", "unable to download", "timed out", "timeout", "connection",
+        "network", "resolve", "tcp", "tls", "ssl", "403", "404",
+    ];
+
+    let message = message.to_lowercase();
+    let (prefix, text) = last_log
+        .map(|(p, t)| (p.to_lowercase(), t.to_lowercase()))
+        .unwrap_or_default();
+
+    if NETWORK_HINTS.iter().any(|hint| text.contains(hint)) {
+        return ErrorCause::Network;
+    }
+    if prefix.starts_with("ytdl_hook") || text.contains("yt-dlp") {
+        return ErrorCause::Extractor;
+    }
+    if message.contains("output initialization failed") || prefix.starts_with("ao") || prefix.starts_with("vo") {
+        return ErrorCause::Output;
+    }
+    if message.contains("unrecognized file format")
+        || message.contains("no audio or video")
+        || prefix.starts_with("vd")
+        || prefix.starts_with("ad")
+    {
+        return ErrorCause::Decoder;
+    }
+    // ストリームが開けない場合（loading failed など）はネットワーク起因として扱う
+    ErrorCause::Network
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 再生を開始した直後の状態機械（play() と同じく Loading から始める）
+    fn loading() -> StatusMachine {
+        let mut machine = StatusMachine::new();
+        machine.set(PlayStatus::Loading);
+        machine
+    }
+
+    /// 最初のフレームまで進めた状態機械
+    fn playing() -> StatusMachine {
+        let mut machine = loading();
+        machine.on_event(&PlayerEvent::PlaybackRestart);
+        machine
+    }
+
+    fn property(change: PropertyChange) -> PlayerEvent {
+        PlayerEvent::PropertyChange(change)
+    }
+
+    fn log(prefix: &str, text: &str) -> Option<(String, String)> {
+        Some((prefix.to_string(), text.to_string()))
+    }
+
+    #[test]
+    fn loading_to_playing() {
+        let mut machine = loading();
+        assert!(machine.on_event(&PlayerEvent::PlaybackRestart));
+        assert_eq!(machine.status(), &PlayStatus::Playing);
+    }
+
+    #[test]
+    fn idle_ignores_leftover_events() {
+        let mut machine = StatusMachine::new();
+        assert!(!machine.on_event(&PlayerEvent::PlaybackRestart));
+        assert_eq!(machine.status(), &PlayStatus::Idle);
+    }
+
+    #[test]
+    fn buffering_follows_cache_events() {
+        let mut machine = playing();
+        machine.on_event(&property(PropertyChange::CacheBufferingState(10)));
+        assert!(machine.on_event(&property(PropertyChange::PausedForCache(true))));
+        assert_eq!(machine.status(), &PlayStatus::Buffering(10));
+
+        assert!(machine.on_event(&property(PropertyChange::CacheBufferingState(55))));
+        assert_eq!(machine.status(), &PlayStatus::Buffering(55));
+        // 範囲外の値は 0–100 に収める
+        machine.on_event(&property(PropertyChange::CacheBufferingState(250)));
+        assert_eq!(machine.status(), &PlayStatus::Buffering(100));
+
+        assert!(machine.on_event(&property(PropertyChange::PausedForCache(false))));
+        assert_eq!(machine.status(), &PlayStatus::Playing);
+    }
+
+    #[test]
+    fn seeking_then_playing() {
+        let mut machine = playing();
+        assert!(machine.on_event(&PlayerEvent::Seek));
+        assert_eq!(machine.status(), &PlayStatus::Seeking);
+        assert!(machine.on_event(&PlayerEvent::PlaybackRestart));
+        assert_eq!(machine.status(), &PlayStatus::Playing);
+    }
+
+    #[test]
+    fn seek_while_loading_stays_loading() {
+        let mut machine = loading();
+        assert!(!machine.on_event(&PlayerEvent::Seek));
+        assert_eq!(machine.status(), &PlayStatus::Loading);
+    }
+
+    #[test]
+    fn paused_seek_returns_to_paused() {
+        let mut machine = playing();
+        machine.on_event(&property(PropertyChange::Pause(true)));
+        assert_eq!(machine.status(), &PlayStatus::Paused);
+        machine.on_event(&PlayerEvent::Seek);
+        machine.on_event(&PlayerEvent::PlaybackRestart);
+        assert_eq!(machine.status(), &PlayStatus::Paused);
+    }
+
+    #[test]
+    fn eof_ends() {
+        let mut machine = playing();
+        let event = PlayerEvent::EndFile { reason: EndFileReason::Eof, error: None };
+        assert!(machine.on_event(&event));
+        assert_eq!(machine.status(), &PlayStatus::Ended);
+    }
+
+    #[test]
+    fn stop_reason_keeps_status() {
+        let mut machine = playing();
+        let event = PlayerEvent::EndFile { reason: EndFileReason::Stop, error: None };
+        assert!(!machine.on_event(&event));
+        assert_eq!(machine.status(), &PlayStatus::Playing);
+    }
+
+    #[test]
+    fn error_uses_last_log() {
+        let mut machine = loading();
+        machine.on_event(&PlayerEvent::LogMessage {
+            prefix: "ytdl_hook".to_string(),
+            level: "error".to_string(),
+            text: "ERROR: Video unavailable".to_string(),
+        });
+        let event = PlayerEvent::EndFile { reason: EndFileReason::Error, error: Some("loading failed".to_string()) };
+        assert!(machine.on_event(&event));
+        match machine.status() {
+            PlayStatus::Error(error) => {
+                assert_eq!(error.cause, ErrorCause::Extractor);
+                assert!(error.message.contains("Video unavailable"));
+            }
+            other => panic!("Error になっていません: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn stalls_without_progress() {
+        let mut machine = playing();
+        let start = Instant::now();
+        assert!(!machine.on_tick(start));
+        assert!(machine.on_tick(start + STALL_TIMEOUT));
+        assert_eq!(machine.status(), &PlayStatus::Stalled);
+
+        // 再生位置が進み始めたら復帰する
+        assert!(machine.on_event(&property(PropertyChange::TimePos(1.0))));
+        assert_eq!(machine.status(), &PlayStatus::Playing);
+    }
+
+    #[test]
+    fn stalls_while_unrelated_events_keep_arriving() {
+        // 音量やメーターのイベントは進捗ではないため、届き続けてもストールを判定する
+        let mut machine = playing();
+        let start = Instant::now();
+        let mut now = start;
+        while now < start + STALL_TIMEOUT {
+            machine.on_event(&property(PropertyChange::Volume(80.0)));
+            machine.on_event(&PlayerEvent::AudioReconfig);
+            assert!(!machine.on_tick(now));
+            now += Duration::from_millis(50);
+        }
+        assert!(machine.on_tick(now));
+        assert_eq!(machine.status(), &PlayStatus::Stalled);
+    }
+
+    #[test]
+    fn paused_does_not_stall() {
+        let mut machine = playing();
+        machine.on_event(&property(PropertyChange::Pause(true)));
+        assert!(!machine.on_tick(Instant::now() + STALL_TIMEOUT * 2));
+        assert_eq!(machine.status(), &PlayStatus::Paused);
+    }
+
+    #[test]
+    fn buffering_stalls_when_cache_stops_filling() {
+        let mut machine = playing();
+        machine.on_event(&property(PropertyChange::PausedForCache(true)));
+        assert!(machine.on_tick(Instant::now() + STALL_TIMEOUT));
+        assert_eq!(machine.status(), &PlayStatus::Stalled);
+    }
+
+    #[test]
+    fn categorize_network() {
+        let cause = categorize_error("loading failed", log("ffmpeg", "HTTP error 403 Forbidden").as_ref());
+        assert_eq!(cause, ErrorCause::Network);
+        let cause = categorize_error("loading failed", log("stream", "Connection timed out").as_ref());
+        assert_eq!(cause, ErrorCause::Network);
+        // ログが無い場合もストリームを開けなかったものとして扱う
+        assert_eq!(categorize_error("loading failed", None), ErrorCause::Network);
+    }
+
+    #[test]
+    fn categorize_extractor() {
+        let cause = categorize_error("loading failed", log("ytdl_hook", "ERROR: Private video").as_ref());
+        assert_eq!(cause, ErrorCause::Extractor);
+        let cause = categorize_error("loading failed", log("cplayer", "yt-dlp failed").as_ref());
+        assert_eq!(cause, ErrorCause::Extractor);
+    }
+
+    #[test]
+    fn categorize_decoder_and_output() {
+        assert_eq!(categorize_error("Unrecognized file format", None), ErrorCause::Decoder);
+        let cause = categorize_error("loading failed", log("vd", "Could not open codec").as_ref());
+        assert_eq!(cause, ErrorCause::Decoder);
+        let cause = categorize_error("audio output initialization failed", None);
+        assert_eq!(cause, ErrorCause::Output);
+        let cause = categorize_error("loading failed", log("ao/coreaudio", "device lost").as_ref());
+        assert_eq!(cause, ErrorCause::Output);
+    }
+}
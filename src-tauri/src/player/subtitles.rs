@@ -0,0 +1,174 @@
+/// 字幕（YouTube の字幕・自動生成字幕）の取得・選択・表示
+///
+/// ## 取得
+/// mpv の ytdl_hook が yt-dlp に字幕を要求し、外部字幕トラックとして追加する。
+/// 要求する言語と自動生成字幕の有無は `ytdl-raw-options` で渡すため、
+/// 変更は次の読み込みから有効になる（すでに取得済みのトラックはすぐ切り替えられる）。
+///
+/// ## 表示先
+/// - `BurnIn`: mpv が映像に焼き込む（Syphon / Spout の出力とプレビューの両方に出る）
+/// - `PreviewOnly`: 出力には出さず、プレビューにだけ字幕を重ねる（オペレーター確認用）
+/// - `Off`: 字幕を選択しない
+///
+/// 表示中の字幕テキストは `sub-text` プロパティの変更として `mpv-event` で UI に届く。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::tracks::{self, TrackKind};
+use crate::output::overlay::{Anchor, OverlayContent, OverlayLayer, Rgba};
+
+/// プレビュー用の字幕レイヤーの id
+pub const CAPTION_LAYER_ID: &str = "subtitle";
+
+/// mpv の sub-font-size の基準になる映像の高さ
+const MPV_SUB_SCALE_HEIGHT: f32 = 720.0;
+
+/// 字幕の表示先
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SubtitleMode {
+    #[default]
+    Off,
+    BurnIn,
+    PreviewOnly,
+}
+
+/// 字幕の見た目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubtitleStyle {
+    /// 文字の大きさ（mpv の sub-font-size と同じく 720p 基準のピクセル数）
+    pub font_size: f32,
+    /// 縦位置（0 = 上端、100 = 下端）
+    pub position: u8,
+    pub color: Rgba,
+    /// 文字の背景（None の場合は縁取り）
+    pub background: Option<Rgba>,
+}
+
+impl Default for SubtitleStyle {
+    fn default() -> Self {
+        Self {
+            font_size: 55.0,
+            position: 95,
+            color: Rgba::WHITE,
+            background: None,
+        }
+    }
+}
+
+/// 字幕の設定
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubtitleSettings {
+    pub mode: SubtitleMode,
+    /// 優先する言語（"ja"、"en" など。None の場合は最初のトラック）
+    pub language: Option<String>,
+    /// 自動生成字幕も取得する（language を指定した場合のみ。全言語の自動翻訳は数が多すぎるため）
+    pub include_auto: bool,
+    pub style: SubtitleStyle,
+}
+
+impl SubtitleSettings {
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        if !(8.0..=200.0).contains(&self.style.font_size) {
+            return Err(anyhow::anyhow!("font_size は 8–200 で指定してください: {}", self.style.font_size));
+        }
+        if self.style.position > 100 {
+            return Err(anyhow::anyhow!("position は 0–100 で指定してください: {}", self.style.position));
+        }
+        if let Some(language) = &self.language {
+            tracks::validate_language(language)?;
+        }
+        Ok(())
+    }
+
+    /// yt-dlp に渡す字幕の取得オプション（ytdl-raw-options の値）
+    pub fn ytdl_raw_options(&self) -> String {
+        match &self.language {
+            Some(language) if self.include_auto => {
+                format!("sub-langs=[{}.*],write-subs=,write-auto-subs=", language)
+            }
+            Some(language) => format!("sub-langs=[{}.*],write-subs=", language),
+            None => "sub-langs=[all,-live_chat],write-subs=".to_string(),
+        }
+    }
+
+    /// プレビューにだけ重ねる字幕レイヤー（PreviewOnly 以外では None）
+    pub fn preview_layer(&self) -> Option<OverlayLayer> {
+        if self.mode != SubtitleMode::PreviewOnly {
+            return None;
+        }
+        Some(OverlayLayer {
+            id: CAPTION_LAYER_ID.to_string(),
+            content: OverlayContent::Text { text: "{subtitle}".to_string() },
+            x: 0.5,
+            y: self.style.position as f32 / 100.0,
+            anchor: Anchor::Bottom,
+            size: self.style.font_size / MPV_SUB_SCALE_HEIGHT,
+            font: None,
+            color: self.style.color,
+            background: self.style.background,
+            visible: true,
+            fade_in: 0.0,
+            fade_out: 0.0,
+        })
+    }
+}
+
+/// mpv の色指定（#AARRGGBB）
+pub fn mpv_color(color: Rgba) -> String {
+    format!("#{:02X}{:02X}{:02X}{:02X}", color.a, color.r, color.g, color.b)
+}
+
+// ─── トラック一覧 ────────────────────────────────────────────────────────────
+
+/// 字幕トラックの情報
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubtitleTrack {
+    /// mpv のトラック ID（sid）
+    pub id: i64,
+    pub lang: Option<String>,
+    pub title: Option<String>,
+    pub codec: Option<String>,
+    /// yt-dlp から取得した外部字幕か
+    pub external: bool,
+    /// 自動生成字幕か（yt-dlp の言語コード・タイトルから推定）
+    pub auto_generated: bool,
+    pub selected: bool,
+}
+
+/// mpv の track-list（JSON）から字幕トラックを取り出す
+pub fn parse_tracks(track_list: &str) -> Result<Vec<SubtitleTrack>> {
+    Ok(tracks::parse_track_list(track_list)?
+        .into_iter()
+        .filter(|track| track.kind == TrackKind::Sub)
+        .map(|track| {
+            // yt-dlp は元言語の自動生成字幕を "<言語>-orig" とし、タイトルに "auto" を含める
+            let auto_generated = track.lang.as_deref().is_some_and(|l| l.ends_with("-orig"))
+                || track.title.as_deref().is_some_and(|t| t.to_lowercase().contains("auto"));
+            SubtitleTrack {
+                id: track.id,
+                lang: track.lang,
+                title: track.title,
+                codec: track.codec,
+                external: track.external,
+                auto_generated,
+                selected: track.selected,
+            }
+        })
+        .collect())
+}
+
+/// 言語に合うトラックを選ぶ（手動の字幕を自動生成より優先する）
+pub fn find_track<'a>(tracks: &'a [SubtitleTrack], language: Option<&str>) -> Option<&'a SubtitleTrack> {
+    let matches = |track: &&SubtitleTrack| match language {
+        Some(language) => tracks::matches_language(track.lang.as_deref(), language),
+        None => true,
+    };
+    tracks
+        .iter()
+        .filter(matches)
+        .min_by_key(|track| track.auto_generated)
+}
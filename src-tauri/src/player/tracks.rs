@@ -0,0 +1,172 @@
+/// 音声・映像・字幕トラックの一覧と選択
+///
+/// mpv の `track-list` プロパティ（JSON）を型付きのトラック情報に変換する。
+/// 選択は `aid` / `vid` / `sid` プロパティで行う。
+///
+/// ## 優先言語
+/// 音声の優先言語は `TrackPreferences` に保持し、再生開始時に `alang` として渡す。
+/// トラックを手動で選択するとその言語を優先言語として覚え、次回の再生にも引き継ぐ。
+/// 優先言語はアプリのデータディレクトリの `tracks.json` に保存し、次回の起動でも使う。
+/// 字幕の優先言語は `SubtitleSettings` 側で使う（取得する言語も決めるため）。
+/// `tracks.json` には保存用の写しを置き、起動時に `SubtitleSettings` へ読み込む。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::json_store::JsonStore;
+
+/// トラックの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackKind {
+    Audio,
+    Video,
+    Sub,
+}
+
+impl TrackKind {
+    /// 選択に使う mpv のプロパティ名
+    pub fn mpv_property(self) -> &'static str {
+        match self {
+            TrackKind::Audio => "aid",
+            TrackKind::Video => "vid",
+            TrackKind::Sub => "sid",
+        }
+    }
+
+    fn from_mpv(value: &str) -> Option<Self> {
+        match value {
+            "audio" => Some(TrackKind::Audio),
+            "video" => Some(TrackKind::Video),
+            "sub" => Some(TrackKind::Sub),
+            _ => None,
+        }
+    }
+}
+
+/// トラックの情報
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Track {
+    /// mpv のトラック ID（種類ごとに 1 から振られる）
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub kind: TrackKind,
+    pub lang: Option<String>,
+    pub codec: Option<String>,
+    pub title: Option<String>,
+    /// 映像トラックのフレームレート（demux-fps。不明な場合は None）
+    pub fps: Option<f64>,
+    /// コンテナで既定トラックに指定されているか
+    pub default: bool,
+    /// 外部ファイル（yt-dlp が取得した字幕など）か
+    pub external: bool,
+    pub selected: bool,
+}
+
+/// mpv の track-list（JSON）を解析する
+pub fn parse_track_list(track_list: &str) -> Result<Vec<Track>> {
+    let tracks: Vec<serde_json::Value> = serde_json::from_str(track_list)
+        .map_err(|e| anyhow::anyhow!("track-list を解析できません: {}", e))?;
+
+    let text = |track: &serde_json::Value, key: &str| {
+        track.get(key).and_then(|v| v.as_str()).map(str::to_string)
+    };
+    let flag = |track: &serde_json::Value, key: &str| {
+        track.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+    };
+    Ok(tracks
+        .iter()
+        .filter_map(|track| {
+            Some(Track {
+                id: track.get("id")?.as_i64()?,
+                kind: TrackKind::from_mpv(track.get("type")?.as_str()?)?,
+                lang: text(track, "lang"),
+                codec: text(track, "codec"),
+                title: text(track, "title"),
+                fps: track.get("demux-fps").and_then(|v| v.as_f64()),
+                default: flag(track, "default"),
+                external: flag(track, "external"),
+                selected: flag(track, "selected"),
+            })
+        })
+        .collect())
+}
+
+/// 言語コードが "ja" / "en-US" / "pt_BR" のような形式か確認する
+pub fn validate_language(language: &str) -> Result<()> {
+    let valid = !language.is_empty()
+        && language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(anyhow::anyhow!("言語コードが不正です: {}", language));
+    }
+    Ok(())
+}
+
+/// トラックの言語が指定の言語に合うか（"en" は "en-US" にも合う）
+pub fn matches_language(lang: Option<&str>, language: &str) -> bool {
+    lang.is_some_and(|l| l == language || l.starts_with(&format!("{}-", language)))
+}
+
+// ─── 優先言語 ────────────────────────────────────────────────────────────────
+
+/// トラック選択の設定（次回の再生にも引き継がれる）
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackPreferences {
+    /// 優先する音声の言語（None の場合は mpv の既定の選択）
+    pub audio_language: Option<String>,
+    /// 優先する字幕の言語（`SubtitleSettings::language` の保存用）
+    pub sub_language: Option<String>,
+}
+
+impl TrackPreferences {
+    /// 設定値を確認する
+    pub fn validate(&self) -> Result<()> {
+        for language in self.audio_language.iter().chain(&self.sub_language) {
+            validate_language(language)?;
+        }
+        Ok(())
+    }
+
+    /// 優先言語に合う音声トラック（既定トラックを優先する）
+    pub fn preferred_audio<'a>(&self, tracks: &'a [Track]) -> Option<&'a Track> {
+        let language = self.audio_language.as_deref()?;
+        tracks
+            .iter()
+            .filter(|track| track.kind == TrackKind::Audio)
+            .filter(|track| matches_language(track.lang.as_deref(), language))
+            .min_by_key(|track| !track.default)
+    }
+}
+
+/// トラック選択の設定の保存先（`tracks.json`）
+pub type TrackPreferencesStore = JsonStore<TrackPreferences>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_round_trips_audio_and_subtitle_languages() {
+        let dir = std::env::temp_dir().join(format!("yt-bridge-tracks-{}", std::process::id()));
+        let path = dir.join("tracks.json");
+        let _ = std::fs::remove_file(&path);
+
+        let mut store = TrackPreferencesStore::load(path.clone(), "トラック設定");
+        assert_eq!(store.get(), &TrackPreferences::default());
+        store.update(|tracks| tracks.audio_language = Some("ja".to_string())).unwrap();
+        store.update(|tracks| tracks.sub_language = Some("en".to_string())).unwrap();
+
+        let reloaded = TrackPreferencesStore::load(path, "トラック設定");
+        assert_eq!(reloaded.get().audio_language.as_deref(), Some("ja"));
+        assert_eq!(reloaded.get().sub_language.as_deref(), Some("en"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn validates_both_languages() {
+        let valid = TrackPreferences { audio_language: Some("ja".to_string()), sub_language: Some("en-US".to_string()) };
+        assert!(valid.validate().is_ok());
+        let invalid = TrackPreferences { audio_language: None, sub_language: Some("en US".to_string()) };
+        assert!(invalid.validate().is_err());
+    }
+}
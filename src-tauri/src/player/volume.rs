@@ -0,0 +1,310 @@
+/// 音量のフェードと dB での指定
+///
+/// 音量は mpv の `volume`（%。100 が原音のまま、`volume-max` まで増幅できる）で扱う。
+/// mpv の音量は 3 乗のカーブ（ゲイン = (volume / 100)³）なので、dB との換算は
+/// `dB = 60 · log10(volume / 100)` になる。
+///
+/// ## フェード
+/// 進行中のフェード（`VolumeFade`）はイベントディスパッチャのコールバックで少しずつ進める。
+/// 再生開始時のフェードインは音声が出始める PLAYBACK_RESTART から始め、
+/// 停止・一時停止のフェードアウトは音量が下がりきってから停止する。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// mpv の volume-max の上限
+pub const MAX_VOLUME_LIMIT: f64 = 1000.0;
+/// dB で返す下限（無音は -inf になり JSON にできないため）
+pub const MIN_DB: f64 = -120.0;
+/// フェード中に音量を設定し直す最小の変化（%）
+pub const MIN_STEP: f64 = 0.1;
+/// フェードの時間の上限（秒）
+const MAX_FADE: f64 = 60.0;
+
+/// フェードのカーブ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FadeCurve {
+    /// 音量（%）を一定の速さで変える
+    #[default]
+    Linear,
+    /// dB を一定の速さで変える（耳には均等に聞こえる）
+    Logarithmic,
+    /// 等パワー（上げるときは立ち上がりが速く、下げるときは終わりが速い）
+    EqualPower,
+    /// 始めと終わりをなだらかにする
+    SCurve,
+}
+
+impl FadeCurve {
+    /// 進み具合 t（0–1）での from から to への音量
+    fn interpolate(self, from: f64, to: f64, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => from + (to - from) * t,
+            Self::Logarithmic => {
+                // 無音は -inf dB になるため、下限の dB を経由する
+                let (from_db, to_db) = (volume_to_db(from), volume_to_db(to));
+                let db = from_db + (to_db - from_db) * t;
+                if t >= 1.0 { to } else { db_to_volume(db) }
+            }
+            Self::EqualPower => {
+                let shaped = if to >= from {
+                    (t * std::f64::consts::FRAC_PI_2).sin()
+                } else {
+                    1.0 - (t * std::f64::consts::FRAC_PI_2).cos()
+                };
+                from + (to - from) * shaped
+            }
+            Self::SCurve => from + (to - from) * t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// mpv の音量（%）を dB に換算する（無音は MIN_DB）
+pub fn volume_to_db(volume: f64) -> f64 {
+    if volume <= 0.0 {
+        return MIN_DB;
+    }
+    (60.0 * (volume / 100.0).log10()).max(MIN_DB)
+}
+
+/// dB を mpv の音量（%）に換算する（MIN_DB 以下は無音）
+pub fn db_to_volume(db: f64) -> f64 {
+    if db <= MIN_DB {
+        return 0.0;
+    }
+    100.0 * 10f64.powf(db / 60.0)
+}
+
+/// 現在の音量
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VolumeLevel {
+    /// mpv の音量（%）
+    pub volume: f64,
+    pub db: f64,
+    /// 設定できる上限（%）
+    pub max: f64,
+}
+
+impl VolumeLevel {
+    pub fn new(volume: f64, max: f64) -> Self {
+        Self { volume, db: volume_to_db(volume), max }
+    }
+}
+
+// ─── 設定 ────────────────────────────────────────────────────────────────────
+
+/// 音量の上限と、再生・停止時のフェード
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolumeSettings {
+    /// 音量の上限（%。100 を超えると増幅する。mpv の volume-max）
+    #[serde(default = "default_max")]
+    pub max: f64,
+    /// 再生開始・再開時のフェードイン（秒。0 で無効）
+    #[serde(default)]
+    pub fade_in: f64,
+    /// 停止・一時停止時のフェードアウト（秒。0 で無効）
+    #[serde(default = "default_fade_out")]
+    pub fade_out: f64,
+    #[serde(default)]
+    pub curve: FadeCurve,
+}
+
+fn default_max() -> f64 {
+    130.0
+}
+
+fn default_fade_out() -> f64 {
+    0.5
+}
+
+impl Default for VolumeSettings {
+    fn default() -> Self {
+        Self { max: default_max(), fade_in: 0.0, fade_out: default_fade_out(), curve: FadeCurve::default() }
+    }
+}
+
+impl VolumeSettings {
+    /// 設定値の範囲を確認する
+    pub fn validate(&self) -> Result<()> {
+        if !(100.0..=MAX_VOLUME_LIMIT).contains(&self.max) {
+            return Err(anyhow::anyhow!("max は 100–{} で指定してください: {}", MAX_VOLUME_LIMIT, self.max));
+        }
+        validate_duration("fade_in", self.fade_in)?;
+        validate_duration("fade_out", self.fade_out)
+    }
+
+    /// 音量が上限までに収まっているか確認する
+    pub fn check_volume(&self, volume: f64) -> Result<()> {
+        if !volume.is_finite() || !(0.0..=self.max).contains(&volume) {
+            return Err(anyhow::anyhow!("volume は 0–{} で指定してください: {}", self.max, volume));
+        }
+        Ok(())
+    }
+}
+
+/// フェードの時間（秒）を確認する
+pub fn validate_duration(name: &str, seconds: f64) -> Result<()> {
+    if !seconds.is_finite() || !(0.0..=MAX_FADE).contains(&seconds) {
+        return Err(anyhow::anyhow!("{} は 0–{} 秒で指定してください: {}", name, MAX_FADE, seconds));
+    }
+    Ok(())
+}
+
+// ─── フェード ────────────────────────────────────────────────────────────────
+
+/// 進行中の音量フェード（ディスパッチャのコールバックで少しずつ進める）
+#[derive(Debug, Clone)]
+pub struct VolumeFade {
+    from: f64,
+    to: f64,
+    curve: FadeCurve,
+    started: Instant,
+    duration: Duration,
+}
+
+impl VolumeFade {
+    pub fn new(from: f64, to: f64, duration: f64, curve: FadeCurve) -> Self {
+        Self {
+            from,
+            to,
+            curve,
+            started: Instant::now(),
+            duration: Duration::from_secs_f64(duration),
+        }
+    }
+
+    /// now 時点の音量と、フェードが終わったか
+    pub fn volume_at(&self, now: Instant) -> (f64, bool) {
+        let elapsed = now.duration_since(self.started);
+        if elapsed >= self.duration {
+            return (self.to, true);
+        }
+        let t = elapsed.as_secs_f64() / self.duration.as_secs_f64();
+        (self.curve.interpolate(self.from, self.to, t).max(0.0), false)
+    }
+    /// now 時点で mpv に設定し直す音量と、フェードが終わったか
+    ///
+    /// 現在の音量 current からの変化が `MIN_STEP` 未満の間は設定し直さない（None）
+    /// （volume の変更イベントが増えないように）。終わったときは必ず最後の音量を返す。
+    pub fn step(&self, now: Instant, current: f64) -> (Option<f64>, bool) {
+        let (volume, done) = self.volume_at(now);
+        let changed = done || (volume - current).abs() >= MIN_STEP;
+        (changed.then_some(volume), done)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURVES: [FadeCurve; 4] = [FadeCurve::Linear, FadeCurve::Logarithmic, FadeCurve::EqualPower, FadeCurve::SCurve];
+
+    fn fade(from: f64, to: f64, seconds: f64, curve: FadeCurve) -> VolumeFade {
+        VolumeFade::new(from, to, seconds, curve)
+    }
+
+    #[test]
+    fn curves_start_and_end_at_the_endpoints() {
+        for curve in CURVES {
+            for (from, to) in [(0.0, 100.0), (100.0, 0.0), (30.0, 130.0)] {
+                assert!((curve.interpolate(from, to, 0.0) - from).abs() < 1e-9, "{:?}", curve);
+                assert!((curve.interpolate(from, to, 1.0) - to).abs() < 1e-9, "{:?}", curve);
+            }
+        }
+    }
+
+    #[test]
+    fn curves_move_monotonically() {
+        for curve in CURVES {
+            for (from, to) in [(0.0, 100.0), (100.0, 0.0)] {
+                let mut previous = from;
+                for step in 1..=100 {
+                    let volume = curve.interpolate(from, to, step as f64 / 100.0);
+                    let toward = if to > from { volume >= previous } else { volume <= previous };
+                    assert!(toward, "{:?} {} → {} が {} で逆戻りしています", curve, from, to, step);
+                    previous = volume;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn curve_shapes_at_the_midpoint() {
+        assert_eq!(FadeCurve::Linear.interpolate(0.0, 100.0, 0.5), 50.0);
+        assert_eq!(FadeCurve::SCurve.interpolate(0.0, 100.0, 0.5), 50.0);
+        // 等パワーは上げるときに速く立ち上がり、下げるときは最後に速く下がる
+        assert!(FadeCurve::EqualPower.interpolate(0.0, 100.0, 0.5) > 70.0);
+        assert!(FadeCurve::EqualPower.interpolate(100.0, 0.0, 0.5) > 70.0);
+        // dB で半分（100% → 50% の dB の中間）
+        let half = FadeCurve::Logarithmic.interpolate(100.0, 50.0, 0.5);
+        assert!((volume_to_db(half) - volume_to_db(50.0) / 2.0).abs() < 1e-9);
+        // 範囲外の進み具合は端に丸める
+        assert_eq!(FadeCurve::Linear.interpolate(0.0, 100.0, 1.5), 100.0);
+    }
+
+    #[test]
+    fn converts_between_volume_and_db() {
+        assert_eq!(volume_to_db(100.0), 0.0);
+        assert!((volume_to_db(50.0) - 60.0 * 0.5f64.log10()).abs() < 1e-9);
+        assert_eq!(volume_to_db(0.0), MIN_DB);
+        assert_eq!(db_to_volume(MIN_DB), 0.0);
+        assert!((db_to_volume(volume_to_db(37.0)) - 37.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fade_follows_elapsed_time() {
+        let fade = fade(0.0, 100.0, 2.0, FadeCurve::Linear);
+        let start = fade.started;
+        assert_eq!(fade.volume_at(start), (0.0, false));
+        let (volume, done) = fade.volume_at(start + Duration::from_millis(500));
+        assert!((volume - 25.0).abs() < 1e-9 && !done);
+        assert_eq!(fade.volume_at(start + Duration::from_secs(2)), (100.0, true));
+        assert_eq!(fade.volume_at(start + Duration::from_secs(5)), (100.0, true));
+    }
+
+    #[test]
+    fn steps_skip_changes_below_min_step() {
+        // 10 秒で 1% しか変わらないフェードを 10 ms ごとに進める
+        let fade = fade(50.0, 51.0, 10.0, FadeCurve::Linear);
+        let start = fade.started;
+        let mut current = 50.0;
+        let mut sets = Vec::new();
+        for tick in 1..=1000 {
+            let now = start + Duration::from_millis(10 * tick);
+            let (volume, done) = fade.step(now, current);
+            if let Some(volume) = volume {
+                assert!(done || (volume - current).abs() >= MIN_STEP);
+                current = volume;
+                sets.push(now.duration_since(start));
+            }
+        }
+        // MIN_STEP（0.1%）ごと = 約 1 秒（+ 1 tick 以内）ごとに設定する
+        assert_eq!(sets.len(), 10);
+        for pair in sets[..9].windows(2) {
+            let gap = pair[1] - pair[0];
+            assert!(gap >= Duration::from_millis(990) && gap <= Duration::from_millis(1020), "{:?}", sets);
+        }
+        // 最後は変化が小さくても終わった時点で必ず終点に合わせる
+        assert_eq!(sets[9], Duration::from_secs(10));
+        assert_eq!(current, 51.0);
+    }
+
+    #[test]
+    fn zero_length_fade_finishes_immediately() {
+        let fade = fade(80.0, 0.0, 0.0, FadeCurve::SCurve);
+        assert_eq!(fade.step(fade.started, 80.0), (Some(0.0), true));
+    }
+
+    #[test]
+    fn validates_settings() {
+        assert!(VolumeSettings::default().validate().is_ok());
+        assert!(VolumeSettings { max: 99.0, ..Default::default() }.validate().is_err());
+        assert!(VolumeSettings { fade_in: MAX_FADE + 1.0, ..Default::default() }.validate().is_err());
+        let settings = VolumeSettings::default();
+        assert!(settings.check_volume(settings.max).is_ok());
+        assert!(settings.check_volume(settings.max + 1.0).is_err());
+        assert!(settings.check_volume(f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,617 @@
+/// ストリーム障害の検知と自動復旧
+///
+/// ## 検知する障害
+/// - END_FILE（reason=error）: ストリームの切断・yt-dlp の失敗など
+/// - END_FILE（reason=eof）が長さの途中で届いた: 接続が切れて mpv がファイルの終わりとみなした場合
+/// - Stalled: キャッシュ不足や再生位置の停止が続いている（status.rs が判定）
+/// - 出力スレッドの描画失敗が続いている / フレームが止まった
+/// - 復旧のための再読み込みが一定時間 Loading のまま終わらない
+///
+/// ## 復旧手順
+/// 1. 指数バックオフ（1s, 2s, 4s, ... 最大 30s）で待つ
+/// 2. 元の URL を loadfile し直す（mpv の ytdl_hook が yt-dlp で URL を再解決する）
+/// 3. 直前の time-pos から再開する（ライブ配信など duration がない場合は先頭から）
+/// 4. 再生が一定時間安定したら試行回数をリセットする
+///
+/// キューでクリップが切り替わった場合は、前のクリップの再生位置や復旧の状態をすべて捨てる。
+///
+/// 各段階で `PlayerEvent::Recovery` を EventBus に注入し、UI と購読者に通知する。
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::events::{EndFileReason, EventBus, PlayerEvent, PropertyChange, RecoveryPhase};
+use super::{PlayStatus, PlayerInner};
+use crate::output::OutputStats;
+
+/// 最大試行回数（これを超えたら諦める）
+const MAX_ATTEMPTS: u32 = 8;
+/// 初回のバックオフ
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// バックオフの上限
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// 再生中に出力フレームがこの時間増えなければ出力停止とみなす
+const FRAME_TIMEOUT: Duration = Duration::from_secs(5);
+/// 再読み込み後、この時間 Loading のままなら失敗とみなす
+const LOAD_TIMEOUT: Duration = Duration::from_secs(45);
+/// 再読み込み後、この時間再生が続いたら復旧完了とみなす
+const STABLE_AFTER: Duration = Duration::from_secs(10);
+/// EOF の位置が長さよりこれ以上手前なら、途中で切れたとみなす（秒）
+const PREMATURE_EOF_MARGIN: f64 = 2.0;
+
+/// ウォッチドッグスレッドを起動する（再生セッションごとに 1 つ）
+///
+/// セッションが切り替わる（stop / 次の play）か mpv が終了するとスレッドは終了する。
+pub(super) fn spawn(
+    inner: Arc<Mutex<PlayerInner>>,
+    session: u64,
+    bus: EventBus,
+    stats: Arc<OutputStats>,
+) {
+    let events = bus.subscribe();
+
+    std::thread::spawn(move || {
+        log::info!("ウォッチドッグを開始しました (session={})", session);
+        let mut watchdog = Watchdog::new(bus, stats);
+
+        loop {
+            match events.recv_timeout(Duration::from_millis(500)) {
+                Ok(PlayerEvent::Shutdown) | Err(RecvTimeoutError::Disconnected) => break,
+                Ok(event) => watchdog.on_event(&event),
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+
+            let Ok(mut guard) = inner.lock() else { break };
+            if guard.session != session {
+                break;
+            }
+            watchdog.check(&mut guard, Instant::now());
+        }
+        log::info!("ウォッチドッグを終了しました (session={})", session);
+    });
+}
+
+struct Watchdog {
+    bus: EventBus,
+    stats: Arc<OutputStats>,
+    /// 現在の復旧試行回数（0 = 正常）
+    attempt: u32,
+    /// 予約中の再読み込み（実行時刻と理由）
+    pending: Option<(Instant, String)>,
+    /// 最後に再読み込みした時刻
+    reloaded_at: Option<Instant>,
+    /// 再読み込み後に再生が再開した時刻
+    restarted_at: Option<Instant>,
+    /// 最大試行回数に達して諦めた
+    gave_up: bool,
+    /// 直前に観測した正常な再生位置
+    last_pos: f64,
+    /// 直前に観測した出力フレーム数とその時刻
+    last_frames: (u64, Instant),
+    /// 直近に届いた障害イベント（次の check で処理する）
+    failure: Option<String>,
+    /// END_FILE（reason=eof）が届いた（長さと比べて途中で切れたかを次の check で判定する）
+    eof: bool,
+    /// 直前に確認したクリップの切り替え回数（`PlayerInner::clip`）
+    clip: u64,
+}
+
+impl Watchdog {
+    fn new(bus: EventBus, stats: Arc<OutputStats>) -> Self {
+        let frames = stats.frame_count();
+        Self {
+            bus,
+            stats,
+            attempt: 0,
+            pending: None,
+            reloaded_at: None,
+            restarted_at: None,
+            gave_up: false,
+            last_pos: 0.0,
+            last_frames: (frames, Instant::now()),
+            failure: None,
+            eof: false,
+            clip: 0,
+        }
+    }
+
+    fn on_event(&mut self, event: &PlayerEvent) {
+        match event {
+            PlayerEvent::PropertyChange(PropertyChange::TimePos(pos)) if *pos > 0.0 => {
+                self.last_pos = *pos;
+            }
+            PlayerEvent::EndFile { reason: EndFileReason::Error, error } => {
+                self.failure = Some(format!(
+                    "end-file: {}",
+                    error.as_deref().unwrap_or("不明なエラー")
+                ));
+            }
+            PlayerEvent::EndFile { reason: EndFileReason::Eof, .. } => self.eof = true,
+            PlayerEvent::PlaybackRestart if self.reloaded_at.is_some() => {
+                self.restarted_at = Some(Instant::now());
+            }
+            _ => {}
+        }
+    }
+
+    /// 障害の有無を確認し、必要なら再読み込みを予約・実行する
+    fn check(&mut self, inner: &mut PlayerInner, now: Instant) {
+        if inner.clip != self.clip {
+            self.clip = inner.clip;
+            self.clip_switched(now);
+        }
+        let status = inner.status.status().clone();
+        if let Some(reason) = self.advance(&status, inner.props.duration, now) {
+            self.reload(inner, &reason, now);
+        }
+    }
+
+    /// 障害の検知と再読み込みの予約を進める。予約した再読み込みの時刻になったらその理由を返す
+    fn advance(&mut self, status: &PlayStatus, duration: f64, now: Instant) -> Option<String> {
+
+        // 出力フレームの進み具合を記録（再生中以外はフレームが止まって当然なので時刻だけ進める）
+        let frames = self.stats.frame_count();
+        if frames != self.last_frames.0 || *status != PlayStatus::Playing {
+            self.last_frames = (frames, now);
+        }
+
+        // 再読み込み後に再生が安定したら復旧完了
+        if let Some(restarted) = self.restarted_at {
+            if *status == PlayStatus::Playing && now.duration_since(restarted) >= STABLE_AFTER {
+                log::info!("ストリームが復旧しました (試行 {} 回)", self.attempt);
+                self.emit(RecoveryPhase::Recovered, Duration::ZERO, "stable");
+                self.attempt = 0;
+                self.reloaded_at = None;
+                self.restarted_at = None;
+                self.gave_up = false;
+            }
+        }
+
+        if std::mem::take(&mut self.eof) && self.failure.is_none() {
+            // ライブ配信（duration なし）は終わりが分からないため、配信の終了として扱う
+            if duration > 0.0 && self.last_pos < duration - PREMATURE_EOF_MARGIN {
+                self.failure = Some(format!("premature-eof: {:.1}s / {:.1}s", self.last_pos, duration));
+            }
+        }
+
+        if let Some(reason) = self.failure.take().or_else(|| self.detect(status, now)) {
+            self.schedule(reason, now);
+        }
+
+        // 予約済みの再読み込みの時刻になったか
+        match self.pending.take() {
+            Some((due, reason)) if now >= due => Some(reason),
+            pending => {
+                self.pending = pending;
+                None
+            }
+        }
+    }
+
+    /// イベント以外の条件（ステータス・出力フレーム）から障害を検知する
+    fn detect(&self, status: &PlayStatus, now: Instant) -> Option<String> {
+        match status {
+            PlayStatus::Stalled => return Some("stalled".to_string()),
+            PlayStatus::Loading => {
+                if let Some(reloaded) = self.reloaded_at {
+                    if self.restarted_at.is_none() && now.duration_since(reloaded) >= LOAD_TIMEOUT {
+                        return Some("load-timeout".to_string());
+                    }
+                }
+            }
+            PlayStatus::Playing => {
+                if self.stats.render_failed() {
+                    return Some("render-failed".to_string());
+                }
+                // 出力スレッドが一度もフレームを出していない環境（Syphon 無効など）では判定しない
+                let (frames, since) = self.last_frames;
+                if frames > 0 && now.duration_since(since) >= FRAME_TIMEOUT {
+                    return Some("no-frames".to_string());
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn schedule(&mut self, reason: String, now: Instant) {
+        if self.pending.is_some() || self.gave_up {
+            return;
+        }
+        // 再読み込み直後の Loading 中は同じ障害を二重に数えない
+        if let Some(reloaded) = self.reloaded_at {
+            if self.restarted_at.is_none() && reason != "load-timeout" && !reason.starts_with("end-file")
+                && !reason.starts_with("premature-eof")
+                && now.duration_since(reloaded) < LOAD_TIMEOUT
+            {
+                return;
+            }
+        }
+
+        if self.attempt >= MAX_ATTEMPTS {
+            log::error!("ストリームの復旧を諦めます（{} 回失敗）: {}", self.attempt, reason);
+            self.gave_up = true;
+            self.emit(RecoveryPhase::GaveUp, Duration::ZERO, &reason);
+            return;
+        }
+
+        self.attempt += 1;
+        let delay = backoff(self.attempt);
+        log::warn!(
+            "ストリーム障害を検知: {}（{} 回目の復旧を {:?} 後に実行）",
+            reason, self.attempt, delay
+        );
+        self.emit(RecoveryPhase::Scheduled, delay, &reason);
+        self.pending = Some((now + delay, reason));
+    }
+
+    fn reload(&mut self, inner: &mut PlayerInner, reason: &str, now: Instant) {
+        let (Some(mpv), Some(url)) = (inner.mpv.as_ref(), inner.current_url.clone()) else {
+            return;
+        };
+        // ライブ配信（duration なし）は先頭 = 最新位置から再開する
+        let start = if inner.props.duration > 0.0 { self.last_pos } else { 0.0 };
+
+        log::info!("ストリームを再読み込みします: {} (開始位置 {:.1}s)", url, start);
+        if let Err(e) = mpv.reload_at(&url, start) {
+            log::error!("再読み込みに失敗: {}", e);
+        }
+        self.reloaded(reason, now);
+    }
+
+    /// クリップが切り替わった（前のクリップの位置から再開したり、試行回数を引き継いだりしない）
+    fn clip_switched(&mut self, now: Instant) {
+        self.attempt = 0;
+        self.pending = None;
+        self.reloaded_at = None;
+        self.restarted_at = None;
+        self.gave_up = false;
+        self.last_pos = 0.0;
+        self.last_frames = (self.stats.frame_count(), now);
+        self.failure = None;
+        self.eof = false;
+    }
+
+    /// 再読み込みを実行したことを記録する
+    fn reloaded(&mut self, reason: &str, now: Instant) {
+        self.stats.set_render_failed(false);
+        self.reloaded_at = Some(now);
+        self.restarted_at = None;
+        self.last_frames = (self.stats.frame_count(), now);
+        self.emit(RecoveryPhase::Reloading, Duration::ZERO, reason);
+    }
+
+    fn emit(&self, phase: RecoveryPhase, delay: Duration, reason: &str) {
+        self.bus.inject(PlayerEvent::Recovery {
+            phase,
+            attempt: self.attempt,
+            delay_ms: delay.as_millis() as u64,
+            reason: reason.to_string(),
+        });
+    }
+}
+
+/// 試行回数に応じたバックオフ時間（1s, 2s, 4s, ... 最大 MAX_BACKOFF）
+fn backoff(attempt: u32) -> Duration {
+    let factor = 1u32 << attempt.saturating_sub(1).min(16);
+    (INITIAL_BACKOFF * factor).min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn watchdog() -> Watchdog {
+        Watchdog::new(EventBus::new(), Arc::new(OutputStats::default()))
+    }
+
+    fn end_file_error() -> PlayerEvent {
+        PlayerEvent::EndFile { reason: EndFileReason::Error, error: Some("connection reset".to_string()) }
+    }
+
+    /// 障害を届けて予約させ、予約時刻に再読み込みしたことにする
+    fn fail_and_reload(watchdog: &mut Watchdog, now: Instant) -> Instant {
+        watchdog.on_event(&end_file_error());
+        assert_eq!(watchdog.advance(&PlayStatus::Loading, 0.0, now), None);
+        let (due, _) = watchdog.pending.clone().expect("再読み込みが予約されていません");
+        let reason = watchdog.advance(&PlayStatus::Loading, 0.0, due).expect("予約時刻に再読み込みしません");
+        watchdog.reloaded(&reason, due);
+        due
+    }
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let seconds: Vec<u64> = (1..=7).map(|attempt| backoff(attempt).as_secs()).collect();
+        assert_eq!(seconds, vec![1, 2, 4, 8, 16, 30, 30]);
+        assert_eq!(backoff(0), INITIAL_BACKOFF);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn schedules_with_backoff_and_reloads_when_due() {
+        let mut watchdog = watchdog();
+        let now = Instant::now();
+        watchdog.on_event(&end_file_error());
+        assert_eq!(watchdog.advance(&PlayStatus::Loading, 0.0, now), None);
+        assert_eq!(watchdog.attempt, 1);
+        let (due, reason) = watchdog.pending.clone().unwrap();
+        assert_eq!(due, now + INITIAL_BACKOFF);
+        assert!(reason.starts_with("end-file"));
+
+        assert_eq!(watchdog.advance(&PlayStatus::Loading, 0.0, due - Duration::from_millis(1)), None);
+        assert_eq!(watchdog.advance(&PlayStatus::Loading, 0.0, due), Some(reason));
+        assert!(watchdog.pending.is_none());
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let mut watchdog = watchdog();
+        let mut now = Instant::now();
+        for _ in 0..MAX_ATTEMPTS {
+            now = fail_and_reload(&mut watchdog, now);
+        }
+        assert_eq!(watchdog.attempt, MAX_ATTEMPTS);
+        assert!(!watchdog.gave_up);
+
+        watchdog.on_event(&end_file_error());
+        assert_eq!(watchdog.advance(&PlayStatus::Loading, 0.0, now), None);
+        assert!(watchdog.gave_up);
+        assert!(watchdog.pending.is_none());
+
+        // 諦めた後は障害が続いても予約しない
+        watchdog.on_event(&end_file_error());
+        watchdog.advance(&PlayStatus::Loading, 0.0, now + MAX_BACKOFF);
+        assert!(watchdog.pending.is_none());
+        assert_eq!(watchdog.attempt, MAX_ATTEMPTS);
+    }
+
+    #[test]
+    fn frame_timeout_while_playing() {
+        let mut watchdog = watchdog();
+        watchdog.stats.record_frame();
+        let now = Instant::now();
+        watchdog.advance(&PlayStatus::Playing, 60.0, now);
+        watchdog.advance(&PlayStatus::Playing, 60.0, now + FRAME_TIMEOUT - Duration::from_millis(1));
+        assert!(watchdog.pending.is_none());
+
+        watchdog.advance(&PlayStatus::Playing, 60.0, now + FRAME_TIMEOUT);
+        assert_eq!(watchdog.pending.as_ref().map(|(_, reason)| reason.as_str()), Some("no-frames"));
+    }
+
+    #[test]
+    fn frozen_frame_counter_is_detected() {
+        let mut watchdog = watchdog();
+        let start = Instant::now();
+        let tick = Duration::from_millis(500);
+
+        // フレームが進んでいる間は何秒再生しても障害にしない
+        let mut now = start;
+        while now < start + FRAME_TIMEOUT * 3 {
+            watchdog.stats.record_frame();
+            watchdog.advance(&PlayStatus::Playing, 60.0, now);
+            assert_eq!(watchdog.detect(&PlayStatus::Playing, now), None);
+            now += tick;
+        }
+        assert!(watchdog.pending.is_none());
+
+        // 描画は続いていても新しいフレームが来なければ、最後のフレームから FRAME_TIMEOUT 後に no-frames
+        let frozen = now - tick;
+        while now < frozen + FRAME_TIMEOUT {
+            watchdog.advance(&PlayStatus::Playing, 60.0, now);
+            now += tick;
+        }
+        assert!(watchdog.pending.is_none());
+        assert_eq!(watchdog.detect(&PlayStatus::Playing, now), Some("no-frames".to_string()));
+        watchdog.advance(&PlayStatus::Playing, 60.0, now);
+        assert_eq!(watchdog.pending.as_ref().map(|(_, reason)| reason.as_str()), Some("no-frames"));
+    }
+
+    #[test]
+    fn no_frame_timeout_without_output_or_when_paused() {
+        // 出力スレッドが一度もフレームを出していない場合は判定しない
+        let mut watchdog = watchdog();
+        let now = Instant::now();
+        watchdog.advance(&PlayStatus::Playing, 60.0, now);
+        watchdog.advance(&PlayStatus::Playing, 60.0, now + FRAME_TIMEOUT * 2);
+        assert!(watchdog.pending.is_none());
+
+        // 一時停止中はフレームが止まって当然
+        let mut watchdog = self::watchdog();
+        watchdog.stats.record_frame();
+        watchdog.advance(&PlayStatus::Paused, 60.0, now);
+        watchdog.advance(&PlayStatus::Paused, 60.0, now + FRAME_TIMEOUT * 2);
+        assert!(watchdog.pending.is_none());
+    }
+
+    #[test]
+    fn render_failure_while_playing() {
+        let mut watchdog = watchdog();
+        watchdog.stats.set_render_failed(true);
+        watchdog.advance(&PlayStatus::Playing, 60.0, Instant::now());
+        assert_eq!(watchdog.pending.as_ref().map(|(_, reason)| reason.as_str()), Some("render-failed"));
+    }
+
+    #[test]
+    fn load_timeout_after_reload() {
+        let mut watchdog = watchdog();
+        let reloaded = fail_and_reload(&mut watchdog, Instant::now());
+
+        // 読み込み中のストールは再読み込みの結果を待つ間なので数えない
+        watchdog.advance(&PlayStatus::Stalled, 0.0, reloaded + Duration::from_secs(1));
+        assert!(watchdog.pending.is_none());
+        watchdog.advance(&PlayStatus::Loading, 0.0, reloaded + LOAD_TIMEOUT - Duration::from_millis(1));
+        assert!(watchdog.pending.is_none());
+
+        watchdog.advance(&PlayStatus::Loading, 0.0, reloaded + LOAD_TIMEOUT);
+        assert_eq!(watchdog.pending.as_ref().map(|(_, reason)| reason.as_str()), Some("load-timeout"));
+        assert_eq!(watchdog.attempt, 2);
+    }
+
+    #[test]
+    fn stable_playback_resets_attempts() {
+        let mut watchdog = watchdog();
+        let now = fail_and_reload(&mut watchdog, Instant::now());
+        let now = fail_and_reload(&mut watchdog, now);
+        assert_eq!(watchdog.attempt, 2);
+
+        watchdog.on_event(&PlayerEvent::PlaybackRestart);
+        let restarted = watchdog.restarted_at.expect("再開時刻が記録されていません");
+        watchdog.advance(&PlayStatus::Playing, 60.0, restarted + STABLE_AFTER - Duration::from_millis(1));
+        assert_eq!(watchdog.attempt, 2);
+
+        watchdog.advance(&PlayStatus::Playing, 60.0, restarted.max(now) + STABLE_AFTER);
+        assert_eq!(watchdog.attempt, 0);
+        assert!(watchdog.reloaded_at.is_none());
+        assert!(watchdog.restarted_at.is_none());
+
+        // リセット後の障害は 1 回目のバックオフから数え直す
+        watchdog.on_event(&end_file_error());
+        let later = restarted.max(now) + STABLE_AFTER * 2;
+        watchdog.advance(&PlayStatus::Playing, 60.0, later);
+        assert_eq!(watchdog.attempt, 1);
+        assert_eq!(watchdog.pending.as_ref().map(|(due, _)| *due), Some(later + INITIAL_BACKOFF));
+    }
+
+    #[test]
+    fn premature_eof_is_a_failure() {
+        let mut watchdog = watchdog();
+        watchdog.on_event(&PlayerEvent::PropertyChange(PropertyChange::TimePos(12.0)));
+        watchdog.on_event(&PlayerEvent::EndFile { reason: EndFileReason::Eof, error: None });
+        watchdog.advance(&PlayStatus::Ended, 60.0, Instant::now());
+        let reason = watchdog.pending.as_ref().map(|(_, reason)| reason.clone()).unwrap_or_default();
+        assert!(reason.starts_with("premature-eof"), "{}", reason);
+    }
+
+    #[test]
+    fn eof_at_the_end_or_live_is_not_a_failure() {
+        let mut watchdog = watchdog();
+        watchdog.on_event(&PlayerEvent::PropertyChange(PropertyChange::TimePos(59.5)));
+        watchdog.on_event(&PlayerEvent::EndFile { reason: EndFileReason::Eof, error: None });
+        watchdog.advance(&PlayStatus::Ended, 60.0, Instant::now());
+        assert!(watchdog.pending.is_none());
+
+        // ライブ配信（duration なし）の終了
+        let mut watchdog = self::watchdog();
+        watchdog.on_event(&PlayerEvent::PropertyChange(PropertyChange::TimePos(5.0)));
+        watchdog.on_event(&PlayerEvent::EndFile { reason: EndFileReason::Eof, error: None });
+        watchdog.advance(&PlayStatus::Ended, 0.0, Instant::now());
+        assert!(watchdog.pending.is_none());
+    }
+
+    #[test]
+    fn clip_switch_discards_previous_clip_state() {
+        let mut watchdog = watchdog();
+        watchdog.on_event(&PlayerEvent::PropertyChange(PropertyChange::TimePos(95.0)));
+        let now = fail_and_reload(&mut watchdog, Instant::now());
+        watchdog.on_event(&end_file_error());
+        watchdog.advance(&PlayStatus::Loading, 120.0, now);
+        assert!(watchdog.pending.is_some());
+
+        watchdog.clip_switched(now);
+        assert_eq!(watchdog.attempt, 0);
+        assert!(watchdog.pending.is_none());
+        assert!(watchdog.reloaded_at.is_none());
+        // 新しいクリップの障害は先頭から再読み込みし、1 回目のバックオフから数える
+        assert_eq!(watchdog.last_pos, 0.0);
+        watchdog.on_event(&end_file_error());
+        watchdog.advance(&PlayStatus::Playing, 30.0, now);
+        assert_eq!(watchdog.attempt, 1);
+        assert_eq!(watchdog.pending.as_ref().map(|(due, _)| *due), Some(now + INITIAL_BACKOFF));
+    }
+
+    // ─── 実際の mpv を使う確認 ──────────────────────────────────────────────────
+
+    /// ヘッダーでは 10 秒と伝えた WAV を 2 秒分だけ送って接続を切る HTTP サーバー
+    fn serve_truncated_wav(listener: std::net::TcpListener) {
+        use std::io::{Read, Write};
+
+        const RATE: u32 = 8000;
+        let data_len = RATE * 2 * 10;
+        let mut header = Vec::new();
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&(36 + data_len).to_le_bytes());
+        header.extend_from_slice(b"WAVEfmt ");
+        header.extend_from_slice(&16u32.to_le_bytes());
+        header.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        header.extend_from_slice(&1u16.to_le_bytes()); // モノラル
+        header.extend_from_slice(&RATE.to_le_bytes());
+        header.extend_from_slice(&(RATE * 2).to_le_bytes());
+        header.extend_from_slice(&2u16.to_le_bytes());
+        header.extend_from_slice(&16u16.to_le_bytes());
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&data_len.to_le_bytes());
+
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            // リクエストヘッダーを読み捨てる
+            let mut request = Vec::new();
+            let mut byte = [0u8; 1];
+            while !request.ends_with(b"\r\n\r\n") && stream.read(&mut byte).map(|n| n == 1).unwrap_or(false) {
+                request.push(byte[0]);
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: audio/wav\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                header.len() as u32 + data_len
+            );
+            let _ = stream.write_all(response.as_bytes());
+            let _ = stream.write_all(&header);
+            let _ = stream.write_all(&vec![0u8; (RATE * 2 * 2) as usize]);
+            // 残りを送らずに切断する
+        }
+    }
+
+    /// 配信の途中で接続が切れたとき、mpv のイベントから障害を検知して再読み込みを予約する
+    ///
+    /// libmpv が必要なため既定では実行しない（`cargo test -- --ignored` で実行する）。
+    #[test]
+    #[ignore]
+    fn schedules_recovery_when_server_drops_mid_stream() {
+        use crate::player::events;
+        use crate::player::status::StatusMachine;
+        use crate::player::MpvContext;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/clip.wav", listener.local_addr().unwrap());
+        std::thread::spawn(move || serve_truncated_wav(listener));
+
+        let ctx = MpvContext::new(&url, None).unwrap();
+        ctx.mpv.set_property("ytdl", false).unwrap();
+        ctx.mpv.set_property("vo", "null").unwrap();
+        ctx.mpv.set_property("ao", "null").unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        events::spawn(ctx.mpv_handle_ptr(), EventBus::new(), None, move |event| {
+            if let Some(event) = event {
+                let _ = tx.send(event.clone());
+            }
+        })
+        .unwrap();
+        ctx.reload_at(&url, 0.0).unwrap();
+
+        let mut machine = StatusMachine::new();
+        machine.set(PlayStatus::Loading);
+        let mut watchdog = watchdog();
+        let mut duration = 0.0;
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while Instant::now() < deadline {
+            match rx.recv_timeout(Duration::from_millis(200)) {
+                Ok(event) => {
+                    if let PlayerEvent::PropertyChange(PropertyChange::Duration(d)) = event {
+                        duration = d;
+                    }
+                    machine.on_event(&event);
+                    watchdog.on_event(&event);
+                }
+                Err(_) => {
+                    machine.on_tick(Instant::now());
+                }
+            }
+            watchdog.advance(machine.status(), duration, Instant::now());
+            if watchdog.attempt > 0 {
+                let (_, reason) = watchdog.pending.clone().unwrap();
+                log::info!("復旧を予約しました: {}", reason);
+                return;
+            }
+        }
+        panic!("接続が切れても復旧が予約されませんでした (status={:?})", machine.status());
+    }
+}
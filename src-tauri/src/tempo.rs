@@ -0,0 +1,119 @@
+/// ネットワークのテンポ情報（UDP テンポブロードキャスト）の受信
+///
+/// 同じネットワークの送信側（DJ ソフトのブリッジ・リズムマシンなど）が定期的に
+/// 次の JSON を UDP で送る（ブロードキャスト・ユニキャストのどちらでもよい）。
+///
+/// ```json
+/// {"bpm": 128.0, "beat": 1234.5}
+/// ```
+///
+/// - `bpm`: セッションのテンポ
+/// - `beat`: 送信した瞬間のセッションの拍位置（小数。小節の頭が 4 の倍数になるように数える）
+///
+/// 受信した時刻と合わせて記録し、次の受信までは bpm から拍位置を進めて使う。
+/// Ableton Link のセッションにはブリッジ（Link のテンポと拍位置をこの形式で送るもの）を介して参加する。
+use anyhow::Result;
+use serde::Deserialize;
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 既定の受信ポート（Ableton Link の 20808 と重ならないようにする）
+pub const DEFAULT_PORT: u16 = 20809;
+
+/// 受信したテンポ
+#[derive(Debug, Clone, Copy)]
+pub struct TempoReading {
+    pub bpm: f64,
+    /// 受信した瞬間の拍位置
+    pub beat: f64,
+    pub received: Instant,
+}
+
+impl TempoReading {
+    /// now 時点の拍位置
+    pub fn beat_at(&self, now: Instant) -> f64 {
+        self.beat + now.duration_since(self.received).as_secs_f64() * self.bpm / 60.0
+    }
+}
+
+#[derive(Deserialize)]
+struct TempoPacket {
+    bpm: f64,
+    beat: f64,
+}
+
+/// 1 パケットを解析する（壊れたパケット・範囲外のテンポは None）
+pub fn parse_packet(packet: &[u8]) -> Option<TempoReading> {
+    let packet: TempoPacket = serde_json::from_slice(packet).ok()?;
+    let valid = packet.bpm.is_finite() && (20.0..=999.0).contains(&packet.bpm) && packet.beat.is_finite();
+    valid.then(|| TempoReading { bpm: packet.bpm, beat: packet.beat, received: Instant::now() })
+}
+
+/// port で受信し、テンポを tx に送るスレッドを起動する
+pub fn spawn_receiver(port: u16, tx: Sender<TempoReading>, stop: Arc<AtomicBool>) -> Result<()> {
+    let socket = bind(port)?;
+    // stop フラグを確認するために定期的に受信を抜ける
+    socket.set_read_timeout(Some(Duration::from_millis(200)))?;
+
+    std::thread::spawn(move || {
+        log::info!("テンポの受信を開始しました (port={})", port);
+        let mut buffer = [0u8; 1024];
+        while !stop.load(Ordering::Relaxed) {
+            let Ok((len, _)) = socket.recv_from(&mut buffer) else { continue };
+            match parse_packet(&buffer[..len]) {
+                Some(reading) => {
+                    if tx.send(reading).is_err() {
+                        break;
+                    }
+                }
+                None => log::debug!("テンポのパケットを解析できません ({} バイト)", len),
+            }
+        }
+        log::info!("テンポの受信を終了しました");
+    });
+    Ok(())
+}
+
+/// 止めた直前の受信スレッドがポートを閉じるまで（読み取りのタイムアウト分）待って開き直す
+fn bind(port: u16) -> Result<UdpSocket> {
+    let mut attempts = 0;
+    loop {
+        match UdpSocket::bind(("0.0.0.0", port)) {
+            Ok(socket) => return Ok(socket),
+            Err(e) if attempts < 5 => {
+                log::debug!("テンポの受信ポート {} を開き直します: {}", port, e);
+                attempts += 1;
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            Err(e) => return Err(anyhow::anyhow!("テンポの受信ポート {} を開けません: {}", port, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tempo_packets() {
+        let reading = parse_packet(br#"{"bpm": 128.0, "beat": 1234.5}"#).unwrap();
+        assert_eq!((reading.bpm, reading.beat), (128.0, 1234.5));
+        // 範囲外のテンポ・壊れたパケットは使わない
+        assert!(parse_packet(br#"{"bpm": 10.0, "beat": 0.0}"#).is_none());
+        assert!(parse_packet(br#"{"bpm": 1000.0, "beat": 0.0}"#).is_none());
+        assert!(parse_packet(br#"{"bpm": 120.0}"#).is_none());
+        assert!(parse_packet(b"bpm=120").is_none());
+    }
+
+    #[test]
+    fn advances_beat_from_bpm() {
+        let received = Instant::now();
+        let reading = TempoReading { bpm: 120.0, beat: 8.0, received };
+        assert_eq!(reading.beat_at(received), 8.0);
+        // 120 BPM は 1 秒に 2 拍進む
+        assert!((reading.beat_at(received + Duration::from_millis(1500)) - 11.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,355 @@
+/// LTC（リニアタイムコード）の受信
+///
+/// オーディオ入力の 1 チャンネルからバイフェーズマーク符号を復号する。
+///
+/// ## 復号の手順
+/// 1. ヒステリシス付きでゼロ交差を検出し、交差の間隔を測る
+/// 2. 1 ビット周期に近い間隔は 0、半周期の間隔 2 つで 1 とする
+///    （ビット周期は 24–30 fps の範囲で受信しながら追従する）
+/// 3. 直近 80 ビットの末尾が同期ワード（0011 1111 1111 1101）になったら 1 フレーム分を読む
+///
+/// フレームの最後のビットを受け取った時点で次のフレームが始まっているため、1 フレーム分を補正する。
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{FrameRate, Timecode, TimecodeReading};
+use crate::error::AppError;
+
+/// 同期ワード（ビット 64–79。先に届くビットを下位に置いた値）
+const SYNC_WORD: u128 = 0xBFFC;
+/// ゼロ交差とみなす振幅（ノイズで交差を誤検出しないため）
+const HYSTERESIS: f32 = 0.02;
+/// 1 フレームの受信完了から次のフレームの開始までの補正（フレーム）
+const FRAME_LEAD: u32 = 1;
+
+/// LTC の復号器
+#[derive(Debug)]
+pub struct LtcDecoder {
+    rate: FrameRate,
+    /// 1 ビットの長さ（サンプル数）の推定値
+    bit_period: f32,
+    min_period: f32,
+    max_period: f32,
+    /// 直前の信号の符号
+    high: bool,
+    /// 直前のゼロ交差からのサンプル数
+    since_edge: u32,
+    /// 半周期の交差を 1 つ受け取った（次の半周期で 1 になる）
+    half_bit: bool,
+    /// 直近 80 ビット（最新のビットがビット 79）
+    bits: u128,
+}
+
+impl LtcDecoder {
+    /// rate は 24 / 25 / 30 fps の区別に使う（ドロップフレームは信号のフラグで判定する）
+    pub fn new(sample_rate: u32, rate: FrameRate) -> Self {
+        let sample_rate = sample_rate as f32;
+        // 24 fps × 80 ビット = 1920 bps 〜 30 fps × 80 ビット = 2400 bps
+        let min_period = sample_rate / 2400.0 * 0.9;
+        let max_period = sample_rate / 1920.0 * 1.1;
+        Self {
+            rate,
+            bit_period: sample_rate / (rate.fps() as f32 * 80.0),
+            min_period,
+            max_period,
+            high: false,
+            since_edge: 0,
+            half_bit: false,
+            bits: 0,
+        }
+    }
+
+    /// サンプルを読み、フレームが揃うたびに on_frame を呼ぶ
+    pub fn feed(&mut self, samples: impl Iterator<Item = f32>, mut on_frame: impl FnMut(TimecodeReading)) {
+        for sample in samples {
+            self.since_edge = self.since_edge.saturating_add(1);
+            let high = if sample > HYSTERESIS {
+                true
+            } else if sample < -HYSTERESIS {
+                false
+            } else {
+                self.high
+            };
+            if high == self.high {
+                continue;
+            }
+            self.high = high;
+            let interval = self.since_edge as f32;
+            self.since_edge = 0;
+            if let Some(reading) = self.edge(interval) {
+                on_frame(reading);
+            }
+        }
+    }
+
+    fn edge(&mut self, interval: f32) -> Option<TimecodeReading> {
+        if interval < self.min_period * 0.3 || interval > self.max_period * 1.5 {
+            // 無信号・ノイズ。ビットの途中から読み直す
+            self.half_bit = false;
+            return None;
+        }
+
+        if interval > self.bit_period * 0.75 {
+            self.track_period(interval);
+            self.half_bit = false;
+            self.push(false)
+        } else {
+            self.track_period(interval * 2.0);
+            if self.half_bit {
+                self.half_bit = false;
+                self.push(true)
+            } else {
+                self.half_bit = true;
+                None
+            }
+        }
+    }
+
+    fn track_period(&mut self, period: f32) {
+        self.bit_period = (self.bit_period * 0.9 + period * 0.1).clamp(self.min_period, self.max_period);
+    }
+
+    fn push(&mut self, bit: bool) -> Option<TimecodeReading> {
+        self.bits = (self.bits >> 1) | ((bit as u128) << 79);
+        if (self.bits >> 64) & 0xFFFF != SYNC_WORD {
+            return None;
+        }
+        let field = |start: u32, len: u32| ((self.bits >> start) & ((1u128 << len) - 1)) as u8;
+        let drop_frame = field(10, 1) == 1;
+        let rate = match (drop_frame, self.rate) {
+            (true, _) => FrameRate::Fps2997Df,
+            (false, FrameRate::Fps2997Df) => FrameRate::Fps30,
+            (false, rate) => rate,
+        };
+        let timecode = Timecode {
+            frames: field(0, 4) + field(8, 2) * 10,
+            seconds: field(16, 4) + field(24, 3) * 10,
+            minutes: field(32, 4) + field(40, 3) * 10,
+            hours: field(48, 4) + field(56, 2) * 10,
+            rate,
+        };
+        timecode.is_valid().then(|| TimecodeReading::new(timecode, FRAME_LEAD))
+    }
+}
+
+/// オーディオ入力デバイスの名前一覧
+pub fn list_inputs() -> Result<Vec<String>> {
+    use cpal::traits::{DeviceTrait, HostTrait};
+    let devices = cpal::default_host()
+        .input_devices()
+        .map_err(|e| anyhow::anyhow!("オーディオ入力を列挙できません: {}", e))?;
+    Ok(devices.filter_map(|device| device.name().ok()).collect())
+}
+
+/// オーディオ入力を開き、LTC を受信するスレッドを起動する
+pub fn spawn(
+    device: Option<&str>,
+    channel: u16,
+    rate: FrameRate,
+    tx: Sender<TimecodeReading>,
+    stop: Arc<AtomicBool>,
+) -> Result<()> {
+    let device_name = device.map(str::to_string);
+    let (opened_tx, opened_rx) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        let stream = match open_stream(device_name.as_deref(), channel, rate, tx) {
+            Ok(stream) => {
+                let _ = opened_tx.send(Ok(()));
+                stream
+            }
+            Err(e) => {
+                let _ = opened_tx.send(Err(e));
+                return;
+            }
+        };
+        log::info!("LTC の受信を開始しました");
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        drop(stream);
+        log::info!("LTC の受信を終了しました");
+    });
+
+    super::wait_opened(opened_rx)
+}
+
+fn open_stream(device: Option<&str>, channel: u16, rate: FrameRate, tx: Sender<TimecodeReading>) -> Result<cpal::Stream> {
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+    let host = cpal::default_host();
+    let device = match device {
+        Some(name) => host
+            .input_devices()
+            .map_err(|e| anyhow::anyhow!("オーディオ入力を列挙できません: {}", e))?
+            .find(|d| d.name().is_ok_and(|n| n == name))
+            .ok_or_else(|| AppError::DeviceNotFound(format!("オーディオ入力: {}", name)))?,
+        None => host
+            .default_input_device()
+            .ok_or_else(|| anyhow::anyhow!("既定のオーディオ入力がありません"))?,
+    };
+    let config = device
+        .default_input_config()
+        .map_err(|e| anyhow::anyhow!("オーディオ入力の設定を取得できません: {}", e))?;
+    let channels = config.channels();
+    if channel >= channels {
+        return Err(anyhow::anyhow!("チャンネル {} はありません（入力は {} チャンネル）", channel, channels));
+    }
+    log::info!(
+        "LTC 入力: {} ({} Hz, {} ch, チャンネル {})",
+        device.name().unwrap_or_default(),
+        config.sample_rate().0,
+        channels,
+        channel
+    );
+
+    let mut decoder = LtcDecoder::new(config.sample_rate().0, rate);
+    let (channels, channel) = (channels as usize, channel as usize);
+    let on_error = |e: cpal::StreamError| log::warn!("LTC 入力のエラー: {}", e);
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::F32 => device.build_input_stream(
+            &config.into(),
+            move |data: &[f32], _: &_| {
+                let samples = data.iter().skip(channel).step_by(channels).copied();
+                decoder.feed(samples, |reading| {
+                    let _ = tx.send(reading);
+                });
+            },
+            on_error,
+            None,
+        ),
+        cpal::SampleFormat::I16 => device.build_input_stream(
+            &config.into(),
+            move |data: &[i16], _: &_| {
+                let samples = data.iter().skip(channel).step_by(channels).map(|s| *s as f32 / 32768.0);
+                decoder.feed(samples, |reading| {
+                    let _ = tx.send(reading);
+                });
+            },
+            on_error,
+            None,
+        ),
+        format => return Err(anyhow::anyhow!("対応していないサンプル形式です: {:?}", format)),
+    }
+    .map_err(|e| anyhow::anyhow!("オーディオ入力を開けません: {}", e))?;
+    stream
+        .play()
+        .map_err(|e| anyhow::anyhow!("オーディオ入力を開始できません: {}", e))?;
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48_000;
+
+    fn timecode(hours: u8, minutes: u8, seconds: u8, frames: u8, rate: FrameRate) -> Timecode {
+        Timecode { hours, minutes, seconds, frames, rate }
+    }
+
+    /// 80 ビットの LTC フレーム（ビット 0 が最初に送られる）
+    fn frame_bits(tc: &Timecode) -> u128 {
+        let field = |value: u8, start: u32| (value as u128) << start;
+        field(tc.frames % 10, 0)
+            | field(tc.frames / 10, 8)
+            | field((tc.rate == FrameRate::Fps2997Df) as u8, 10)
+            | field(tc.seconds % 10, 16)
+            | field(tc.seconds / 10, 24)
+            | field(tc.minutes % 10, 32)
+            | field(tc.minutes / 10, 40)
+            | field(tc.hours % 10, 48)
+            | field(tc.hours / 10, 56)
+            | (SYNC_WORD << 64)
+    }
+
+    /// タイムコードをバイフェーズマーク符号の信号にする
+    fn encode(timecodes: &[Timecode]) -> Vec<f32> {
+        let half_bit = SAMPLE_RATE as f64 / (timecodes[0].rate.fps() * 160.0);
+        let mut level = 0.5f32;
+        let mut halves = Vec::new();
+        for tc in timecodes {
+            let bits = frame_bits(tc);
+            for i in 0..80 {
+                // ビットの境界で必ず反転し、1 はビットの中央でも反転する
+                level = -level;
+                halves.push(level);
+                if (bits >> i) & 1 == 1 {
+                    level = -level;
+                }
+                halves.push(level);
+            }
+        }
+        // 最後のビットを確定させるため、次のビットの境界まで送る
+        level = -level;
+        halves.extend([level, level]);
+
+        let samples = (halves.len() as f64 * half_bit) as usize;
+        (0..samples)
+            .map(|n| halves[(((n as f64 + 0.5) / half_bit) as usize).min(halves.len() - 1)])
+            .collect()
+    }
+
+    fn decode(rate: FrameRate, samples: &[f32]) -> Vec<TimecodeReading> {
+        let mut decoder = LtcDecoder::new(SAMPLE_RATE, rate);
+        let mut readings = Vec::new();
+        decoder.feed(samples.iter().copied(), |reading| readings.push(reading));
+        readings
+    }
+
+    #[test]
+    fn decodes_25_fps() {
+        let frames: Vec<_> = (10..14).map(|f| timecode(10, 20, 30, f, FrameRate::Fps25)).collect();
+        let readings = decode(FrameRate::Fps25, &encode(&frames));
+
+        let decoded: Vec<_> = readings.iter().map(|r| r.timecode).collect();
+        assert_eq!(decoded, frames);
+        // 受信完了時には次のフレームが始まっている
+        let last = readings.last().unwrap();
+        assert!((last.seconds - (frames[3].to_seconds() + 1.0 / 25.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn decodes_2997_drop_frame() {
+        let frames: Vec<_> = (4..8).map(|f| timecode(1, 2, 3, f, FrameRate::Fps2997Df)).collect();
+        // ドロップフレームは信号のフラグで判定する（30 fps として開いても 29.97 DF になる）
+        let readings = decode(FrameRate::Fps30, &encode(&frames));
+
+        let decoded: Vec<_> = readings.iter().map(|r| r.timecode).collect();
+        assert_eq!(decoded, frames);
+        assert_eq!(decoded[0].to_string(), "01:02:03;04");
+    }
+
+    #[test]
+    fn non_drop_signal_at_2997_is_30_fps() {
+        let frames: Vec<_> = (0..3).map(|f| timecode(0, 0, 1, f, FrameRate::Fps30)).collect();
+        let readings = decode(FrameRate::Fps2997Df, &encode(&frames));
+
+        assert_eq!(readings.len(), 3);
+        assert!(readings.iter().all(|r| r.timecode.rate == FrameRate::Fps30));
+    }
+
+    #[test]
+    fn ignores_silence_and_noise() {
+        let mut samples = vec![0.0f32; 4800];
+        // ヒステリシス未満の揺れは交差とみなさない
+        samples.extend((0..4800).map(|n| if n % 2 == 0 { 0.01 } else { -0.01 }));
+        assert!(decode(FrameRate::Fps25, &samples).is_empty());
+    }
+
+    #[test]
+    fn resyncs_after_a_dropout() {
+        let first: Vec<_> = (0..2).map(|f| timecode(0, 0, 0, f, FrameRate::Fps25)).collect();
+        let second: Vec<_> = (0..2).map(|f| timecode(0, 0, 5, f, FrameRate::Fps25)).collect();
+        let mut samples = encode(&first);
+        samples.extend(vec![0.0f32; 2400]);
+        samples.extend(encode(&second));
+
+        let decoded: Vec<_> = decode(FrameRate::Fps25, &samples).iter().map(|r| r.timecode).collect();
+        assert_eq!(&decoded[decoded.len() - 2..], &second[..]);
+        assert_eq!(&decoded[..2], &first[..]);
+    }
+}
@@ -0,0 +1,162 @@
+/// 外部タイムコード（MTC / LTC）の受信
+///
+/// 照明卓などが送るタイムコードを受け取り、`TimecodeReading` としてチャンネルに流す。
+/// 再生位置を追従させる処理（チェイス）は player::chase が行う。
+///
+/// - MTC: MIDI 入力ポートのクォーターフレーム / フルフレームメッセージ（mtc.rs）
+/// - LTC: オーディオ入力のバイフェーズ信号（ltc.rs）
+///
+/// 受信スレッドは stop フラグが立つまで入力を開いたままにする
+/// （cpal のストリームはスレッドをまたげないため、開いたスレッドで保持する）。
+pub mod ltc;
+pub mod mtc;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// タイムコードのフレームレート
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FrameRate {
+    #[serde(rename = "24")]
+    Fps24,
+    #[serde(rename = "25")]
+    Fps25,
+    /// 29.97 fps ドロップフレーム
+    #[serde(rename = "29.97df")]
+    Fps2997Df,
+    #[default]
+    #[serde(rename = "30")]
+    Fps30,
+}
+
+impl FrameRate {
+    /// 1 秒あたりのフレーム数（ドロップフレームは実際のレート）
+    pub fn fps(self) -> f64 {
+        match self {
+            FrameRate::Fps24 => 24.0,
+            FrameRate::Fps25 => 25.0,
+            FrameRate::Fps2997Df => 30000.0 / 1001.0,
+            FrameRate::Fps30 => 30.0,
+        }
+    }
+
+    /// フレーム番号の上限（1 秒あたりのラベル数）
+    pub fn nominal(self) -> u32 {
+        match self {
+            FrameRate::Fps24 => 24,
+            FrameRate::Fps25 => 25,
+            FrameRate::Fps2997Df | FrameRate::Fps30 => 30,
+        }
+    }
+
+    /// MTC のレートコード（0–3）から変換する
+    pub fn from_mtc(code: u8) -> Self {
+        match code & 0x03 {
+            0 => FrameRate::Fps24,
+            1 => FrameRate::Fps25,
+            2 => FrameRate::Fps2997Df,
+            _ => FrameRate::Fps30,
+        }
+    }
+}
+
+/// 時・分・秒・フレームのタイムコード
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Timecode {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub frames: u8,
+    pub rate: FrameRate,
+}
+
+impl Timecode {
+    /// 値が範囲内か（壊れたメッセージを捨てるために使う）
+    pub fn is_valid(&self) -> bool {
+        self.hours < 24
+            && self.minutes < 60
+            && self.seconds < 60
+            && (self.frames as u32) < self.rate.nominal()
+    }
+
+    /// 00:00:00:00 からの経過秒数
+    pub fn to_seconds(&self) -> f64 {
+        let total_seconds = self.hours as u64 * 3600 + self.minutes as u64 * 60 + self.seconds as u64;
+        let mut frame_number = total_seconds * self.rate.nominal() as u64 + self.frames as u64;
+        if self.rate == FrameRate::Fps2997Df {
+            // 10 の倍数以外の分の頭でフレーム番号 0, 1 が飛ばされる
+            let total_minutes = self.hours as u64 * 60 + self.minutes as u64;
+            frame_number -= 2 * (total_minutes - total_minutes / 10);
+        }
+        frame_number as f64 / self.rate.fps()
+    }
+}
+
+impl std::fmt::Display for Timecode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let separator = if self.rate == FrameRate::Fps2997Df { ';' } else { ':' };
+        write!(
+            f,
+            "{:02}:{:02}:{:02}{}{:02}",
+            self.hours, self.minutes, self.seconds, separator, self.frames
+        )
+    }
+}
+
+/// 受信したタイムコード
+#[derive(Debug, Clone, Copy)]
+pub struct TimecodeReading {
+    pub timecode: Timecode,
+    /// 受信した瞬間の位置（秒）。メッセージの伝送にかかるフレーム分を補正済み
+    pub seconds: f64,
+    pub received: Instant,
+}
+
+impl TimecodeReading {
+    /// タイムコードの完了から lead_frames フレーム進んだ位置として記録する
+    pub fn new(timecode: Timecode, lead_frames: u32) -> Self {
+        Self {
+            timecode,
+            seconds: timecode.to_seconds() + lead_frames as f64 / timecode.rate.fps(),
+            received: Instant::now(),
+        }
+    }
+}
+
+/// タイムコードの入力元
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum TimecodeSource {
+    /// MIDI タイムコード（port は入力ポート名。部分一致）
+    Mtc { port: String },
+    /// オーディオ入力の LTC（device を省略すると既定の入力、channel は 0 始まり）
+    Ltc {
+        #[serde(default)]
+        device: Option<String>,
+        #[serde(default)]
+        channel: u16,
+        /// LTC には 29.97 以外のレートが含まれないため指定する
+        #[serde(default)]
+        rate: FrameRate,
+    },
+}
+
+/// 入力元を開き、受信したタイムコードを tx に送るスレッドを起動する
+///
+/// 入力を開けなかった場合はエラーを返す（スレッドは残らない）。
+pub fn spawn_source(source: &TimecodeSource, tx: Sender<TimecodeReading>, stop: Arc<AtomicBool>) -> Result<()> {
+    match source {
+        TimecodeSource::Mtc { port } => mtc::spawn(port, tx, stop),
+        TimecodeSource::Ltc { device, channel, rate } => ltc::spawn(device.as_deref(), *channel, *rate, tx, stop),
+    }
+}
+
+/// 受信スレッドが入力を開いた結果を待つ
+fn wait_opened(rx: std::sync::mpsc::Receiver<Result<()>>) -> Result<()> {
+    rx.recv()
+        .map_err(|_| anyhow::anyhow!("タイムコードの受信スレッドが終了しました"))?
+}
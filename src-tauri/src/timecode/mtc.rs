@@ -0,0 +1,263 @@
+/// MIDI タイムコード（MTC）の受信
+///
+/// ## クォーターフレーム（F1 xx）
+/// 1 フレームの 1/4 ごとに 8 個のメッセージでタイムコードを分けて送る。
+/// 8 個揃った時点で送信側は 2 フレーム進んでいるため、その分を補正する。
+///
+/// ## フルフレーム（F0 7F 7F 01 01 hh mm ss ff F7）
+/// 停止中の位置合わせ・ロケート時に送られる。補正なしでそのまま使う。
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{FrameRate, Timecode, TimecodeReading};
+use crate::error::AppError;
+
+/// クォーターフレーム 8 個分の遅れ（フレーム）
+const QUARTER_FRAME_LEAD: u32 = 2;
+
+/// MTC メッセージを組み立てる
+#[derive(Debug, Default)]
+pub struct MtcParser {
+    pieces: [u8; 8],
+    /// 受け取ったクォーターフレームの番号（ビットマスク）
+    received: u8,
+}
+
+impl MtcParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// MIDI メッセージ 1 つを読み、タイムコードが揃えば返す
+    pub fn feed(&mut self, message: &[u8]) -> Option<TimecodeReading> {
+        match message {
+            [0xF1, data, ..] => self.quarter_frame(*data),
+            [0xF0, 0x7F, _, 0x01, 0x01, hh, mm, ss, ff, ..] => {
+                // 位置が飛んだのでクォーターフレームを集め直す
+                self.received = 0;
+                let timecode = Timecode {
+                    hours: hh & 0x1F,
+                    minutes: *mm,
+                    seconds: *ss,
+                    frames: *ff,
+                    rate: FrameRate::from_mtc(hh >> 5),
+                };
+                timecode.is_valid().then(|| TimecodeReading::new(timecode, 0))
+            }
+            _ => None,
+        }
+    }
+
+    fn quarter_frame(&mut self, data: u8) -> Option<TimecodeReading> {
+        let piece = (data >> 4) & 0x07;
+        self.pieces[piece as usize] = data & 0x0F;
+        self.received |= 1 << piece;
+        if piece != 7 || self.received != 0xFF {
+            return None;
+        }
+        self.received = 0;
+
+        let p = &self.pieces;
+        let timecode = Timecode {
+            frames: p[0] | ((p[1] & 0x01) << 4),
+            seconds: p[2] | ((p[3] & 0x03) << 4),
+            minutes: p[4] | ((p[5] & 0x03) << 4),
+            hours: p[6] | ((p[7] & 0x01) << 4),
+            rate: FrameRate::from_mtc(p[7] >> 1),
+        };
+        timecode.is_valid().then(|| TimecodeReading::new(timecode, QUARTER_FRAME_LEAD))
+    }
+}
+
+/// MIDI 入力ポートの名前一覧
+pub fn list_ports() -> Result<Vec<String>> {
+    let input = midir::MidiInput::new("yt-spout-syphon-bridge")
+        .map_err(|e| anyhow::anyhow!("MIDI を初期化できません: {}", e))?;
+    Ok(input
+        .ports()
+        .iter()
+        .filter_map(|port| input.port_name(port).ok())
+        .collect())
+}
+
+/// 名前に port を含む MIDI 入力を開き、MTC を受信するスレッドを起動する
+pub fn spawn(port: &str, tx: Sender<TimecodeReading>, stop: Arc<AtomicBool>) -> Result<()> {
+    let port_name = port.to_string();
+    let (opened_tx, opened_rx) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        let connection = match connect(&port_name, tx) {
+            Ok(connection) => {
+                let _ = opened_tx.send(Ok(()));
+                connection
+            }
+            Err(e) => {
+                let _ = opened_tx.send(Err(e));
+                return;
+            }
+        };
+        log::info!("MTC の受信を開始しました");
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        connection.close();
+        log::info!("MTC の受信を終了しました");
+    });
+
+    super::wait_opened(opened_rx)
+}
+
+fn connect(port_name: &str, tx: Sender<TimecodeReading>) -> Result<midir::MidiInputConnection<()>> {
+    let mut input = midir::MidiInput::new("yt-spout-syphon-bridge")
+        .map_err(|e| anyhow::anyhow!("MIDI を初期化できません: {}", e))?;
+    // MTC のためにシステムメッセージ（SysEx・タイミング）を受け取る
+    input.ignore(midir::Ignore::ActiveSense);
+    let port = input
+        .ports()
+        .into_iter()
+        .find(|p| input.port_name(p).is_ok_and(|name| name.contains(port_name)))
+        .ok_or_else(|| AppError::DeviceNotFound(format!("MIDI 入力: {}", port_name)))?;
+
+    let mut parser = MtcParser::new();
+    input
+        .connect(&port, "mtc", move |_stamp, message, _| {
+            if let Some(reading) = parser.feed(message) {
+                let _ = tx.send(reading);
+            }
+        }, ())
+        .map_err(|e| anyhow::anyhow!("MIDI 入力を開けません ({}): {}", port_name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timecode(hours: u8, minutes: u8, seconds: u8, frames: u8, rate: FrameRate) -> Timecode {
+        Timecode { hours, minutes, seconds, frames, rate }
+    }
+
+    fn rate_code(rate: FrameRate) -> u8 {
+        match rate {
+            FrameRate::Fps24 => 0,
+            FrameRate::Fps25 => 1,
+            FrameRate::Fps2997Df => 2,
+            FrameRate::Fps30 => 3,
+        }
+    }
+
+    /// クォーターフレーム 8 個（F1 0n … F1 7n）
+    fn quarter_frames(tc: &Timecode) -> Vec<[u8; 2]> {
+        let pieces = [
+            tc.frames & 0x0F,
+            tc.frames >> 4,
+            tc.seconds & 0x0F,
+            tc.seconds >> 4,
+            tc.minutes & 0x0F,
+            tc.minutes >> 4,
+            tc.hours & 0x0F,
+            (tc.hours >> 4) | (rate_code(tc.rate) << 1),
+        ];
+        pieces.iter().enumerate().map(|(i, piece)| [0xF1, ((i as u8) << 4) | piece]).collect()
+    }
+
+    fn full_frame(tc: &Timecode) -> [u8; 10] {
+        let hh = (rate_code(tc.rate) << 5) | tc.hours;
+        [0xF0, 0x7F, 0x7F, 0x01, 0x01, hh, tc.minutes, tc.seconds, tc.frames, 0xF7]
+    }
+
+    #[test]
+    fn assembles_eight_quarter_frames() {
+        let tc = timecode(1, 2, 3, 20, FrameRate::Fps25);
+        let mut parser = MtcParser::new();
+        let messages = quarter_frames(&tc);
+
+        for message in &messages[..7] {
+            assert!(parser.feed(message).is_none());
+        }
+        let reading = parser.feed(&messages[7]).unwrap();
+        assert_eq!(reading.timecode, tc);
+        // 8 個揃った時点で 2 フレーム進んでいる
+        assert!((reading.seconds - (tc.to_seconds() + 2.0 / 25.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn decodes_each_rate_from_quarter_frames() {
+        for rate in [FrameRate::Fps24, FrameRate::Fps25, FrameRate::Fps2997Df, FrameRate::Fps30] {
+            let tc = timecode(23, 59, 58, 17, rate);
+            let mut parser = MtcParser::new();
+            let reading = quarter_frames(&tc).iter().filter_map(|m| parser.feed(m)).last().unwrap();
+            assert_eq!(reading.timecode, tc);
+        }
+    }
+
+    #[test]
+    fn waits_for_all_pieces_when_joining_mid_stream() {
+        let first = timecode(0, 0, 10, 0, FrameRate::Fps30);
+        let second = timecode(0, 0, 10, 2, FrameRate::Fps30);
+        let mut parser = MtcParser::new();
+
+        // 途中（ピース 4）から受信し始めたフレームは使わない
+        for message in &quarter_frames(&first)[4..] {
+            assert!(parser.feed(message).is_none());
+        }
+        let readings: Vec<_> = quarter_frames(&second).iter().filter_map(|m| parser.feed(m)).collect();
+        assert_eq!(readings.len(), 1);
+        assert_eq!(readings[0].timecode, second);
+    }
+
+    #[test]
+    fn full_frame_is_used_without_lead() {
+        let tc = timecode(10, 0, 0, 0, FrameRate::Fps2997Df);
+        let reading = MtcParser::new().feed(&full_frame(&tc)).unwrap();
+        assert_eq!(reading.timecode, tc);
+        assert!((reading.seconds - tc.to_seconds()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn full_frame_restarts_quarter_frame_collection() {
+        let tc = timecode(0, 1, 0, 0, FrameRate::Fps25);
+        let mut parser = MtcParser::new();
+        let messages = quarter_frames(&tc);
+        for message in &messages[..6] {
+            parser.feed(message);
+        }
+        assert!(parser.feed(&full_frame(&tc)).is_some());
+        // ロケート前のピースとは組み合わせない
+        assert!(parser.feed(&messages[6]).is_none());
+        assert!(parser.feed(&messages[7]).is_none());
+    }
+
+    #[test]
+    fn invalid_sysex_is_ignored() {
+        let tc = timecode(1, 0, 0, 0, FrameRate::Fps25);
+        let mut parser = MtcParser::new();
+
+        // ユーザービット（sub-id 02）など、フルフレーム以外の MTC SysEx
+        let mut user_bits = full_frame(&tc);
+        user_bits[4] = 0x02;
+        assert!(parser.feed(&user_bits).is_none());
+
+        // 非リアルタイム（7E）の SysEx
+        let mut non_realtime = full_frame(&tc);
+        non_realtime[1] = 0x7E;
+        assert!(parser.feed(&non_realtime).is_none());
+
+        // 途中で切れたメッセージ
+        assert!(parser.feed(&full_frame(&tc)[..7]).is_none());
+
+        // 範囲外の値
+        let mut out_of_range = full_frame(&tc);
+        out_of_range[6] = 75;
+        assert!(parser.feed(&out_of_range).is_none());
+        let mut bad_frame = full_frame(&tc);
+        bad_frame[8] = 25;
+        assert!(parser.feed(&bad_frame).is_none());
+
+        // MTC 以外のメッセージ
+        assert!(parser.feed(&[0x90, 0x3C, 0x7F]).is_none());
+        assert!(parser.feed(&[]).is_none());
+    }
+}
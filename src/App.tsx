@@ -11,10 +11,14 @@ import PlayerControls from "./components/PlayerControls";
 const HEIGHT_WITH_PREVIEW = 532;
 const HEIGHT_WITHOUT_PREVIEW = 332;
 
+// 再生セッションが生きている（コントロールを表示する）ステータス
+const ACTIVE_STATUSES = ["playing", "paused", "buffering", "seeking", "stalled"];
+
 export default function App() {
   const { status, play, stop, pause, setAudioDevice, setVolume } = usePlayer();
   const [url, setUrl] = useState("");
   const [previewVisible, setPreviewVisible] = useState(true);
+  const isActive = ACTIVE_STATUSES.includes(status.status);
 
   const handlePlay = () => {
     if (url.trim()) {
@@ -40,7 +44,7 @@ export default function App() {
           onSubmit={handlePlay}
           disabled={false}
           isLoading={status.status === "loading"}
-          isPlaying={status.status === "playing" || status.status === "paused"}
+          isPlaying={isActive}
         />
 
         {/* プレビュー */}
@@ -48,7 +52,7 @@ export default function App() {
 
         {/* プレイヤーコントロール */}
         <PlayerControls
-          isPlaying={status.status === "playing" || status.status === "paused"}
+          isPlaying={isActive}
           onPause={pause}
           onStop={stop}
         />
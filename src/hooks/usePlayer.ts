@@ -4,10 +4,44 @@ import { listen } from "@tauri-apps/api/event";
 
 // ─── 型定義 ──────────────────────────────────────────────────────────────────
 
+/** バックエンドが返す型付きエラー（code で判別、message は既定の日本語） */
+export interface IpcError {
+  code:
+    | "not_playing"
+    | "invalid_url"
+    | "extractor_failed"
+    | "network_failed"
+    | "decoder_failed"
+    | "output_unavailable"
+    | "device_not_found"
+    | "invalid_argument"
+    | "internal";
+  message: string;
+  detail?: string | null;
+}
+
+/** invoke の reject 値を IpcError に正規化する */
+export function toIpcError(err: unknown): IpcError {
+  if (err && typeof err === "object" && "code" in err) {
+    return err as IpcError;
+  }
+  return { code: "internal", message: String(err) };
+}
+
 export interface PlayerStatus {
-  status: "idle" | "loading" | "playing" | "paused" | "error";
+  status:
+    | "idle"
+    | "loading"
+    | "playing"
+    | "paused"
+    | "buffering"
+    | "seeking"
+    | "ended"
+    | "stalled"
+    | "error";
   url?: string;
-  error?: string;
+  error?: IpcError | null;
+  buffering?: number | null;
   spout_active: boolean;
   syphon_active: boolean;
 }
@@ -63,7 +97,7 @@ export function usePlayer() {
       setStatus((prev) => ({
         ...prev,
         status: "error",
-        error: String(err),
+        error: toIpcError(err),
       }));
     }
   }, []);